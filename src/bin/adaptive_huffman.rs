@@ -0,0 +1,63 @@
+use algorithms::compression::adaptive_huffman::AdaptiveHuffman;
+use algorithms::compression::huffman::Huffman;
+use rand::Rng;
+
+fn main() {
+    // A log stream arriving line by line: the encoder and the decoder each keep
+    // their own model and only ever see the lines in order.
+    let levels = ["INFO", "WARN", "ERROR", "DEBUG"];
+    let services = ["auth", "billing", "search", "gateway"];
+
+    let lines:Vec<String> = (0..5000).map(|i| {
+        let mut rng = rand::thread_rng();
+        format!("2024-03-{:02} 12:{:02}:{:02} [{}] {}: request {} served in {}ms\n",
+                1 + i/200, rng.gen_range(0..60), rng.gen_range(0..60),
+                levels[rng.gen_range(0..levels.len())], services[rng.gen_range(0..services.len())],
+                rng.gen_range(0..100000), rng.gen_range(1..500))
+    }).collect();
+
+    let mut encoder = AdaptiveHuffman::new();
+    let mut decoder = AdaptiveHuffman::new();
+    let mut original_size:usize = 0;
+    let mut adaptive_size:usize = 0;
+    let mut static_size:usize = 0;
+
+    for line in lines.iter() {
+        let encoded = encoder.encode(line.as_bytes());
+        let decoded = decoder.decode(&encoded).unwrap();
+        assert_eq!(decoded, line.as_bytes());
+
+        original_size += line.len();
+        adaptive_size += encoded.len();
+        static_size += Huffman::compress(line.as_bytes()).len();
+    }
+
+    println!("Log stream: {:?} bytes, adaptive per line = {:?} bytes, static per line = {:?} bytes", original_size, adaptive_size, static_size);
+    assert!(adaptive_size < static_size);
+
+    // One-shot use is a drop-in for the static codec.
+    let all:Vec<u8> = lines.concat().into_bytes();
+    let adaptive = AdaptiveHuffman::compress(&all);
+    let fixed = Huffman::compress(&all);
+    assert_eq!(AdaptiveHuffman::decompress(&adaptive).unwrap(), all);
+    println!("Whole log: adaptive = {:?} bytes, static = {:?} bytes", adaptive.len(), fixed.len());
+
+    let random:Vec<u8> = (0..100000).map(|_| rand::thread_rng().gen_range(0..16u8)*rand::thread_rng().gen_range(0..16u8)).collect();
+    let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let all_bytes:Vec<u8> = (0..=255u8).cycle().take(5000).collect();
+
+    for data in [random, exe, all_bytes, vec![], vec![7u8; 1000]] {
+        let compressed = AdaptiveHuffman::compress(&data);
+        assert_eq!(AdaptiveHuffman::decompress(&compressed).unwrap(), data);
+        println!("{:?} bytes -> {:?} bytes", data.len(), compressed.len());
+    }
+
+    // A decoder that skipped a block is out of sync with the encoder.
+    let mut encoder = AdaptiveHuffman::new();
+    let first = encoder.encode(b"hello world");
+    let second = encoder.encode(b"hello again");
+    assert_ne!(AdaptiveHuffman::decompress(&second).ok(), Some(b"hello again".to_vec()));
+    let mut decoder = AdaptiveHuffman::new();
+    assert_eq!(decoder.decode(&first).unwrap(), b"hello world");
+    assert_eq!(decoder.decode(&second).unwrap(), b"hello again");
+}
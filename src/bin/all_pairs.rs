@@ -0,0 +1,209 @@
+use algorithms::graph::all_pairs::{floyd_warshall, johnson, AllPairs};
+use algorithms::graph::shortest_path::bellman_ford;
+use algorithms::graph::Graph;
+use rand::Rng;
+
+fn get_cycle_weight<N>(graph:&Graph<N, i64>, cycle:&[usize]) -> i64 {
+    (0..cycle.len()).map(|i| {
+        let (u, v) = (cycle[i], cycle[(i+1) % cycle.len()]);
+        graph.neighbors(u).filter(|(x, _)| *x == v).map(|(_, w)| *w).min().unwrap()
+    }).sum()
+}
+
+fn get_path_length(graph:&Graph<usize, i64>, path:&[usize]) -> i64 {
+    path.windows(2).map(|p| graph.neighbors(p[0]).filter(|(v, _)| *v == p[1]).map(|(_, w)| *w).min().unwrap()).sum()
+}
+
+fn print_matrix(graph:&Graph<&str, i64>, all_pairs:&AllPairs<i64>) {
+    print!("{:>6}", "");
+    for v in graph.keys().iter() {
+        print!("{:>6}", v);
+    }
+    println!();
+
+    for (u, row) in all_pairs.distances().iter().enumerate() {
+        print!("{:>6}", graph.key(u));
+        for d in row.iter() {
+            match d {
+                Some(x) => print!("{:>6}", x),
+                None => print!("{:>6}", "-"),
+            }
+        }
+        println!();
+    }
+}
+
+fn main() {
+    // Shipping costs between warehouses, a negative cost is a subsidised lane.
+    let mut network:Graph<&str, i64> = Graph::directed();
+    network.add_edge("Oslo", "Bonn", 8);
+    network.add_edge("Oslo", "Lyon", 12);
+    network.add_edge("Bonn", "Lyon", 3);
+    network.add_edge("Bonn", "Pisa", 9);
+    network.add_edge("Lyon", "Pisa", -2);
+    network.add_edge("Pisa", "Oslo", 15);
+    network.add_edge("Pisa", "Riga", 4);
+    network.add_node("Kyiv");
+
+    let by_floyd = floyd_warshall(&network).unwrap();
+    let by_johnson = johnson(&network).unwrap();
+    print_matrix(&network, &by_floyd);
+
+    assert_eq!(by_floyd.distances(), by_johnson.distances());
+    let (oslo, riga) = (network.id("Oslo").unwrap(), network.id("Riga").unwrap());
+    assert_eq!(by_floyd.distance(oslo, riga), Some(13));
+
+    let route:Vec<&str> = by_johnson.path(oslo, riga).unwrap().into_iter().map(|u| *network.key(u)).collect();
+    println!("Oslo -> Riga = {:?}", route);
+    assert_eq!(route, vec!["Oslo", "Bonn", "Lyon", "Pisa", "Riga"]);
+    assert_eq!(by_floyd.path(oslo, riga), by_johnson.path(oslo, riga));
+    assert_eq!(by_floyd.path(riga, oslo), None);
+
+    // A subsidy large enough to make a round trip pay.
+    network.add_edge("Riga", "Bonn", -20);
+    let cycle = floyd_warshall(&network).unwrap_err();
+    let names:Vec<&str> = cycle.iter().map(|u| *network.key(*u)).collect();
+    println!("Negative cycle = {:?}", names);
+    assert!(get_cycle_weight(&network, &cycle) < 0);
+    assert!(get_cycle_weight(&network, &johnson(&network).unwrap_err()) < 0);
+
+    // Random graphs against Bellman-Ford from every node.
+    let mut rng = rand::thread_rng();
+    let mut with_cycles:usize = 0;
+
+    for _ in 0..300 {
+        let n = rng.gen_range(1..25);
+        let mut graph:Graph<usize, i64> = Graph::directed();
+        for u in 0..n {
+            graph.add_node(u);
+        }
+        for _ in 0..rng.gen_range(0..3*n) {
+            graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-5..40));
+        }
+
+        let singles:Vec<_> = (0..n).map(|u| bellman_ford(&graph, u)).collect();
+        let has_cycle = singles.iter().any(|(_, c)| c.is_some());
+
+        match (floyd_warshall(&graph), johnson(&graph)) {
+            (Ok(a), Ok(b)) => {
+                assert!(!has_cycle);
+                for (u, (paths, _)) in singles.iter().enumerate() {
+                    assert_eq!(a.distances()[u], paths.distances());
+                    assert_eq!(b.distances()[u], paths.distances());
+
+                    for v in 0..n {
+                        for all_pairs in [&a, &b] {
+                            if let Some(path) = all_pairs.path(u, v) {
+                                assert_eq!((path[0], *path.last().unwrap()), (u, v));
+                                assert_eq!(Some(get_path_length(&graph, &path)), all_pairs.distance(u, v));
+                            }
+                        }
+                    }
+                }
+            }
+            (Err(a), Err(b)) => {
+                assert!(has_cycle);
+                with_cycles += 1;
+                assert!(get_cycle_weight(&graph, &a) < 0);
+                assert!(get_cycle_weight(&graph, &b) < 0);
+            }
+            _ => panic!("Floyd-Warshall and Johnson disagree on negative cycles"),
+        }
+    }
+    println!("{} of 300 random graphs had a negative cycle", with_cycles);
+
+    // Negative cycles whose distances leave the range of the weight type.
+    let mut small:Graph<usize, i8> = Graph::directed();
+    let mut huge:Graph<usize, i64> = Graph::directed();
+    for (u, v) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+        small.add_edge(u, v, -100);
+        huge.add_edge(u, v, i64::MIN/2);
+    }
+    for mut cycle in [floyd_warshall(&small).unwrap_err(), johnson(&small).unwrap_err(),
+                      floyd_warshall(&huge).unwrap_err(), johnson(&huge).unwrap_err()] {
+        cycle.sort();
+        assert_eq!(cycle, vec![0, 1, 2]);
+    }
+
+    // A path in range through a pair out of range, with and without a
+    // negative cycle elsewhere.
+    let mut graph:Graph<u32, i64> = Graph::directed();
+    for u in 0..4 {
+        graph.add_node(u);
+    }
+    graph.add_edge(2, 3, i64::MAX);
+    graph.add_edge(3, 0, 10);
+    graph.add_edge(0, 1, -100);
+    for all_pairs in [floyd_warshall(&graph).unwrap(), johnson(&graph).unwrap()] {
+        assert_eq!(all_pairs.distance(2, 1), Some(i64::MAX-90));
+        assert_eq!(all_pairs.path(2, 1), Some(vec![2, 3, 0, 1]));
+        assert_eq!(all_pairs.overflowed(), &[(2, 0)]);
+    }
+
+    let mut small:Graph<u32, i8> = Graph::directed();
+    for u in 0..4 {
+        small.add_node(u);
+    }
+    for (u, v, w) in [(0, 1, -96), (3, 0, 43), (2, 3, 127), (3, 2, -21)] {
+        small.add_edge(u, v, w);
+    }
+    for all_pairs in [floyd_warshall(&small).unwrap(), johnson(&small).unwrap()] {
+        assert_eq!(all_pairs.path(2, 1), Some(vec![2, 3, 0, 1]));
+        assert_eq!(all_pairs.distance(2, 1), Some(74));
+    }
+
+    let mut small:Graph<u32, i8> = Graph::directed();
+    for u in 0..6 {
+        small.add_node(u);
+    }
+    for (u, v, w) in [(3, 5, -86), (4, 2, 33), (0, 0, 8), (0, 5, 35), (5, 4, 96), (4, 1, -62),
+                      (0, 1, 9), (3, 3, 27), (0, 0, 122), (2, 3, -122), (0, 3, 3), (0, 1, 31)] {
+        small.add_edge(u, v, w);
+    }
+    for mut cycle in [floyd_warshall(&small).unwrap_err(), johnson(&small).unwrap_err()] {
+        cycle.sort();
+        assert_eq!(cycle, vec![2, 3, 4, 5]);
+    }
+
+    // Small weights against the exact distances in a wider type. Pairs out of
+    // range must be reported, never clamped.
+    for _ in 0..2000 {
+        let n = rng.gen_range(1..8);
+        let mut small:Graph<usize, i8> = Graph::directed();
+        let mut wide:Graph<usize, i64> = Graph::directed();
+        for u in 0..n {
+            small.add_node(u);
+            wide.add_node(u);
+        }
+        for _ in 0..rng.gen_range(0..3*n) {
+            let (u, v, w) = (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-60..=127));
+            small.add_edge_ids(u, v, w);
+            wide.add_edge_ids(u, v, w as i64);
+        }
+
+        let exact = floyd_warshall(&wide);
+        for result in [floyd_warshall(&small), johnson(&small)] {
+            match (result, &exact) {
+                (Ok(a), Ok(b)) => {
+                    for u in 0..n {
+                        for v in 0..n {
+                            let d = b.distance(u, v);
+                            assert_eq!(a.distance(u, v).map(|x| x as i64), d.filter(|x| i8::try_from(*x).is_ok()));
+                            assert_eq!(a.overflowed().contains(&(u, v)), d.is_some_and(|x| i8::try_from(x).is_err()));
+
+                            let path = a.path(u, v);
+                            assert_eq!(path.is_some(), d.is_some());
+                            if let Some(path) = path {
+                                assert_eq!((path[0], *path.last().unwrap()), (u, v));
+                                assert_eq!(Some(get_path_length(&wide, &path)), d);
+                            }
+                        }
+                    }
+                }
+                (Err(cycle), Err(_)) => assert!(get_cycle_weight(&wide, &cycle) < 0),
+                (Ok(_), Err(_)) => panic!("negative cycle missed"),
+                (Err(_), Ok(_)) => panic!("negative cycle reported where there is none"),
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use algorithms::graph::search::{astar, bidirectional_dijkstra};
+use algorithms::graph::shortest_path::dijkstra;
+use algorithms::graph::Graph;
+use rand::Rng;
+
+// Length of a path using the lightest edge between consecutive nodes.
+fn get_path_length(graph:&Graph<usize, u64>, path:&[usize]) -> u64 {
+    path.windows(2).map(|p| graph.neighbors(p[0]).filter(|(v, _)| *v == p[1]).map(|(_, w)| *w).min().unwrap()).sum()
+}
+
+fn main() {
+    // A city grid with blocked crossings, every street costs 1 to 9 minutes.
+    let size:i32 = 60;
+    let mut rng = rand::thread_rng();
+    let mut city:Graph<(i32, i32), u64> = Graph::undirected();
+
+    for x in 0..size {
+        for y in 0..size {
+            city.add_node((x, y));
+        }
+    }
+    for x in 0..size {
+        for y in 0..size {
+            if rng.gen_bool(0.15) && (x, y) != (0, 0) && (x, y) != (size-1, size-1) {
+                continue;
+            }
+            if x+1 < size {
+                city.add_edge((x, y), (x+1, y), rng.gen_range(1..10));
+            }
+            if y+1 < size {
+                city.add_edge((x, y), (x, y+1), rng.gen_range(1..10));
+            }
+        }
+    }
+
+    let src = city.id(&(0, 0)).unwrap();
+    let target = city.id(&(size-1, size-1)).unwrap();
+
+    // Every street costs at least 1, so the Manhattan distance never overestimates.
+    let manhattan = |u:usize| {
+        let (x, y) = *city.key(u);
+        ((size-1-x) + (size-1-y)) as u64
+    };
+
+    let expected = dijkstra(&city, src).distance(target);
+    let by_astar = astar(&city, src, target, manhattan);
+    let by_bidirectional = bidirectional_dijkstra(&city, src, target);
+
+    assert_eq!(by_astar.as_ref().map(|r| r.0), expected);
+    assert_eq!(by_bidirectional.as_ref().map(|r| r.0), expected);
+    if let Some((minutes, route)) = by_astar {
+        println!("Crossing the city takes {} minutes over {} crossings", minutes, route.len());
+        assert_eq!(route[0], src);
+        assert_eq!(*route.last().unwrap(), target);
+    }
+
+    // Random directed graphs against plain Dijkstra.
+    for _ in 0..300 {
+        let n = rng.gen_range(1..50);
+        let mut graph:Graph<usize, u64> = Graph::directed();
+        let mut reversed:Graph<usize, u64> = Graph::directed();
+        for u in 0..n {
+            graph.add_node(u);
+            reversed.add_node(u);
+        }
+        for _ in 0..rng.gen_range(0..4*n) {
+            let (u, v, w) = (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..50));
+            graph.add_edge_ids(u, v, w);
+            reversed.add_edge_ids(v, u, w);
+        }
+
+        let (src, target) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        let expected = dijkstra(&graph, src).distance(target);
+
+        // Half the true remaining distance is admissible but not exact.
+        let remaining = dijkstra(&reversed, target);
+        let half = |u:usize| remaining.distance(u).unwrap_or(0)/2;
+
+        let results = [
+            astar(&graph, src, target, |_| 0),
+            astar(&graph, src, target, half),
+            astar(&graph, src, target, |u| remaining.distance(u).unwrap_or(0)),
+            bidirectional_dijkstra(&graph, src, target),
+        ];
+
+        for result in results.iter() {
+            assert_eq!(result.as_ref().map(|r| r.0), expected);
+            if let Some((dist, path)) = result {
+                assert_eq!((path[0], *path.last().unwrap()), (src, target));
+                assert_eq!(get_path_length(&graph, path), *dist);
+            }
+        }
+    }
+
+    // Undirected graphs take the same edges in both directions.
+    for _ in 0..300 {
+        let n = rng.gen_range(1..50);
+        let mut graph:Graph<usize, u64> = Graph::undirected();
+        for u in 0..n {
+            graph.add_node(u);
+        }
+        for _ in 0..rng.gen_range(0..2*n) {
+            graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..50));
+        }
+
+        let (src, target) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        let result = bidirectional_dijkstra(&graph, src, target);
+        assert_eq!(result.as_ref().map(|r| r.0), dijkstra(&graph, src).distance(target));
+        if let Some((dist, path)) = result {
+            assert_eq!(get_path_length(&graph, &path), dist);
+        }
+    }
+    println!("A* and bidirectional Dijkstra agree with Dijkstra on 600 random graphs");
+}
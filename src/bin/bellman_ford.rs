@@ -1,142 +1,235 @@
 use std::collections::HashMap;
-use std::cmp::Ordering;
-use std::isize::MAX;
-
-struct Graph {
-    nodes: Vec<Box<Node>>,
-    edges: Vec<Box<Edge>>,
-}
-
-#[derive(Eq, PartialEq, Clone, Hash)]
-struct Node {
-    key: String,
+use algorithms::graph::shortest_path::{bellman_ford, spfa, NegativeCycle};
+use algorithms::graph::Graph;
+use rand::Rng;
+
+// Cycle weight using the lightest edge between consecutive nodes.
+fn get_cycle_weight(graph:&Graph<usize, i64>, cycle:&[usize]) -> i64 {
+    (0..cycle.len()).map(|i| {
+        let (u, v) = (cycle[i], cycle[(i+1) % cycle.len()]);
+        graph.neighbors(u).filter(|(x, _)| *x == v).map(|(_, w)| *w).min().unwrap()
+    }).sum()
 }
 
-#[derive(Eq, PartialEq, Clone)]
-struct Edge {
-    from: Box<Node>,
-    to: Box<Node>,
-    weight: isize,
-}
+fn main() {
+    let mut graph:Graph<&str, isize> = Graph::directed();
+
+    graph.add_edge("A", "B", 5);
+    graph.add_edge("B", "C", 1);
+    graph.add_edge("B", "D", 2);
+    graph.add_edge("C", "E", 1);
+    graph.add_edge("E", "D", -1);
+    graph.add_edge("D", "F", 2);
+    graph.add_edge("F", "E", -3);
+    graph.add_edge("F", "G", 4);
+    graph.add_node("H");
+
+    let (paths, neg_cycle) = bellman_ford(&graph, graph.id("A").unwrap());
+    let shortest:HashMap<&str, Option<isize>> = paths.distances().iter().enumerate().map(|(u, d)| (*graph.key(u), *d)).collect();
+    println!("Shortest distances = {:?}", shortest);
 
-impl Node {
-    fn new(key:String) -> Self {
-        Self {
-            key,
-        }
+    // E -> D -> F -> E weighs -2, and everything after it is unbounded.
+    let NegativeCycle { cycle, unbounded } = neg_cycle.unwrap();
+    let cycle:Vec<&str> = cycle.into_iter().map(|u| *graph.key(u)).collect();
+    let mut unbounded:Vec<&str> = unbounded.into_iter().map(|u| *graph.key(u)).collect();
+    unbounded.sort();
+    println!("Negative cycle = {:?}, unbounded = {:?}", cycle, unbounded);
+
+    assert_eq!(cycle.len(), 3);
+    assert!(["D", "E", "F"].iter().all(|k| cycle.contains(k)));
+    assert_eq!(unbounded, vec!["D", "E", "F", "G"]);
+    assert_eq!(&paths.distances()[..3], &[Some(0), Some(5), Some(6)]);
+    assert_eq!(paths.distance(graph.id("H").unwrap()), None);
+
+    let (spfa_paths, spfa_cycle) = spfa(&graph, 0);
+    assert_eq!(&spfa_paths.distances()[..3], &paths.distances()[..3]);
+    assert_eq!(spfa_cycle.unwrap().unbounded.len(), 4);
+
+    let mut graph:Graph<&str, isize> = Graph::directed();
+    graph.add_edge("A", "B", 4);
+    graph.add_edge("A", "C", 2);
+    graph.add_edge("C", "B", -3);
+    graph.add_edge("B", "D", 1);
+    graph.add_node("E");
+
+    let (paths, neg_cycle) = bellman_ford(&graph, 0);
+    assert!(neg_cycle.is_none());
+    assert_eq!(paths.distances(), &[Some(0), Some(-1), Some(2), Some(0), None]);
+    assert_eq!(bellman_ford(&graph.to_csr(), 0).0.distances(), paths.distances());
+    assert_eq!(spfa(&graph, 0).0.distances(), paths.distances());
+
+    let route:Vec<&str> = paths.path_to(graph.id("D").unwrap()).unwrap().into_iter().map(|u| *graph.key(u)).collect();
+    println!("Shortest path A -> D = {:?}", route);
+    assert_eq!(route, vec!["A", "C", "B", "D"]);
+    assert_eq!(paths.predecessor(0), None);
+
+    // Huge weights next to unreached nodes must not overflow. A distance
+    // past the largest weight is reported instead of clamped.
+    let mut graph:Graph<u8, i64> = Graph::directed();
+    graph.add_edge(0, 1, i64::MAX);
+    graph.add_edge(1, 2, i64::MAX);
+    graph.add_edge(3, 0, i64::MIN);
+    for (paths, neg_cycle) in [bellman_ford(&graph, 0), spfa(&graph, 0)] {
+        assert!(neg_cycle.is_none());
+        assert_eq!(paths.distances(), &[Some(0), Some(i64::MAX), None, None]);
+        assert_eq!(paths.overflowed(), &[2]);
     }
-}
 
-impl Edge {
-    fn new(from:Node, to:Node, weight:isize) -> Self {
-        Self {
-            from: Box::new(from),
-            to: Box::new(to),
-            weight,
-        }
+    // Sums are exact, so a negative edge past an overflowed node brings the
+    // path back in range.
+    let mut graph:Graph<u8, i64> = Graph::directed();
+    graph.add_edge(0, 1, i64::MAX);
+    graph.add_edge(1, 2, i64::MAX);
+    graph.add_edge(2, 3, i64::MIN);
+    for (paths, neg_cycle) in [bellman_ford(&graph, 0), spfa(&graph, 0)] {
+        assert!(neg_cycle.is_none());
+        assert_eq!(paths.distance(3), Some(i64::MAX-1));
+        assert_eq!(paths.overflowed(), &[2]);
+        assert!(paths.is_reachable(2));
+        assert_eq!(paths.path_to(3), Some(vec![0, 1, 2, 3]));
     }
-}
 
-impl Graph {
-    fn new() -> Self {
-        Self {
-            nodes: Vec::new(),
-            edges: Vec::new(),
-        }
+    // Negative cycles whose distances leave the range of the weight type.
+    let mut small:Graph<u32, i8> = Graph::directed();
+    let mut huge:Graph<u32, i64> = Graph::directed();
+    for (u, v) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+        small.add_edge(u, v, -100);
+        huge.add_edge(u, v, i64::MIN/2);
+    }
+    small.add_edge(4, 0, 1);
+    huge.add_edge(4, 0, 1);
+
+    let results = [bellman_ford(&small, 0), spfa(&small, 0)].map(|(_, c)| c);
+    let results = results.into_iter().chain([bellman_ford(&huge, 0), spfa(&huge, 0)].map(|(_, c)| c));
+    for neg_cycle in results {
+        let NegativeCycle { mut cycle, unbounded } = neg_cycle.expect("a negative cycle must be reported");
+        cycle.sort();
+        assert_eq!(cycle, vec![0, 1, 2]);
+        assert_eq!(unbounded, vec![0, 1, 2, 3]);
     }
-}
 
-#[derive(Eq, PartialEq, Clone)]
-struct NodeDist {
-    node_key: String,
-    dist: isize,
-}
+    // A path below the smallest weight without any cycle.
+    let mut chain:Graph<u32, i8> = Graph::directed();
+    chain.add_edge(0, 1, -100);
+    chain.add_edge(1, 2, -100);
+    chain.add_edge(2, 3, 50);
+    for (paths, neg_cycle) in [bellman_ford(&chain, 0), spfa(&chain, 0)] {
+        assert!(neg_cycle.is_none());
+        assert_eq!(&paths.distances()[..2], &[Some(0), Some(-100)]);
+        assert_eq!(paths.overflowed(), &[2, 3]);
+    }
 
-impl Ord for NodeDist {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.dist.cmp(&other.dist)
+    // A cycle first reached below the smallest weight.
+    let mut stalled:Graph<u32, i8> = Graph::directed();
+    stalled.add_edge(0, 1, -100);
+    stalled.add_edge(1, 2, -100);
+    stalled.add_edge(2, 3, -1);
+    stalled.add_edge(3, 2, -1);
+    for (_, neg_cycle) in [bellman_ford(&stalled, 0), spfa(&stalled, 0)] {
+        let NegativeCycle { mut cycle, unbounded } = neg_cycle.expect("a negative cycle must be reported");
+        cycle.sort();
+        assert_eq!(cycle, vec![2, 3]);
+        assert_eq!(unbounded, vec![2, 3]);
     }
-}
 
-impl PartialOrd for NodeDist {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    // A cycle only reached by a path above the largest weight, and a source
+    // on a negative loop next to another one.
+    let mut behind:Graph<u32, i8> = Graph::directed();
+    behind.add_edge(0, 1, 100);
+    behind.add_edge(1, 2, 100);
+    behind.add_edge(2, 1, -128);
+    let mut loops:Graph<u32, i8> = Graph::directed();
+    loops.add_edge(0, 1, 118);
+    loops.add_edge(0, 0, -102);
+    loops.add_edge(1, 1, -51);
+    for (graph, expected) in [(&behind, vec![1, 2]), (&loops, vec![0, 1])] {
+        for (paths, neg_cycle) in [bellman_ford(graph, 0), spfa(graph, 0)] {
+            let NegativeCycle { cycle, unbounded } = neg_cycle.expect("a negative cycle must be reported");
+            assert!(cycle.iter().all(|v| expected.contains(v)));
+            assert_eq!(unbounded, expected);
+            assert!(paths.overflowed().is_empty());
+        }
     }
-}
 
-fn bellman_ford(graph:&Graph, src_node:String) -> (HashMap<String, isize>, bool) {
-    let mut shortest_distances:HashMap<String, isize> = HashMap::new();
+    // Random graphs with negative edges, Bellman-Ford against SPFA.
+    let mut rng = rand::thread_rng();
+    let mut with_cycles:usize = 0;
 
-    for node in graph.nodes.iter() {
-        if node.key == src_node {
-            shortest_distances.insert(node.key.clone(), 0);
+    for _ in 0..500 {
+        let n = rng.gen_range(1..30);
+        let mut graph:Graph<usize, i64> = Graph::directed();
+        for u in 0..n {
+            graph.add_node(u);
         }
-
-        else {
-            shortest_distances.insert(node.key.clone(), MAX);
+        for _ in 0..rng.gen_range(0..3*n) {
+            graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-10..40));
         }
-    }
 
-    let num_nodes = graph.nodes.len();
-    let mut contains_negative_cycle:bool = false;
-
-    for i in 0..num_nodes {
-        for edge in graph.edges.iter() {
-            let u = &edge.from;
-            let v = &edge.to;
-            let w = edge.weight;
-    
-            let du = shortest_distances.get(&u.key);
-            let dv = shortest_distances.get(&v.key);
-    
-            match du {
-                Some(x) => {
-                    match dv {
-                        Some(y) => {
-                            if *x + w < *y {
-                                if i == num_nodes-1 {
-                                    contains_negative_cycle = true;
-                                }
-                                shortest_distances.insert(v.key.clone(), *x+w);
-                            }
-                        }
-                        None => {}
+        let src = rng.gen_range(0..n);
+        let (bf_paths, bf_cycle) = bellman_ford(&graph, src);
+        let (spfa_paths, spfa_cycle) = spfa(&graph, src);
+
+        match (bf_cycle, spfa_cycle) {
+            (None, None) => {
+                assert_eq!(bf_paths.distances(), spfa_paths.distances());
+                assert!(bf_paths.overflowed().is_empty());
+                for v in 0..n {
+                    if let Some(path) = bf_paths.path_to(v) {
+                        assert_eq!(path[0], src);
+                        assert_eq!(*path.last().unwrap(), v);
                     }
                 }
-                None => {}
             }
+            (Some(a), Some(b)) => {
+                with_cycles += 1;
+                assert_eq!(a.unbounded, b.unbounded);
+                assert!(get_cycle_weight(&graph, &a.cycle) < 0);
+                assert!(get_cycle_weight(&graph, &b.cycle) < 0);
+                assert!(a.cycle.iter().all(|v| a.unbounded.contains(v)));
+
+                for v in (0..n).filter(|v| !a.unbounded.contains(v)) {
+                    assert_eq!(bf_paths.distance(v), spfa_paths.distance(v));
+                }
+            }
+            _ => panic!("Bellman-Ford and SPFA disagree on negative cycles"),
         }
     }
+    println!("{} of 500 random graphs had a negative cycle", with_cycles);
+
+    // Small weights against the exact distances in a wider type.
+    for _ in 0..3000 {
+        let n = rng.gen_range(1..8);
+        let mut small:Graph<usize, i8> = Graph::directed();
+        let mut wide:Graph<usize, i64> = Graph::directed();
+        for u in 0..n {
+            small.add_node(u);
+            wide.add_node(u);
+        }
+        for _ in 0..rng.gen_range(0..3*n) {
+            let (u, v, w) = (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-90..=127));
+            small.add_edge_ids(u, v, w);
+            wide.add_edge_ids(u, v, w as i64);
+        }
 
-    return (shortest_distances, contains_negative_cycle);
-}
+        let src = rng.gen_range(0..n);
+        let (exact, exact_cycle) = bellman_ford(&wide, src);
+        let exact_unbounded:Vec<usize> = exact_cycle.map(|c| c.unbounded).unwrap_or_default();
 
-fn main() {
-    let mut graph = Graph::new();
-
-    let node_0 = Node::new(String::from("A"));
-    let node_1 = Node::new(String::from("B"));
-    let node_2 = Node::new(String::from("C"));
-    let node_3 = Node::new(String::from("D"));
-    let node_4 = Node::new(String::from("E"));
-    let node_5 = Node::new(String::from("F"));
-
-    let nodes = vec![Box::new(node_0.clone()), Box::new(node_1.clone()), Box::new(node_2.clone()), Box::new(node_3.clone()), Box::new(node_4.clone()), Box::new(node_5.clone())];
-    
-    let edge1 = Edge::new(node_0.clone(), node_1.clone(), 5);
-    let edge2 = Edge::new(node_1.clone(), node_2.clone(), 1);
-    let edge3 = Edge::new(node_1.clone(), node_3.clone(), 2);
-    let edge4 = Edge::new(node_2.clone(), node_4.clone(), 1);
-    let edge5 = Edge::new(node_4.clone(), node_3.clone(), -1);
-    let edge6 = Edge::new(node_3.clone(), node_5.clone(), 2);
-    let edge7 = Edge::new(node_5.clone(), node_4.clone(), -3);
-
-    let edges = vec![Box::new(edge1), Box::new(edge2), Box::new(edge3), Box::new(edge4), Box::new(edge5), Box::new(edge6), Box::new(edge7)];
-    
-    graph.nodes = nodes;
-    graph.edges = edges;
-
-    let (shortest, neg_cycle) = bellman_ford(&graph, String::from("A"));
-    println!("Shortest distances = {:?}", shortest);
-    println!("Contains negative cycle = {:?}", neg_cycle);
-}
\ No newline at end of file
+        for (paths, neg_cycle) in [bellman_ford(&small, src), spfa(&small, src)] {
+            match neg_cycle {
+                Some(c) => {
+                    assert_eq!(c.unbounded, exact_unbounded);
+                    assert!(get_cycle_weight(&wide, &c.cycle) < 0);
+                }
+                None => assert!(exact_unbounded.is_empty()),
+            }
+
+            for v in (0..n).filter(|v| !exact_unbounded.contains(v)) {
+                let d = exact.distance(v);
+                assert_eq!(paths.distance(v).map(|x| x as i64), d.filter(|x| i8::try_from(*x).is_ok()));
+                assert_eq!(paths.overflowed().contains(&v), d.is_some_and(|x| i8::try_from(x).is_err()));
+                assert_eq!(paths.is_reachable(v), d.is_some());
+            }
+        }
+    }
+}
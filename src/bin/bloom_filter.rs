@@ -0,0 +1,47 @@
+use algorithms::probabilistic::bloom::{BloomFilter, CountingBloomFilter};
+
+fn main() {
+    let n:u64 = 100000;
+
+    for fp_rate in [0.1, 0.01, 0.001] {
+        let mut bloom = BloomFilter::from_capacity(n as usize, fp_rate);
+        let mut counting = CountingBloomFilter::from_capacity(n as usize, fp_rate);
+
+        for i in 0..n {
+            bloom.add(&format!("user-{}", i));
+            counting.add(&format!("user-{}", i));
+        }
+
+        for i in 0..n {
+            assert!(bloom.contains(&format!("user-{}", i)));
+            assert!(counting.contains(&format!("user-{}", i)));
+        }
+
+        let mut false_positives:u64 = 0;
+        for i in n..10*n {
+            if bloom.contains(&format!("user-{}", i)) {
+                false_positives += 1;
+            }
+        }
+
+        let measured = false_positives as f64/(9*n) as f64;
+        println!("target = {:?}, expected = {:.5}, measured = {:.5}, bits = {:?}, hashes = {:?}", fp_rate, bloom.expected_fp_rate(), measured, bloom.num_bits(), bloom.num_hashes());
+        assert!(measured <= 1.2*fp_rate, "false positive rate {} above target {}", measured, fp_rate);
+
+        // Remove every other item, the rest must still be found.
+        for i in (0..n).step_by(2) {
+            assert!(counting.remove(&format!("user-{}", i)));
+        }
+        for i in (1..n).step_by(2) {
+            assert!(counting.contains(&format!("user-{}", i)));
+        }
+
+        let mut still_present:u64 = 0;
+        for i in (0..n).step_by(2) {
+            if counting.contains(&format!("user-{}", i)) {
+                still_present += 1;
+            }
+        }
+        println!("Counting filter: removed items still reported = {:.5}", still_present as f64/(n/2) as f64);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use algorithms::cache::arc::ARCCache;
+use algorithms::cache::lfu::LFUCache;
+use algorithms::cache::lru::LRUCache;
+use algorithms::cache::tiny_lfu::TinyLFUCache;
+use algorithms::cache::two_queue::TwoQueueCache;
+use algorithms::cache::{replay, Cache};
+use rand::Rng;
+
+// Keys 0..n with P(k) proportional to 1/(k+1)^s, sampled by inverse CDF.
+fn get_zipf_trace(n:usize, s:f64, len:usize) -> Vec<u64> {
+    let mut cdf:Vec<f64> = Vec::with_capacity(n);
+    let mut total:f64 = 0.0;
+    for k in 0..n {
+        total += 1.0/((k+1) as f64).powf(s);
+        cdf.push(total);
+    }
+
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| {
+        let u = rng.gen::<f64>()*total;
+        cdf.partition_point(|c| *c < u).min(n-1) as u64
+    }).collect()
+}
+
+// Zipf requests interrupted by long sequential scans over keys never seen again.
+fn get_scan_trace(len:usize) -> Vec<u64> {
+    let zipf = get_zipf_trace(5000, 0.9, len);
+    let mut trace:Vec<u64> = Vec::with_capacity(2*len);
+    let mut next_scan_key:u64 = 1 << 32;
+
+    for (i, key) in zipf.into_iter().enumerate() {
+        trace.push(key);
+        if i % 10000 == 9999 {
+            for _ in 0..5000 {
+                trace.push(next_scan_key);
+                next_scan_key += 1;
+            }
+        }
+    }
+    trace
+}
+
+// A loop over slightly more keys than fit, the classic worst case for LRU.
+fn get_loop_trace(keys:u64, len:usize) -> Vec<u64> {
+    (0..len as u64).map(|i| i % keys).collect()
+}
+
+// The popular keys move to a new range every quarter of the trace.
+fn get_shifting_trace(len:usize) -> Vec<u64> {
+    let zipf = get_zipf_trace(5000, 1.0, len);
+    zipf.into_iter().enumerate().map(|(i, k)| k + 100000*(4*i/len) as u64).collect()
+}
+
+type Policy = (&'static str, Box<dyn Cache<u64, u64>>);
+
+fn get_policies(capacity:usize) -> Vec<Policy> {
+    vec![
+        ("LRU", Box::new(LRUCache::new(capacity))),
+        ("LFU", Box::new(LFUCache::new(capacity))),
+        ("ARC", Box::new(ARCCache::new(capacity))),
+        ("2Q", Box::new(TwoQueueCache::new(capacity))),
+        ("W-TinyLFU", Box::new(TinyLFUCache::new(capacity))),
+    ]
+}
+
+fn main() {
+    let traces:Vec<(&str, Vec<u64>)> = vec![
+        ("zipf 0.9", get_zipf_trace(10000, 0.9, 200000)),
+        ("zipf + scans", get_scan_trace(200000)),
+        ("loop", get_loop_trace(1200, 200000)),
+        ("shifting", get_shifting_trace(200000)),
+    ];
+
+    let mut results:HashMap<(&str, &str, usize), f64> = HashMap::new();
+
+    for capacity in [100, 1000] {
+        println!("capacity {}", capacity);
+        print!("{:>14}", "trace");
+        for (name, _) in get_policies(capacity) {
+            print!("{:>11}", name);
+        }
+        println!();
+
+        for (trace_name, trace) in traces.iter() {
+            print!("{:>14}", trace_name);
+            for (name, mut cache) in get_policies(capacity) {
+                let stats = replay(cache.as_mut(), trace, |k| *k);
+                assert!(cache.len() <= capacity);
+                results.insert((trace_name, name, capacity), stats.hit_rate());
+                print!("{:>10.2}%", 100.0*stats.hit_rate());
+            }
+            println!();
+        }
+        println!();
+    }
+
+    // LRU never hits on a loop larger than the cache (and neither does ARC,
+    // whose ghost lists only remember as many keys as the cache holds), while
+    // 2Q and W-TinyLFU refuse to let every new key in and keep part of it.
+    for name in ["2Q", "W-TinyLFU"] {
+        assert!(results[&("loop", name, 1000)] > results[&("loop", "LRU", 1000)] + 0.3);
+    }
+    assert!(results[&("zipf + scans", "W-TinyLFU", 1000)] > results[&("zipf + scans", "LRU", 1000)]);
+    assert!(results[&("zipf 0.9", "W-TinyLFU", 1000)] >= results[&("zipf 0.9", "LRU", 1000)]);
+
+    // Random puts and gets against a map of the latest values: a policy may
+    // forget keys but never return a stale value or exceed its capacity.
+    let mut rng = rand::thread_rng();
+    for capacity in [1, 2, 3, 10, 64] {
+        for (name, mut cache) in get_policies(capacity) {
+            let mut latest:HashMap<u64, u64> = HashMap::new();
+            for i in 0..20000 {
+                let key = rng.gen_range(0..3*capacity as u64 + 2);
+                if rng.gen_bool(0.5) {
+                    let displaced = cache.put(key, i);
+                    latest.insert(key, i);
+                    for (k, v) in displaced {
+                        assert!(k == key || latest[&k] == v, "{} displaced a stale value", name);
+                    }
+                }
+                else if let Some(v) = cache.get(&key) {
+                    assert_eq!(*v, latest[&key], "{} returned a stale value", name);
+                }
+                assert!(cache.len() <= capacity, "{} exceeds its capacity", name);
+                assert_eq!(cache.contains(&key), cache.contains(&key));
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use algorithms::graph::components::{articulation_points, bridges, condensation, kosaraju_scc, tarjan_scc};
+use algorithms::graph::disjoint_set::DisjointSet;
+use algorithms::graph::topological_sort::topsort;
+use algorithms::graph::Graph;
+use rand::Rng;
+
+fn get_reachable(graph:&Graph<usize, ()>, u:usize) -> Vec<bool> {
+    let mut reached:Vec<bool> = vec![false; graph.num_nodes()];
+    let mut stack:Vec<usize> = vec![u];
+    reached[u] = true;
+
+    while let Some(x) = stack.pop() {
+        for (y, _) in graph.out_edges(x) {
+            if !reached[y] {
+                reached[y] = true;
+                stack.push(y);
+            }
+        }
+    }
+    reached
+}
+
+// Components must partition the nodes into mutually reachable sets, listed
+// so that every edge goes forwards.
+fn check_components(graph:&Graph<usize, ()>, components:&[Vec<usize>]) {
+    let mut component:Vec<Option<usize>> = vec![None; graph.num_nodes()];
+    for (c, nodes) in components.iter().enumerate() {
+        for u in nodes.iter() {
+            assert!(component[*u].is_none());
+            component[*u] = Some(c);
+        }
+    }
+
+    let reachable:Vec<Vec<bool>> = (0..graph.num_nodes()).map(|u| get_reachable(graph, u)).collect();
+    for u in 0..graph.num_nodes() {
+        for v in 0..graph.num_nodes() {
+            assert_eq!(component[u] == component[v], reachable[u][v] && reachable[v][u]);
+        }
+    }
+    for edge in graph.edges().iter() {
+        assert!(component[edge.from] <= component[edge.to]);
+    }
+}
+
+// Number of connected components, leaving out one node and one edge.
+fn count_components(graph:&Graph<usize, ()>, skip_node:Option<usize>, skip_edge:Option<usize>) -> usize {
+    let mut sets = DisjointSet::new(graph.num_nodes());
+    for (e, edge) in graph.edges().iter().enumerate() {
+        let touches = skip_node.is_some_and(|u| edge.from == u || edge.to == u);
+        if !touches && skip_edge != Some(e) {
+            sets.union(edge.from, edge.to);
+        }
+    }
+    sets.num_sets() - usize::from(skip_node.is_some())
+}
+
+fn get_random_graph(rng:&mut impl Rng, directed:bool) -> Graph<usize, ()> {
+    let n = rng.gen_range(1..12);
+    let mut graph:Graph<usize, ()> = Graph::new(directed);
+    for u in 0..n {
+        graph.add_node(u);
+    }
+    for _ in 0..rng.gen_range(0..2*n) {
+        graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), ());
+    }
+    graph
+}
+
+fn main() {
+    // Module imports, with two import cycles.
+    let mut imports:Graph<&str, ()> = Graph::directed();
+    let edges = [("app", "ui"), ("app", "db"), ("ui", "widgets"), ("widgets", "theme"), ("theme", "ui"),
+                 ("db", "pool"), ("pool", "config"), ("config", "db"), ("ui", "config"), ("config", "log")];
+    for (u, v) in edges.iter() {
+        imports.add_edge(*u, *v, ());
+    }
+
+    let condensed = condensation(&imports);
+    let mut groups:Vec<Vec<&str>> = condensed.members.iter().map(|nodes| {
+        let mut keys:Vec<&str> = nodes.iter().map(|u| *imports.key(*u)).collect();
+        keys.sort();
+        keys
+    }).collect();
+    println!("Import groups = {:?}", groups);
+
+    assert_eq!(groups.len(), 4);
+    assert_eq!(groups[0], vec!["app"]);
+    assert_eq!(groups[3], vec!["log"]);
+    groups.sort();
+    assert_eq!(groups, vec![vec!["app"], vec!["config", "db", "pool"], vec!["log"], vec!["theme", "ui", "widgets"]]);
+    assert_eq!(condensed.dag.num_edges(), 4);
+    assert!(topsort(&condensed.dag).is_ok());
+    assert_eq!(condensed.component[imports.id("db").unwrap()], condensed.component[imports.id("pool").unwrap()]);
+
+    // Roads between villages, a bridge is a road with no way around it.
+    let mut roads:Graph<&str, ()> = Graph::undirected();
+    let edges = [("Ash", "Elm"), ("Elm", "Oak"), ("Oak", "Ash"), ("Oak", "Fir"), ("Fir", "Yew"),
+                 ("Yew", "Ivy"), ("Ivy", "Fir"), ("Yew", "Bay"), ("Bay", "Yew")];
+    for (u, v) in edges.iter() {
+        roads.add_edge(*u, *v, ());
+    }
+
+    let critical:Vec<(&str, &str)> = bridges(&roads).into_iter().map(|e| {
+        let edge = roads.edge(e);
+        (*roads.key(edge.from), *roads.key(edge.to))
+    }).collect();
+    let mut cut_villages:Vec<&str> = articulation_points(&roads).into_iter().map(|u| *roads.key(u)).collect();
+    cut_villages.sort();
+    println!("Bridges = {:?}, articulation points = {:?}", critical, cut_villages);
+
+    // The two roads between Yew and Bay are parallel, so neither is a bridge.
+    assert_eq!(critical, vec![("Oak", "Fir")]);
+    assert_eq!(cut_villages, vec!["Fir", "Oak", "Yew"]);
+
+    // Random graphs with parallel edges and self loops, against brute force.
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let graph = get_random_graph(&mut rng, true);
+        let by_tarjan = tarjan_scc(&graph);
+        let by_kosaraju = kosaraju_scc(&graph);
+        check_components(&graph, &by_tarjan);
+        check_components(&graph, &by_kosaraju);
+        assert_eq!(by_tarjan.len(), by_kosaraju.len());
+
+        let condensed = condensation(&graph);
+        assert!(topsort(&condensed.dag).is_ok());
+        for edge in graph.edges().iter() {
+            let (a, b) = (condensed.component[edge.from], condensed.component[edge.to]);
+            assert!(a == b || condensed.dag.neighbors(a).any(|(x, _)| x == b));
+        }
+
+        match topsort(&graph) {
+            Ok(order) => {
+                assert_eq!(order.len(), graph.num_nodes());
+                assert_eq!(by_tarjan.len(), graph.num_nodes());
+            }
+            Err(cycle) => {
+                assert!(!cycle.is_empty());
+                for i in 0..cycle.len() {
+                    let (u, v) = (cycle[i], cycle[(i+1) % cycle.len()]);
+                    assert!(graph.neighbors(u).any(|(x, _)| x == v));
+                }
+            }
+        }
+
+        let graph = get_random_graph(&mut rng, false);
+        let total = count_components(&graph, None, None);
+        let found = bridges(&graph);
+        for e in 0..graph.num_edges() {
+            assert_eq!(found.contains(&e), count_components(&graph, None, Some(e)) > total);
+        }
+        let found = articulation_points(&graph);
+        for u in 0..graph.num_nodes() {
+            assert_eq!(found.contains(&u), count_components(&graph, Some(u), None) > total);
+        }
+    }
+
+    // Long paths would overflow a recursive search.
+    let n = 200_000;
+    let mut ring:Graph<usize, ()> = Graph::directed();
+    let mut path:Graph<usize, ()> = Graph::undirected();
+    for u in 0..n {
+        ring.add_edge(u, (u+1) % n, ());
+        path.add_edge(u, u+1, ());
+    }
+    assert_eq!(tarjan_scc(&ring).len(), 1);
+    assert_eq!(kosaraju_scc(&ring).len(), 1);
+    assert_eq!(topsort(&ring).unwrap_err().len(), n);
+    assert_eq!(bridges(&path).len(), n);
+    assert_eq!(articulation_points(&path).len(), n-1);
+
+    println!("Tarjan, Kosaraju, bridges and articulation points agree on 1000 random graphs");
+}
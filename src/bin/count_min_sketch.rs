@@ -0,0 +1,45 @@
+use algorithms::probabilistic::count_min::CountMinSketch;
+use std::collections::HashMap;
+use rand::Rng;
+
+fn main() {
+    let mut exact:HashMap<u64, u64> = HashMap::new();
+    let mut shard_1 = CountMinSketch::from_error(0.01, 0.01, false);
+    let mut shard_2 = CountMinSketch::from_error(0.01, 0.01, false);
+    let mut conservative = CountMinSketch::from_error(0.01, 0.01, true);
+
+    // Roughly Zipfian user ids, a handful of heavy users and a long tail.
+    for i in 0..200000 {
+        let u:f64 = rand::thread_rng().gen_range(0.0..1.0);
+        let user = (1.0/(u*0.99999+0.00001)) as u64;
+
+        *exact.entry(user).or_insert(0) += 1;
+        conservative.add(&user, 1);
+
+        if i % 2 == 0 {
+            shard_1.add(&user, 1);
+        }
+        else {
+            shard_2.add(&user, 1);
+        }
+    }
+
+    shard_1.merge(&shard_2).unwrap();
+    let restored = CountMinSketch::from_bytes(&shard_1.to_bytes()).unwrap();
+
+    let mut max_err:u64 = 0;
+    let mut max_err_cu:u64 = 0;
+
+    for (user, cnt) in exact.iter() {
+        let est = restored.estimate(user);
+        let est_cu = conservative.estimate(user);
+        assert!(est >= *cnt && est_cu >= *cnt);
+
+        max_err = max_err.max(est-cnt);
+        max_err_cu = max_err_cu.max(est_cu-cnt);
+    }
+
+    println!("Total = {:?}, distinct = {:?}", restored.total(), exact.len());
+    println!("Count of user 1 = {:?}, estimate = {:?}", exact[&1], restored.estimate(&1u64));
+    println!("Max overestimate = {:?}, with conservative update = {:?}", max_err, max_err_cu);
+}
@@ -0,0 +1,45 @@
+use algorithms::probabilistic::cuckoo::CuckooFilter;
+
+fn main() {
+    let n:u64 = 100000;
+    let mut cuckoo = CuckooFilter::new(n as usize);
+
+    for i in 0..n {
+        cuckoo.add(&i).unwrap();
+    }
+
+    for i in 0..n {
+        assert!(cuckoo.contains(&i));
+    }
+
+    let mut false_positives:u64 = 0;
+    for i in n..11*n {
+        if cuckoo.contains(&i) {
+            false_positives += 1;
+        }
+    }
+
+    // With 16 bit fingerprints and 2 buckets of 4 slots: at most 8/2^16.
+    let measured = false_positives as f64/(10*n) as f64;
+    let bound = 8.0/65536.0;
+    println!("Load factor = {:.3}, measured false positive rate = {:.6}, bound = {:.6}", cuckoo.load_factor(), measured, bound);
+    assert!(measured <= bound);
+
+    for i in (0..n).step_by(2) {
+        assert!(cuckoo.remove(&i));
+    }
+    for i in (1..n).step_by(2) {
+        assert!(cuckoo.contains(&i));
+    }
+    println!("Items after removal = {:?}", cuckoo.len());
+
+    let mut full = CuckooFilter::new(1000);
+    let mut inserted:u64 = 0;
+    while full.add(&inserted).is_ok() {
+        inserted += 1;
+    }
+    for i in 0..inserted {
+        assert!(full.contains(&i));
+    }
+    println!("Filter for 1000 items took {:?} before reporting full", inserted);
+}
@@ -0,0 +1,59 @@
+use algorithms::compression::deflate::Deflate;
+use algorithms::compression::huffman::Huffman;
+use algorithms::compression::lz77::{detokenize, Lz77};
+use rand::Rng;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    let words = ["the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "and", "runs", "away"];
+    let text:Vec<u8> = (0..50000).map(|_| words[rng.gen_range(0..words.len())]).collect::<Vec<&str>>().join(" ").into_bytes();
+    let random:Vec<u8> = (0..100000).map(|_| rng.gen()).collect();
+    let skewed:Vec<u8> = (0..100000).map(|_| rng.gen_range(0..16u8)*rng.gen_range(0..16u8)).collect();
+    let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let source = std::fs::read(file!()).unwrap_or_default();
+
+    let inputs:Vec<(&str, Vec<u8>)> = vec![
+        ("text", text),
+        ("random", random),
+        ("skewed", skewed),
+        ("executable", exe),
+        ("source", source),
+        ("empty", vec![]),
+        ("single byte", vec![42]),
+        ("long run", vec![7u8; 200000]),
+        ("all bytes", (0..=255u8).collect()),
+    ];
+
+    for (name, data) in inputs.iter() {
+        let deflated = Deflate::compress(data);
+        let huffman = Huffman::compress(data);
+
+        assert_eq!(Deflate::decompress(&deflated).unwrap(), *data);
+        println!("{:>12}: {:>8} bytes -> deflate {:>8} bytes, huffman {:>8} bytes", name, data.len(), deflated.len(), huffman.len());
+    }
+
+    // Smaller windows find fewer matches but still round trip.
+    let text = &inputs[0].1;
+    for window in [256, 4096, 32768] {
+        let lz77 = Lz77::new(window, 64);
+        let tokens = lz77.tokenize(text);
+        assert_eq!(detokenize(&tokens).unwrap(), *text);
+
+        let deflated = Deflate::new(Lz77::new(window, 64)).encode(text);
+        assert_eq!(Deflate::decode(&deflated).unwrap(), *text);
+        println!("window {:>5}: {:?} tokens, {:?} bytes", window, tokens.len(), deflated.len());
+    }
+
+    // A well known raw stream produced by zlib: "hello hello hello hello".
+    let zlib_stream:[u8; 10] = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01];
+    assert_eq!(Deflate::decode(&zlib_stream).unwrap(), b"hello hello hello hello");
+
+    // Corrupted streams are rejected instead of panicking.
+    let mut corrupted = Deflate::compress(&inputs[0].1);
+    for i in (0..corrupted.len()).step_by(97) {
+        corrupted[i] ^= 0x55;
+    }
+    assert!(Deflate::decode(&corrupted).map(|d| d != inputs[0].1).unwrap_or(true));
+    assert!(Deflate::decode(&[0x07]).is_err());
+}
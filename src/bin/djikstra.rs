@@ -1,156 +1,67 @@
 use std::collections::HashMap;
-use std::collections::BinaryHeap;
-use std::cmp::Ordering;
-use std::usize::MAX;
+use algorithms::graph::shortest_path::{bellman_ford, dijkstra, dijkstra_to};
+use algorithms::graph::Graph;
+use rand::Rng;
 
-struct Graph {
-    nodes: Vec<Box<Node>>,
-    edges: Vec<Box<Edge>>,
-}
-
-#[derive(Eq, PartialEq, Clone, Hash)]
-struct Node {
-    key: String,
-}
-
-#[derive(Eq, PartialEq, Clone)]
-struct Edge {
-    from: Box<Node>,
-    to: Box<Node>,
-    weight: usize,
-}
-
-impl Node {
-    fn new(key:String) -> Self {
-        Self {
-            key,
-        }
-    }
-}
-
-impl Edge {
-    fn new(from:Node, to:Node, weight:usize) -> Self {
-        Self {
-            from: Box::new(from),
-            to: Box::new(to),
-            weight,
-        }
-    }
-}
-
-impl Graph {
-    fn new() -> Self {
-        Self {
-            nodes: Vec::new(),
-            edges: Vec::new(),
-        }
-    }
-}
-
-#[derive(Eq, PartialEq, Clone)]
-struct NodeDist {
-    node_key: String,
-    dist: usize,
-}
-
-impl Ord for NodeDist {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.dist.cmp(&other.dist)
-    }
-}
-
-impl PartialOrd for NodeDist {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn djikstra(graph:&Graph, src_node:String) -> HashMap<String, usize> {
-    let mut shortest_distances:HashMap<String, usize> = HashMap::new();
-    let mut ordered_nodes:BinaryHeap<NodeDist> = BinaryHeap::new();
+fn main() {
+    let mut graph:Graph<&str, usize> = Graph::undirected();
+
+    graph.add_edge("0", "1", 2);
+    graph.add_edge("0", "2", 6);
+    graph.add_edge("1", "3", 5);
+    graph.add_edge("2", "3", 8);
+    graph.add_edge("3", "4", 10);
+    graph.add_edge("3", "5", 15);
+    graph.add_edge("4", "6", 2);
+    graph.add_edge("5", "6", 6);
+    graph.add_node("7");
+
+    let paths = dijkstra(&graph, graph.id("0").unwrap());
+    let shortest:HashMap<&str, Option<usize>> = paths.distances().iter().enumerate().map(|(u, d)| (*graph.key(u), *d)).collect();
+    println!("Shortest distances = {:?}", shortest);
 
-    for node in graph.nodes.iter() {
-        if node.key == src_node {
-            shortest_distances.insert(node.key.clone(), 0);
-            ordered_nodes.push(NodeDist { node_key: node.key.clone(), dist: 0 });
+    assert_eq!(paths.distances(), &[Some(0), Some(2), Some(6), Some(7), Some(17), Some(22), Some(19), None]);
+    assert_eq!(dijkstra(&graph.to_csr(), 0).distances(), paths.distances());
+
+    let route:Vec<&str> = paths.path_to(graph.id("6").unwrap()).unwrap().into_iter().map(|u| *graph.key(u)).collect();
+    println!("Shortest path 0 -> 6 = {:?}", route);
+    assert_eq!(route, vec!["0", "1", "3", "4", "6"]);
+    assert_eq!(paths.path_to(0), Some(vec![0]));
+    assert_eq!(paths.path_to(graph.id("7").unwrap()), None);
+
+    // Stopping at "3" leaves the far side of the graph unexplored.
+    let to_3 = dijkstra_to(&graph, 0, 3);
+    assert_eq!(to_3.distance(3), Some(7));
+    assert_eq!(to_3.path_to(3), Some(vec![0, 1, 3]));
+    assert!(!to_3.is_reachable(6));
+
+    // Random directed graphs, checked against Bellman-Ford.
+    let mut rng = rand::thread_rng();
+    for _ in 0..200 {
+        let n = rng.gen_range(1..40);
+        let mut graph:Graph<usize, u64> = Graph::directed();
+        for u in 0..n {
+            graph.add_node(u);
         }
-
-        else {
-            shortest_distances.insert(node.key.clone(), MAX);
+        for _ in 0..rng.gen_range(0..4*n) {
+            graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..100));
         }
-    }
 
-    let mut neighbors:HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        let src = rng.gen_range(0..n);
+        let paths = dijkstra(&graph, src);
+        assert_eq!(paths.distances(), bellman_ford(&graph, src).0.distances());
+        assert_eq!(paths.distances(), dijkstra(&graph.to_csr(), src).distances());
 
-    for edge in graph.edges.iter() {
-        let u = &edge.from;
-        let v = &edge.to;
-        let w = edge.weight;
-        
-        let mut nb = neighbors.entry(u.key.clone()).or_insert(vec![]);
-        nb.push((v.key.clone(), w));
-        nb = neighbors.entry(v.key.clone()).or_insert(vec![]);
-        nb.push((u.key.clone(), w));
-    }
+        // Every path must exist in the graph and add up to the distance.
+        for v in 0..n {
+            let target = dijkstra_to(&graph, src, v);
+            assert_eq!(target.distance(v), paths.distance(v));
 
-    while ordered_nodes.len() > 0 {
-        let min_wt = ordered_nodes.pop();
-        match min_wt {
-            Some(min_node_dist) => {
-                let u = min_node_dist.node_key;
-                let d = min_node_dist.dist;
-                let nbs = neighbors.get(&u);
-                match nbs {
-                    Some(nb) => {
-                        for v in nb.iter() {
-                            let d_curr = shortest_distances.get(&v.0);
-                            match d_curr {
-                                Some(x) => {
-                                    if d + v.1 < *x {
-                                        shortest_distances.insert(v.0.clone(), d+v.1);
-                                        ordered_nodes.push(NodeDist { node_key: v.0.clone(), dist: d+v.1 });
-                                    }
-                                }
-                                None => {}
-                            }
-                        }
-                    }
-                    None => {}
-                }
+            if let Some(path) = target.path_to(v) {
+                let length:u64 = path.windows(2).map(|p| graph.neighbors(p[0]).filter(|(x, _)| *x == p[1]).map(|(_, w)| *w).min().unwrap()).sum();
+                assert_eq!(Some(length), paths.distance(v));
+                assert_eq!(path[0], src);
             }
-            None => {}
         }
     }
-    return shortest_distances;
 }
-
-fn main() {
-    let mut graph = Graph::new();
-
-    let node_0 = Node::new(String::from("0"));
-    let node_1 = Node::new(String::from("1"));
-    let node_2 = Node::new(String::from("2"));
-    let node_3 = Node::new(String::from("3"));
-    let node_4 = Node::new(String::from("4"));
-    let node_5 = Node::new(String::from("5"));
-    let node_6 = Node::new(String::from("6"));
-
-    let nodes = vec![Box::new(node_0.clone()), Box::new(node_1.clone()), Box::new(node_2.clone()), Box::new(node_3.clone()), Box::new(node_4.clone()), Box::new(node_5.clone()), Box::new(node_6.clone())];
-    
-    let edge1 = Edge::new(node_0.clone(), node_1.clone(), 2);
-    let edge2 = Edge::new(node_0.clone(), node_2.clone(), 6);
-    let edge3 = Edge::new(node_1.clone(), node_3.clone(), 5);
-    let edge4 = Edge::new(node_2.clone(), node_3.clone(), 8);
-    let edge5 = Edge::new(node_3.clone(), node_4.clone(), 10);
-    let edge6 = Edge::new(node_3.clone(), node_5.clone(), 15);
-    let edge7 = Edge::new(node_4.clone(), node_6.clone(), 2);
-    let edge8 = Edge::new(node_5.clone(), node_6.clone(), 6);
-
-    let edges = vec![Box::new(edge1), Box::new(edge2), Box::new(edge3), Box::new(edge4), Box::new(edge5), Box::new(edge6), Box::new(edge7), Box::new(edge8)];
-    
-    graph.nodes = nodes;
-    graph.edges = edges;
-
-    let shortest = djikstra(&graph, String::from("0"));
-    println!("Shortest distances = {:?}", shortest);
-}
\ No newline at end of file
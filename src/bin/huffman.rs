@@ -0,0 +1,80 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::process;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use algorithms::compression::stream::{compress_stream, decompress_stream, StreamStats, DEFAULT_BLOCK_SIZE};
+
+// A missing path or "-" means stdin.
+fn open_input(path:Option<&String>) -> io::Result<Box<dyn Read>> {
+    match path.map(|p| p.as_str()) {
+        None | Some("-") => Ok(Box::new(BufReader::new(io::stdin().lock()))),
+        Some(p) => Ok(Box::new(BufReader::new(File::open(p)?))),
+    }
+}
+
+// A missing path or "-" means stdout.
+fn open_output(path:Option<&String>) -> io::Result<Box<dyn Write>> {
+    match path.map(|p| p.as_str()) {
+        None | Some("-") => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+        Some(p) => Ok(Box::new(BufWriter::new(File::create(p)?))),
+    }
+}
+
+fn report(action:&str, stats:&StreamStats, original:u64, compressed:u64) {
+    let ratio = if original == 0 { 1.0 } else { compressed as f64/original as f64 };
+    eprintln!("{} {} -> {} bytes, ratio {:.3} ({:.2}% saved)", action, stats.bytes_in, stats.bytes_out, ratio, 100.0*(1.0-ratio));
+}
+
+fn run(matches:&ArgMatches) -> io::Result<()> {
+    match matches.subcommand() {
+        Some(("compress", sub)) => {
+            let block_size = *sub.get_one::<usize>("block-size").unwrap_or(&DEFAULT_BLOCK_SIZE);
+            let mut input = open_input(sub.get_one::<String>("input"))?;
+            let mut output = open_output(sub.get_one::<String>("output"))?;
+
+            let stats = compress_stream(&mut input, &mut output, block_size)?;
+            report("Compressed", &stats, stats.bytes_in, stats.bytes_out);
+        }
+        Some(("decompress", sub)) => {
+            let mut input = open_input(sub.get_one::<String>("input"))?;
+            let mut output = open_output(sub.get_one::<String>("output"))?;
+
+            let stats = decompress_stream(&mut input, &mut output)?;
+            report("Decompressed", &stats, stats.bytes_out, stats.bytes_in);
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn main() {
+    let input = Arg::new("input")
+        .help("Input file, stdin if missing or -");
+    let output = Arg::new("output")
+        .help("Output file, stdout if missing or -");
+
+    let matches = Command::new("Huffman Compression Program")
+        .version("0.1.0")
+        .author("Abhijit Mondal <abhi2iitk@gmail.com>")
+        .about("Compress and decompress files with Huffman coding")
+        .subcommand_required(true)
+        .subcommand(Command::new("compress")
+                 .about("Compress a file, storing a CRC-32 of the original data")
+                 .arg(input.clone())
+                 .arg(output.clone())
+                 .arg(Arg::new("block-size")
+                          .short('b')
+                          .long("block-size")
+                          .value_parser(value_parser!(usize))
+                          .help("Bytes per independently coded block, 1 MiB by default")))
+        .subcommand(Command::new("decompress")
+                 .about("Decompress a file and verify its CRC-32")
+                 .arg(input)
+                 .arg(output))
+        .get_matches();
+
+    if let Err(e) = run(&matches) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
@@ -1,287 +1,5 @@
-use std::collections::HashMap;
-use std::collections::BinaryHeap;
-use std::cmp::Ordering;
-use std::io::Bytes;
-use std::str::FromStr;
-
-#[derive(Clone)]
-struct Node {
-    character : char,
-    frequency : usize,
-    lt_node : Option<Box<Node>>,
-    rt_node : Option<Box<Node>>,
-}
-
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.frequency <= other.frequency {
-            return Ordering::Greater;
-        }
-        else {
-            return Ordering::Less;
-        }
-    }
-}
-
-impl Eq for Node {}
-
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.frequency <= other.frequency {
-            return Some(Ordering::Greater);
-        }
-        else {
-            return Some(Ordering::Less);
-        }
-    }
-}
-
-impl PartialEq for Node {
-    fn eq(&self, other: &Self) -> bool {
-        self.cmp(other) == Ordering::Equal
-    }
-}
-
-struct Huffman {
-    root_node : Option<Box<Node>>,
-    priority_queue : BinaryHeap<Node>,
-}
-
-impl Node {
-    fn new(character:char, frequency:usize) -> Self {
-        Self {
-            character,
-            frequency,
-            lt_node : None,
-            rt_node : None,
-        }
-    }
-}
-
-impl Huffman {
-    fn new() -> Self {
-        Self {
-            root_node : None,
-            priority_queue : BinaryHeap::new(),
-        }
-    }
-}
-
-impl Huffman {
-    fn insert(&mut self, mytext: String) {
-        let mut character_cnts:HashMap<char, usize> = HashMap::new();
-        for (_i, c) in mytext.chars().enumerate() {
-            let cnt = character_cnts.entry(c).or_insert(0);
-            *cnt += 1;
-        }
-
-        for (c, cnt) in &character_cnts {
-            let node:Node = Node::new(*c, *cnt);
-            self.priority_queue.push(node);
-        }
-
-        while self.priority_queue.len() > 0 {
-            let mut lt_node:Option<Box<Node>> = None;
-            let mut rt_node:Option<Box<Node>> = None;
-
-            let mut lt_freq:usize = 0;
-            let mut rt_freq:usize = 0;
-
-            let pq_root = self.priority_queue.pop();
-            match pq_root {
-                Some(root) => {
-                    lt_freq = root.frequency;
-                    lt_node = Some(Box::new(root));
-                }
-                None => {}
-            }
-
-            if self.priority_queue.len() > 0 {
-                let pq_root = self.priority_queue.pop();
-                match pq_root {
-                    Some(root) => {
-                        rt_freq = root.frequency;
-                        rt_node = Some(Box::new(root));
-                    }
-                    None => {}
-                }
-            }
-
-            let node_char:char = '\0';
-            let node_freq:usize = lt_freq + rt_freq;
-
-            let mut node:Node = Node::new(node_char, node_freq);
-
-            node.lt_node = lt_node;
-            node.rt_node = rt_node;
-
-            self.priority_queue.push(node.clone());
-
-            if self.priority_queue.len() == 1 {
-                self.root_node = Some(Box::new(node));
-                break;
-            }
-        }
-    }
-}
-
-impl Huffman {
-    fn print_tree(&self, root_node:&Option<Box<Node>>, level:usize) {  
-        match root_node {
-            Some(node) => {
-                println!("{} {:?}", "-".repeat(2*level), node.character);
-                self.print_tree(&node.lt_node, level+1);
-                self.print_tree(&node.rt_node, level+1);
-            }
-            None => {}
-        }
-    }
-}
-
-impl Huffman {
-    fn encode(&self, mytext:String, codes:&HashMap<char, String>) -> (Option<Vec<u32>>, usize) {
-        let mut out_rep:String = String::from("");
-        let mut vector:Vec<u32> = Vec::new();
-
-        for (_i, c) in mytext.chars().enumerate() {
-            if codes.contains_key(&c) {
-                let rep = codes.get(&c);
-                match rep {
-                    Some(x) => {
-                        out_rep += x;
-                    }
-                    None => {
-                        return (None, 0);
-                    }
-                }
-            }
-            else {
-                return (None, 0);
-            }
-        }
-
-        let mut start:usize = 0;
-
-        while start < out_rep.len() {
-            let slice:&str;
-
-            if start + 32 > out_rep.len() {
-                slice = &out_rep[start..];
-            }
-            else {
-                slice = &out_rep[start..start+32];
-            }
-
-            let mut q:u32 = 1 << 31;
-            let mut v:u32 = 0;
-
-            for (_j, c) in slice.chars().enumerate() {
-                if c == '1' {
-                    v += q;
-                }
-                q = q >> 1;
-            }
-
-            vector.push(v);
-            start += 32;
-        }
-
-        return (Some(vector), mytext.len());
-    }
-}
-
-impl Huffman {
-    fn decode(&self, encoded:(Option<Vec<u32>>, usize), codes:&HashMap<char, String>) -> Option<String> {
-        let mut output = String::from("");
-
-        let enc = encoded.0;
-        let len = encoded.1;
-
-        let mut codes_rev:HashMap<String, char> = HashMap::new();
-        for (k, v) in codes {
-            codes_rev.entry(v.to_string()).or_insert(*k);
-        }
-
-        match enc {
-            Some(coding) => {
-                let mut rep = String::from("");
-                for i in 0..coding.len() {
-                    let mut v = coding[i];
-                    let mut r = String::from("");
-                    while v > 0 {
-                        let rem = v & 1;
-                        r = rem.to_string() + &r;
-                        v = v >> 1;
-                    }
-
-                    if r.len() < 32 {
-                        let diff = 32 - r.len();
-                        r = "0".repeat(diff) + &r;
-                    }
-
-                    rep += &r;
-                }
-
-                let mut curr = String::from("");
-                let mut num:usize = 0;
-
-                for (_i, c) in rep.chars().enumerate() {
-                    curr += &c.to_string();
-
-                    if codes_rev.contains_key(&curr) {
-                        num += 1;
-                        let character = codes_rev.get(&curr);
-
-                        match character {
-                            Some(x) => {
-                                output += &x.to_string();
-                            }
-                            None => {
-                                return None;
-                            }
-                        }
-
-                        if num == len {
-                            break;
-                        }
-
-                        curr = String::from("");
-                    }
-                }
-            }
-            None => {
-                return None;
-            }
-        }
-
-        return Some(output);
-    }
-    
-}
-
-impl Huffman {
-    fn get_codes(&self, node: &Node, curr_code: String, codes: &mut HashMap<char, String>) {
-        if (node.lt_node.is_none()) && (node.rt_node.is_none()) {
-            let character = node.character;
-            codes.entry(character).or_insert(curr_code);
-        }
-        else {
-            match &node.lt_node {
-                Some(lt_node) => {
-                    self.get_codes(&*lt_node, curr_code.clone() + "0", codes);
-                }
-                None => {}
-            }
-
-            match &node.rt_node {
-                Some(rt_node) => {
-                    self.get_codes(&*rt_node, curr_code.clone() + "1", codes);
-                }
-                None => {}
-            }
-        }
-    }
-}
+use algorithms::compression::huffman::{get_code_lengths, Huffman};
+use rand::Rng;
 
 fn main() {
     let mytext:String = String::from("_Walt Whitman has somewhere a fine and just distinction between “loving
@@ -362,28 +80,48 @@ fn main() {
     avail himself, appear at once on the stage._
     ");
     let mut hm = Huffman::new();
-    hm.insert(mytext);
-    hm.print_tree(&hm.root_node, 0);
+    hm.insert(mytext.as_bytes());
+    hm.print_codes();
     println!();
 
-    let mut codes:HashMap<char, String> = HashMap::new();
-
-    match &hm.root_node {
-        Some(root) => {
-            hm.get_codes(root, String::from(""), &mut codes);
-        }
-        None => {}
-    }
-
+    let codes = hm.get_codes();
     println!("{:?}", codes);
 
-    let encoded = hm.encode(String::from("playwright has ever been laid upon_ Pride and Prejudice; _and I dare
+    let sample = String::from("playwright has ever been laid upon_ Pride and Prejudice; _and I dare
     say that, if it were, the situations would prove not startling or
     garish enough for the footlights, the character-scheme too subtle and
-    delicate for pit and gallery."), &codes);
+    delicate for pit and gallery.");
+
+    let encoded = hm.encode(sample.as_bytes()).unwrap();
     println!("{:?}", encoded);
 
-    let decoded = hm.decode(encoded, &codes);
+    let decoded = Huffman::decode(&encoded).unwrap();
+    println!("{:?}", String::from_utf8(decoded.clone()));
+    assert_eq!(decoded, sample.as_bytes());
+
+    // Arbitrary binary data: random bytes and this executable itself.
+    let random:Vec<u8> = (0..100000).map(|_| rand::thread_rng().gen_range(0..16u8)*rand::thread_rng().gen_range(0..16u8)).collect();
+    let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+
+    for data in [random, exe, vec![], vec![7u8; 1000]] {
+        let compressed = Huffman::compress(&data);
+        assert_eq!(Huffman::decompress(&compressed).unwrap(), data);
+        println!("{:?} bytes -> {:?} bytes", data.len(), compressed.len());
+    }
+
+    // Fibonacci frequencies give a maximally deep Huffman tree (one more level
+    // per symbol), package-merge has to cap it at 15 bits.
+    let mut frequencies:Vec<u64> = vec![1, 1];
+    while frequencies.len() < 40 {
+        frequencies.push(frequencies[frequencies.len()-1] + frequencies[frequencies.len()-2]);
+    }
+
+    let unlimited = get_code_lengths(&frequencies, 63);
+    let limited = get_code_lengths(&frequencies, 15);
+    let kraft:f64 = limited.iter().map(|l| 0.5f64.powi(*l as i32)).sum();
 
-    println!("{:?}", decoded);
+    let cost = |lengths:&[u8]| -> u64 { lengths.iter().zip(frequencies.iter()).map(|(l, f)| *l as u64*f).sum() };
+    println!("Max code length: unlimited = {:?}, limited = {:?}", unlimited.iter().max(), limited.iter().max());
+    println!("Total bits: unlimited = {:?}, limited = {:?}", cost(&unlimited), cost(&limited));
+    assert!(*limited.iter().max().unwrap() <= 15 && kraft == 1.0);
 }
@@ -0,0 +1,78 @@
+use std::io::{Cursor, ErrorKind};
+use algorithms::compression::stream::{compress_stream, decompress_stream, MAX_BLOCK_SIZE};
+use rand::Rng;
+
+fn compress(data:&[u8], block_size:usize) -> Vec<u8> {
+    let mut compressed:Vec<u8> = Vec::new();
+    let stats = compress_stream(&mut Cursor::new(data), &mut compressed, block_size).unwrap();
+    assert_eq!(stats.bytes_in, data.len() as u64);
+    assert_eq!(stats.bytes_out, compressed.len() as u64);
+    compressed
+}
+
+fn decompress(compressed:&[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decompressed:Vec<u8> = Vec::new();
+    let stats = decompress_stream(&mut Cursor::new(compressed), &mut decompressed)?;
+    assert_eq!(stats.bytes_in, compressed.len() as u64);
+    assert_eq!(stats.bytes_out, decompressed.len() as u64);
+    Ok(decompressed)
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+
+    let words = ["the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog"];
+    let text:Vec<u8> = (0..30000).map(|_| words[rng.gen_range(0..words.len())]).collect::<Vec<&str>>().join(" ").into_bytes();
+    let random:Vec<u8> = (0..50000).map(|_| rng.gen()).collect();
+
+    // Round trips with one block, many blocks and a partial last block.
+    for data in [&text, &random, &vec![], &vec![42u8], &vec![7u8; 100000]] {
+        for block_size in [1, 1000, 4096, 1 << 20] {
+            if block_size == 1 && data.len() > 1000 {
+                continue;
+            }
+            let compressed = compress(data, block_size);
+            assert_eq!(decompress(&compressed).unwrap(), *data);
+        }
+    }
+
+    let compressed = compress(&text, 4096);
+    println!("Text: {} bytes -> {} bytes in blocks of 4096", text.len(), compressed.len());
+    assert!(compressed.len() < text.len());
+
+    // Trailer: CRC-32 then the original length.
+    let mut bad_crc = compressed.clone();
+    let crc_at = bad_crc.len()-12;
+    bad_crc[crc_at] ^= 1;
+    let err = decompress(&bad_crc).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("CRC mismatch"));
+
+    let mut bad_len = compressed.clone();
+    let len_at = bad_len.len()-8;
+    bad_len[len_at] ^= 1;
+    assert!(decompress(&bad_len).unwrap_err().to_string().contains("Length mismatch"));
+
+    // Cut inside the first block, and right after its length field.
+    let err = decompress(&compressed[..100]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(decompress(&compressed[..8]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(decompress(&compressed[..compressed.len()-1]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // Flipped bits inside the blocks never decode to the wrong data.
+    for _ in 0..200 {
+        let mut corrupted = compressed.clone();
+        let at = rng.gen_range(8..corrupted.len()-16);
+        corrupted[at] ^= 1 << rng.gen_range(0..8);
+        assert!(decompress(&corrupted).map(|d| d != text).unwrap_or(true));
+    }
+
+    assert_eq!(decompress(b"ZIP\x01").unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decompress(b"HFZ\x09").unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Block sizes whose encoded length could not fit in the u32 length field.
+    for block_size in [0, MAX_BLOCK_SIZE+1, usize::MAX] {
+        let err = compress_stream(&mut Cursor::new(&text), &mut Vec::new(), block_size).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
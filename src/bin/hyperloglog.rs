@@ -1,120 +1,7 @@
-use fasthash::murmur3;
-use std::cmp::max;
+use algorithms::probabilistic::hyperloglog::HLL;
 use rand::{distributions::Alphanumeric, Rng};
 use rand::seq::SliceRandom;
 
-fn get_trailing_zeros(mut n:u32, num_bits:u8)->u8 {
-    let mut j:u8 = 0;
-
-    if n == 0 {
-        j = num_bits;
-    }
-        
-    else {
-        while n > 0 {
-            if (n & 1) == 1 {
-                break;
-            }
-            n = n >> 1;
-            j += 1;
-        }
-    }
-
-    return j;
-}
-
-fn hmean(vector:Vec<u32>)->f32 {
-    let mut sum:f32 = 0.0;
-    let n = vector.len();
-    for i in 0..n {
-        sum += 1.0/vector[i] as f32;
-    }
-
-    return n as f32/sum;
-}
-    
-
-struct HLL {
-    num_bits: u8,
-    p_bits: u8,
-    m: u32,
-    buckets: Vec<i16>,
-    alpha: f32,
-}
-
-impl HLL {
-    fn new(p:u8) -> Self {
-        let bsize:u32 = 1<<p;
-        let mut alp:f32 = 0.7213/(1.0 + 1.079/bsize as f32);
-
-        if bsize == 16 {
-            alp = 0.673;
-        }
-        else if bsize == 32 {
-            alp = 0.697;
-        }
-        else if bsize == 64 {
-            alp = 0.709;
-        }
-
-        Self {
-            num_bits: 31,
-            p_bits: p,
-            m: bsize,
-            buckets: vec![-1;bsize as usize],
-            alpha: alp,
-        }
-    }
-}
-
-impl HLL {
-    fn add(&mut self, data:&String) {
-        let mut hsh = murmur3::hash32_with_seed(data.as_bytes(), 42);
-        hsh = hsh & ((1<<self.num_bits)-1);
-
-        let v = self.num_bits-self.p_bits;
-        let q = hsh & ((1<<v)-1);
-        let r = hsh>>v;
-        let j = get_trailing_zeros(q, v);
-        self.buckets[r as usize] = max(self.buckets[r as usize], (j+1).into());
-    }
-}
-
-impl HLL {
-    fn get_size(&self)->u32 {
-        let u:u32 = 1 << self.num_bits;
-        let mut v:Vec<u32> = Vec::new();
-
-        for x in self.buckets.iter() {
-            v.push(u32::pow(2, *x as u32));
-        }
-
-        let n:f32 = self.alpha*self.m as f32*hmean(v);
-
-        if n <= 2.5*self.m as f32 {
-            let mut z:u32 = 0;
-            for i in 0..self.m {
-                if self.buckets[i as usize] == 0 {
-                    z += 1;
-                }
-            }
-
-            if z != 0 {
-                let q = f32::ln(self.m as f32/z as f32);
-                return self.m*q as u32;
-            }
-        }
-
-        else if n > (1.0/30.0)*u as f32 {
-            let q = -f32::ln(1.0-(n/u as f32));
-            return u*q as u32;
-        }
-        
-        return n as u32;
-    }
-}
-
-
 fn main() {
     let k = rand::thread_rng().gen_range(100..100000);
     let mut data:Vec<String> = Vec::new();
@@ -137,7 +24,58 @@ fn main() {
     }
 
     let a = hll.get_size();
-    let x:f32 = u32::abs_diff(k, a) as f32/k as f32;
+    let x:f64 = (k as f64-a).abs()/k as f64;
     println!("{:?}, {:?}, {:?}", k, a, x);
 
-}
\ No newline at end of file
+    let mut shard_1 = HLL::new(12);
+    let mut shard_2 = HLL::new(12);
+
+    for i in 0..60000u64 {
+        shard_1.add_bytes(&i.to_le_bytes());
+    }
+    for i in 40000..100000u64 {
+        shard_2.add_bytes(&i.to_le_bytes());
+    }
+
+    let restored = HLL::from_bytes(&shard_2.to_bytes()).unwrap();
+    assert_eq!(restored.to_bytes(), shard_2.to_bytes());
+
+    shard_1.merge(&restored).unwrap();
+    assert!(shard_1.merge(&HLL::new(10)).is_err());
+    println!("Merged shards = {:?}", shard_1.get_size());
+
+    let mut n:u64 = 10;
+    let mut offset:u64 = 0;
+    let mut step:u64 = 5;
+
+    while n <= 10000000 {
+        let mut classic = HLL::new(14);
+        let mut plus = HLL::new_plus(14);
+
+        for i in offset..offset+n {
+            classic.add_bytes(&i.to_le_bytes());
+            plus.add_bytes(&i.to_le_bytes());
+        }
+        offset += n;
+
+        let classic_err = (classic.get_size()-n as f64).abs()/n as f64;
+        let plus_err = (plus.get_size()-n as f64).abs()/n as f64;
+        let (lo, hi) = plus.get_bounds(3.0);
+
+        println!("n = {:?}, classic error = {:.4}, HLL++ error = {:.4}, HLL++ sparse = {:?}, bounds = ({:.1}, {:.1})", n, classic_err, plus_err, plus.is_sparse(), lo, hi);
+        assert!(plus_err <= 3.0*plus.standard_error(), "HLL++ estimate outside of 3 standard errors for n = {}", n);
+
+        let restored = HLL::from_bytes(&plus.to_bytes()).unwrap();
+        assert_eq!(restored.get_size(), plus.get_size());
+
+        n *= step;
+        step = if step == 5 { 2 } else { 5 };
+    }
+
+    // Corrupt sparse headers are rejected instead of trusted.
+    let header:Vec<u8> = [b"HLL".as_slice(), &[2, 14, 3]].concat();
+    assert!(HLL::from_bytes(&[header.as_slice(), &u32::MAX.to_le_bytes(), &[1, 2, 3]].concat()).is_err());
+    assert!(HLL::from_bytes(&[header.as_slice(), &1u32.to_le_bytes(), &[1]].concat()).is_ok());
+    assert!(HLL::from_bytes(&[header.as_slice(), &1u32.to_le_bytes(), &[64]].concat()).is_err());
+    assert!(HLL::from_bytes(&[header.as_slice(), &1u32.to_le_bytes(), &[41]].concat()).is_err());
+}
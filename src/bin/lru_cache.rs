@@ -1,283 +1,197 @@
 use std::collections::HashMap;
-use std::hash::Hash;
 use std::fmt::Debug;
-
-pub trait HashKeyTrait: Eq + Hash + PartialOrd + Debug + Copy {}
-pub trait MyTrait: PartialOrd + Debug + Copy {}
-
-impl MyTrait for i8 {}
-impl MyTrait for i16 {}
-impl MyTrait for i32 {}
-impl MyTrait for i64 {}
-impl MyTrait for isize {}
-
-impl MyTrait for u8 {}
-impl MyTrait for u16 {}
-impl MyTrait for u32 {}
-impl MyTrait for u64 {}
-impl MyTrait for usize {}
-
-impl MyTrait for f32 {}
-impl MyTrait for f64 {}
-
-impl MyTrait for &str {}
-
-impl HashKeyTrait for i8 {}
-impl HashKeyTrait for i16 {}
-impl HashKeyTrait for i32 {}
-impl HashKeyTrait for i64 {}
-impl HashKeyTrait for isize {}
-
-impl HashKeyTrait for u8 {}
-impl HashKeyTrait for u16 {}
-impl HashKeyTrait for u32 {}
-impl HashKeyTrait for u64 {}
-impl HashKeyTrait for usize {}
-
-impl HashKeyTrait for &str {}
-
-struct LRUCache<S, T> where S: HashKeyTrait, T: MyTrait {
-    max_size: usize,
-    key_head: Option<S>,
-    key_tail: Option<S>,
-    node_map: HashMap<S, Node<S, T>>,
-    curr_size: usize,
-}
-
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn new(len:usize) -> Self {
-        Self {
-            max_size: len,
-            key_head: None,
-            key_tail: None,
-            node_map: HashMap::new(),
-            curr_size: 0
-        }
-    }
-}
-
-struct Node<S, T> where S: HashKeyTrait, T: MyTrait {
-    key: S,
-    val: T,
-    prev_key: Option<S>,
-    next_key: Option<S>,
-}
-
-impl<S:HashKeyTrait, T: MyTrait> Node<S, T> {
-    fn new(&key: &S, &val:&T) -> Self {
-        Self {
-            key,
-            val,
-            prev_key: None,
-            next_key: None,
-        }
-    }
-}
-
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn add(&mut self, &key:&S,  &val:&T) {
-        if self.curr_size == self.max_size {
-            match self.key_head {
-                Some(x) => {
-                    self.delete(&x);
-                }
-                None => {}
-            }
-        }
-
-        match self.key_tail {
-            Some(x) => {
-                let node_tail = self.node_map.get(&x);
-                match node_tail {
-                    Some(_y) => {
-                        self.node_map.entry(x).and_modify(|mynode| mynode.next_key = Some(key));
-                        
-                        let new_node = Node {key, val, prev_key: Some(x), next_key:None};
-                        self.node_map.insert(key, new_node);
-                        self.curr_size += 1;
-                        self.key_tail = Some(key);
-                    }
-                    None => {}
-                }
-            }
-            None => {
-                let new_node = Node::new(&key, &val);
-                self.node_map.insert(key, new_node);
-                self.curr_size += 1;
-
-                self.key_head = Some(key);
-                self.key_tail = Some(key);
-            }
-        }
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use algorithms::cache::lru::{EvictionReason, LRUCache};
+use algorithms::cache::ManualClock;
+use rand::Rng;
+
+fn print_cache<K: Hash + Eq + Debug, V: Debug>(cache:&LRUCache<K, V>) {
+    for (k, v) in cache.iter() {
+        println!("{:?}, {:?}", k, v);
     }
+    println!();
 }
 
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn get(&mut self, &key:&S) -> Option<T> {
-        let output:Option<T>;
-
-        match self.node_map.get(&key) {
-            Some(x) => {
-                output = Some(x.val);
+fn main() {
+    let mut cache:LRUCache<String, Vec<i32>> = LRUCache::new(3);
+    cache.put("a".to_string(), vec![1]);
+    cache.put("b".to_string(), vec![2]);
+    cache.put("c".to_string(), vec![3]);
+    print_cache(&cache);
+
+    // Replacing a value hands back the old one.
+    assert_eq!(cache.put("a".to_string(), vec![4]), vec![("a".to_string(), vec![1])]);
+    print_cache(&cache);
+
+    // "b" is now the least recently used and gets evicted.
+    assert_eq!(cache.put("d".to_string(), vec![4]), vec![("b".to_string(), vec![2])]);
+    assert_eq!(cache.put("e".to_string(), vec![5]), vec![("c".to_string(), vec![3])]);
+    print_cache(&cache);
+
+    // peek leaves the order alone, get moves the key to the front.
+    assert_eq!(cache.peek("a"), Some(&vec![4]));
+    assert_eq!(cache.peek_lru(), Some((&"a".to_string(), &vec![4])));
+    assert_eq!(cache.get("a"), Some(&vec![4]));
+    assert_eq!(cache.peek_lru(), Some((&"d".to_string(), &vec![4])));
+
+    cache.get_mut("e").unwrap().push(8);
+    assert_eq!(cache.get("f"), None);
+    print_cache(&cache);
+
+    let order:Vec<&str> = cache.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(order, vec!["e", "a", "d"]);
+
+    assert_eq!(cache.resize(1), vec![("d".to_string(), vec![4]), ("a".to_string(), vec![4])]);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.remove("e"), Some(vec![5, 8]));
+    assert!(cache.is_empty());
+
+    // Compare against a plain vector kept in recency order.
+    let mut cache:LRUCache<u32, u32> = LRUCache::new(50);
+    let mut model:Vec<(u32, u32)> = Vec::new();
+    let mut rng = rand::thread_rng();
+    let (mut hits, mut misses):(u64, u64) = (0, 0);
+
+    for i in 0..100000 {
+        let key = rng.gen_range(0..100);
+        match rng.gen_range(0..4) {
+            0 => {
+                let evicted = cache.put(key, i);
+                let expected:Vec<(u32, u32)> = match model.iter().position(|(k, _)| *k == key) {
+                    Some(p) => vec![model.remove(p)],
+                    None if model.len() == 50 => vec![model.pop().unwrap()],
+                    None => vec![],
+                };
+                model.insert(0, (key, i));
+                assert_eq!(evicted, expected);
             }
-            None => {
-                output = None;
+            1 => {
+                let expected = model.iter().position(|(k, _)| *k == key).map(|p| model.remove(p));
+                assert_eq!(cache.remove(&key), expected.map(|(_, v)| v));
             }
-        }
-
-        self.delete(&key);
-
-        match output {
-            Some(v) => {
-                self.add(&key, &v);
+            2 => {
+                let expected = model.iter().position(|(k, _)| *k == key).map(|p| {
+                    let e = model.remove(p);
+                    model.insert(0, e);
+                    e.1
+                });
+                if expected.is_some() { hits += 1 } else { misses += 1 }
+                assert_eq!(cache.get(&key).copied(), expected);
             }
-            None => {}
-        }
-
-        return output;
-    }
-}
-
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn delete(&mut self, &key: &S) {
-        match self.node_map.get(&key) {
-            Some(node) => {
-                match node.prev_key {
-                    Some(x) => {
-                        match node.next_key {
-                            Some(y) => {
-                                let next_node = self.node_map.get(&y);
-                                match next_node {
-                                    Some(_y1) => {
-                                        self.node_map.entry(y).and_modify(|mynode| mynode.prev_key = Some(x));
-                                    }
-                                    None => {}
-                                }
-
-                                let prev_node = self.node_map.get(&x);
-                                match prev_node {
-                                    Some(_x1) => {
-                                        self.node_map.entry(x).and_modify(|mynode| mynode.next_key = Some(y));
-                                    }
-                                    None => {}
-                                }
-                            }
-                            None => {
-                                let prev_node = self.node_map.entry(x);
-                                prev_node.and_modify(|mynode| mynode.next_key = None);
-                                
-                                let prev_node = self.node_map.get(&x);
-                                match prev_node {
-                                    Some(x1) => {
-                                        self.key_tail = Some(x1.key);
-                                    }
-                                    None => {}
-                                }
-                            }
-                        }
-                    }
-                    None => {
-                        match node.next_key {
-                            Some(y) => {
-                                let next_node = self.node_map.entry(y);
-                                next_node.and_modify(|mynode| mynode.prev_key = None);
-                                
-                                let next_node = self.node_map.get(&y);
-                                match next_node {
-                                    Some(y1) => {
-                                        self.key_head = Some(y1.key);
-                                    }
-                                    None => {}
-                                }
-                            }
-                            None => {}
-                        }
-                    }
+            _ => {
+                let capacity = rng.gen_range(40..=50);
+                if capacity < cache.capacity() {
+                    let evicted = cache.resize(capacity);
+                    let expected:Vec<(u32, u32)> = model.drain(capacity.min(model.len())..).rev().collect();
+                    assert_eq!(evicted, expected);
+                    cache.resize(50);
                 }
-
-                self.node_map.remove(&key);
-                self.curr_size -= 1;
-                
             }
-            None => {}
         }
-    }
-}
 
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn update(&mut self, &key:&S, &val:&T) {
-        self.delete(&key);
-        self.add(&key, &val);
+        let items:Vec<(u32, u32)> = cache.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(items, model);
     }
+    assert_eq!((cache.stats().hits, cache.stats().misses), (hits, misses));
+    println!("{:?} entries after random operations, hit rate {:.3}", cache.len(), cache.stats().hit_rate());
+
+    // An HTTP response cache: capacity in bytes and a time to live per entry,
+    // driven by a manual clock instead of sleeping.
+    let clock = ManualClock::new();
+    let mut responses:LRUCache<String, Vec<u8>> = LRUCache::new(1000)
+        .with_weigher(|url:&String, body:&Vec<u8>| url.len() + body.len())
+        .with_clock(Arc::new(clock.clone()))
+        .with_ttl(Duration::from_secs(60));
+
+    responses.put("/index".to_string(), vec![0; 494]);
+    responses.put_with_ttl("/news".to_string(), vec![0; 295], Duration::from_secs(10));
+    assert_eq!(responses.size(), 800);
+
+    // 300 more bytes only fit once the least recently used page is gone.
+    let evicted = responses.put("/about".to_string(), vec![0; 294]);
+    assert_eq!(evicted.iter().map(|(k, _)| k.as_str()).collect::<Vec<&str>>(), vec!["/index"]);
+    assert_eq!(responses.size(), 600);
+
+    // Too big to ever fit: handed straight back.
+    let rejected = responses.put("/video".to_string(), vec![0; 5000]);
+    assert_eq!(rejected.len(), 1);
+    assert!(!responses.contains("/video"));
+
+    clock.advance(Duration::from_secs(11));
+    assert!(responses.peek("/news").is_none());
+    assert!(responses.contains("/about"));
+    assert_eq!(responses.len(), 2);
+    assert!(responses.get("/news").is_none());
+    assert_eq!(responses.len(), 1);
+
+    clock.advance(Duration::from_secs(50));
+    assert_eq!(responses.iter().count(), 0);
+    let purged = responses.purge_expired();
+    assert_eq!(purged.len(), 1);
+    assert!(responses.is_empty() && responses.size() == 0);
+    println!("Purged {:?} expired responses", purged.len());
+
+    // Expired entries are invisible to remove and the LRU end, and get dropped.
+    responses.put_with_ttl("/old".to_string(), vec![0; 96], Duration::from_secs(5));
+    responses.put_with_ttl("/stale".to_string(), vec![0; 94], Duration::from_secs(5));
+    responses.put("/fresh".to_string(), vec![0; 94]);
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(responses.peek_lru().map(|(k, _)| k.as_str()), Some("/fresh"));
+    assert_eq!(responses.remove("/stale"), None);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses.pop_lru().map(|(k, _)| k), Some("/fresh".to_string()));
+    assert!(responses.is_empty() && responses.size() == 0);
+    assert_eq!(responses.pop_lru(), None);
+    assert_eq!(responses.peek_lru(), None);
+    assert_eq!(responses.stats().expirations, 4);
+
+    // A write-back cache in front of a slow store: writes stay in the cache
+    // and reach the store only when evicted, expired or flushed.
+    let store:Arc<Mutex<HashMap<u32, String>>> = Arc::new(Mutex::new(HashMap::new()));
+    let events:Arc<Mutex<Vec<(u32, EvictionReason)>>> = Arc::new(Mutex::new(Vec::new()));
+    let (sink_store, listener_events) = (store.clone(), events.clone());
+
+    let mut cache:LRUCache<u32, String> = LRUCache::new(2)
+        .with_clock(Arc::new(clock.clone()))
+        .with_eviction_listener(move |k:&u32, _:&String, reason| listener_events.lock().unwrap().push((*k, reason)))
+        .with_write_back(move |k:&u32, v:&String| { sink_store.lock().unwrap().insert(*k, v.clone()); });
+
+    cache.put_clean(1, "loaded".to_string());
+    cache.put(2, "written".to_string());
+    assert!(!cache.is_dirty(&1) && cache.is_dirty(&2));
+    cache.put(2, "written twice".to_string());
+    assert!(store.lock().unwrap().is_empty());
+
+    // Evicting the clean entry writes nothing, the dirty one reaches the store.
+    cache.put(3, "new".to_string());
+    assert!(store.lock().unwrap().is_empty());
+    cache.get_mut(&3).unwrap().push('!');
+    cache.put_with_ttl(4, "short lived".to_string(), Duration::from_secs(1));
+    assert_eq!(store.lock().unwrap().get(&2).map(|v| v.as_str()), Some("written twice"));
+
+    clock.advance(Duration::from_secs(2));
+    assert!(cache.get(&4).is_none());
+    assert_eq!(cache.flush(), 1);
+    assert_eq!(cache.flush(), 0);
+    assert_eq!(cache.remove(&3), Some("new!".to_string()));
+
+    let store = store.lock().unwrap();
+    assert_eq!(store.len(), 3);
+    assert_eq!(store[&3], "new!");
+    assert_eq!(store[&4], "short lived");
+
+    assert_eq!(*events.lock().unwrap(), vec![
+        (2, EvictionReason::Replaced),
+        (1, EvictionReason::Evicted),
+        (2, EvictionReason::Evicted),
+        (4, EvictionReason::Expired),
+        (3, EvictionReason::Removed),
+    ]);
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.inserts), (1, 1, 5));
+    assert_eq!((stats.evictions, stats.expirations, stats.write_backs), (2, 1, 3));
+    println!("{:?}", stats);
 }
-
-impl<S:HashKeyTrait, T: MyTrait> LRUCache<S, T> {
-    fn print_cache(&self, head:Option<&Node<S, T>>) {
-        match head {
-            Some(x) => {
-                println!("{:?}, {:?}", x.key, x.val);
-                match x.next_key {
-                    Some(y) => {
-                        self.print_cache(self.node_map.get(&y));
-                    }
-                    None => {}
-                }
-            }
-            None => {
-                match self.key_head {
-                    Some(x) => {
-                        self.print_cache(self.node_map.get(&x));
-                    }
-                    None => {}
-                }
-            }
-        }
-    }
-}
-
-fn main() {
-    let mut cache:LRUCache<&str, i32> = LRUCache::new(3);
-    cache.add(&"a", &1);
-    cache.print_cache(None);
-    println!();
-
-    cache.add(&"b", &2);
-    cache.print_cache(None);
-    println!();
-
-    cache.add(&"c", &3);
-    cache.print_cache(None);
-    println!();
-
-    cache.update(&"a", &4);
-    cache.print_cache(None);
-    println!();
-
-    cache.add(&"d", &4);
-    cache.print_cache(None);
-    println!();
-
-    cache.add(&"e", &5);
-    cache.print_cache(None);
-    println!();
-
-    cache.add(&"f", &6);
-    cache.print_cache(None);
-    println!();
-
-    cache.update(&"e", &8);
-    cache.print_cache(None);
-    println!();
-
-    match cache.get(&"f") {
-        Some(x) => {
-            println!("{:?}", x);
-        }
-        None => {
-            println!("Non existing key!!!");
-        }
-        
-    }
-    cache.print_cache(None);
-    println!();
-}
\ No newline at end of file
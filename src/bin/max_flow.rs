@@ -0,0 +1,173 @@
+use algorithms::graph::flow::FlowNetwork;
+use algorithms::graph::Graph;
+use rand::Rng;
+
+// Capacities are respected and every node but s and t passes on what it gets.
+fn check_flow(network:&FlowNetwork<i64>, s:usize, t:usize, value:i64) {
+    let mut balance:Vec<i64> = vec![0; network.num_nodes()];
+    for e in 0..network.num_edges() {
+        let (u, v) = network.endpoints(e);
+        let f = network.flow(e);
+        assert!(f >= 0 && f <= network.capacity(e));
+        balance[u] -= f;
+        balance[v] += f;
+    }
+
+    for (u, b) in balance.iter().enumerate() {
+        if u == s {
+            assert_eq!(*b, -value);
+        }
+        else if u == t {
+            assert_eq!(*b, value);
+        }
+        else {
+            assert_eq!(*b, 0);
+        }
+    }
+}
+
+// Smallest cut over all node sets holding s but not t, for tiny networks.
+fn get_brute_force_cut(network:&FlowNetwork<i64>, s:usize, t:usize) -> i64 {
+    let mut best = i64::MAX;
+    for mask in 0u32..(1 << network.num_nodes()) {
+        if mask & (1 << s) == 0 || mask & (1 << t) != 0 {
+            continue;
+        }
+        let value:i64 = (0..network.num_edges()).filter(|e| {
+            let (u, v) = network.endpoints(*e);
+            mask & (1 << u) != 0 && mask & (1 << v) == 0
+        }).map(|e| network.capacity(e)).sum();
+        best = best.min(value);
+    }
+    best
+}
+
+// Cheapest way to give every worker one job, over all permutations.
+fn get_brute_force_assignment(costs:&[Vec<i64>], worker:usize, taken:&mut Vec<bool>) -> i64 {
+    if worker == costs.len() {
+        return 0;
+    }
+
+    let mut best = i64::MAX;
+    for job in 0..costs.len() {
+        if !taken[job] {
+            taken[job] = true;
+            best = best.min(costs[worker][job] + get_brute_force_assignment(costs, worker+1, taken));
+            taken[job] = false;
+        }
+    }
+    best
+}
+
+fn main() {
+    // Water mains between pumping stations, capacities in litres per second.
+    let mut mains:Graph<&str, i64> = Graph::directed();
+    mains.add_edge("Reservoir", "North", 16);
+    mains.add_edge("Reservoir", "South", 13);
+    mains.add_edge("North", "East", 12);
+    mains.add_edge("South", "North", 4);
+    mains.add_edge("South", "West", 14);
+    mains.add_edge("East", "South", 9);
+    mains.add_edge("East", "City", 20);
+    mains.add_edge("West", "East", 7);
+    mains.add_edge("West", "City", 4);
+
+    let (s, t) = (mains.id("Reservoir").unwrap(), mains.id("City").unwrap());
+    let mut network = FlowNetwork::from_graph(&mains);
+
+    let by_dinic = network.dinic(s, t);
+    check_flow(&network, s, t, by_dinic);
+    let by_push_relabel = network.push_relabel(s, t);
+    check_flow(&network, s, t, by_push_relabel);
+    println!("Max flow into the city = {}", by_dinic);
+    assert_eq!(by_dinic, 23);
+    assert_eq!(by_push_relabel, 23);
+
+    let cut = network.min_cut(s, t);
+    let pipes:Vec<(&str, &str)> = cut.edges.iter().map(|e| {
+        let edge = mains.edge(*e);
+        (*mains.key(edge.from), *mains.key(edge.to))
+    }).collect();
+    println!("Bottleneck = {:?}", pipes);
+    assert_eq!(cut.value, 23);
+    assert_eq!(cut.edges.iter().map(|e| network.capacity(*e)).sum::<i64>(), 23);
+    assert_eq!(pipes, vec![("North", "East"), ("West", "East"), ("West", "City")]);
+    assert!(cut.source_side.contains(&s) && !cut.source_side.contains(&t));
+
+    // Random networks with parallel edges, self loops and dead ends.
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let n = rng.gen_range(2..9);
+        let mut network:FlowNetwork<i64> = FlowNetwork::new(n);
+        for _ in 0..rng.gen_range(0..4*n) {
+            network.add_edge(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(0..20));
+        }
+        let s = rng.gen_range(0..n);
+        let t = (s + rng.gen_range(1..n)) % n;
+
+        let by_dinic = network.dinic(s, t);
+        check_flow(&network, s, t, by_dinic);
+        let by_push_relabel = network.push_relabel(s, t);
+        check_flow(&network, s, t, by_push_relabel);
+        assert_eq!(by_dinic, by_push_relabel);
+        assert_eq!(by_dinic, get_brute_force_cut(&network, s, t));
+
+        let cut = network.min_cut(s, t);
+        assert_eq!(cut.value, by_dinic);
+        assert_eq!(cut.edges.iter().map(|e| network.capacity(*e)).sum::<i64>(), by_dinic);
+
+        let (flow, _) = network.min_cost_max_flow(s, t).unwrap();
+        assert_eq!(flow, by_dinic);
+        check_flow(&network, s, t, flow);
+    }
+
+    // Assignment problems as min-cost flow, some costs are negative.
+    for _ in 0..300 {
+        let n = rng.gen_range(1..7);
+        let costs:Vec<Vec<i64>> = (0..n).map(|_| (0..n).map(|_| rng.gen_range(-10..30)).collect()).collect();
+
+        let (source, sink) = (2*n, 2*n+1);
+        let mut network:FlowNetwork<i64> = FlowNetwork::new(2*n+2);
+        for (worker, row) in costs.iter().enumerate() {
+            network.add_edge(source, worker, 1);
+            network.add_edge(n+worker, sink, 1);
+            for (job, c) in row.iter().enumerate() {
+                network.add_edge_with_cost(worker, n+job, 1, *c);
+            }
+        }
+
+        let (flow, cost) = network.min_cost_max_flow(source, sink).unwrap();
+        check_flow(&network, source, sink, flow);
+        assert_eq!(flow, n as i64);
+        assert_eq!(cost, get_brute_force_assignment(&costs, 0, &mut vec![false; n]));
+        assert_eq!(cost, (0..network.num_edges()).map(|e| network.flow(e)*network.cost(e)).sum::<i64>());
+    }
+
+    // A loop that pays for every unit sent around it has no cheapest flow.
+    let mut network:FlowNetwork<i64> = FlowNetwork::new(4);
+    network.add_edge_with_cost(0, 1, 5, 2);
+    network.add_edge_with_cost(1, 2, 5, -4);
+    network.add_edge_with_cost(2, 1, 5, 1);
+    network.add_edge_with_cost(2, 3, 5, 2);
+    let mut cycle = network.min_cost_max_flow(0, 3).unwrap_err();
+    cycle.sort();
+    assert_eq!(cycle, vec![1, 2]);
+
+    // Flows up to the largest weight, whose total capacity at the source
+    // just fits in the type.
+    let mut network:FlowNetwork<u8> = FlowNetwork::new(3);
+    network.add_edge(0, 1, 200);
+    network.add_edge(0, 1, 55);
+    network.add_edge(1, 2, 255);
+    assert_eq!(network.dinic(0, 2), 255);
+    assert_eq!(network.push_relabel(0, 2), 255);
+    assert_eq!(network.min_cut(0, 2).value, 255);
+
+    let mut network:FlowNetwork<i8> = FlowNetwork::new(3);
+    network.add_edge_with_cost(0, 1, 3, 5);
+    network.add_edge_with_cost(1, 2, 3, -2);
+    network.add_edge_with_cost(0, 2, 1, 10);
+    assert_eq!(network.min_cost_max_flow(0, 2), Ok((4, 19)));
+
+    println!("Dinic, push-relabel and min cut agree on 1000 random networks");
+}
@@ -0,0 +1,83 @@
+use algorithms::probabilistic::minhash::{LshIndex, MinHash};
+use std::collections::HashSet;
+use rand::Rng;
+
+fn exact_jaccard(a:&HashSet<String>, b:&HashSet<String>) -> f64 {
+    let inter = a.intersection(b).count();
+    let union = a.union(b).count();
+    inter as f64/union as f64
+}
+
+fn random_set(size:usize) -> HashSet<String> {
+    let mut out:HashSet<String> = HashSet::new();
+    while out.len() < size {
+        out.insert(format!("token-{}", rand::thread_rng().gen_range(0..10000000)));
+    }
+    out
+}
+
+fn mutate(set:&HashSet<String>, fraction:f64) -> HashSet<String> {
+    let mut out:HashSet<String> = HashSet::new();
+    for s in set.iter() {
+        if rand::thread_rng().gen_range(0.0..1.0) < fraction {
+            out.insert(format!("token-{}", rand::thread_rng().gen_range(0..10000000)));
+        }
+        else {
+            out.insert(s.clone());
+        }
+    }
+    out
+}
+
+fn main() {
+    let num_perm:usize = 128;
+
+    // Pairs with varying overlap, the estimate should be within 4.5 standard
+    // deviations sqrt(J(1-J)/k) of the exact Jaccard similarity, wide enough
+    // that six unseeded checks practically never fail by chance.
+    for fraction in [0.0, 0.1, 0.3, 0.5, 0.8, 1.0] {
+        let a = random_set(2000);
+        let b = mutate(&a, fraction);
+
+        let exact = exact_jaccard(&a, &b);
+        let estimate = MinHash::from_items(num_perm, a.iter()).jaccard(&MinHash::from_items(num_perm, b.iter())).unwrap();
+        let tolerance = 4.5*(exact*(1.0-exact)/num_perm as f64).sqrt() + 0.01;
+
+        println!("exact = {:.4}, estimate = {:.4}, tolerance = {:.4}", exact, estimate, tolerance);
+        assert!((exact-estimate).abs() <= tolerance);
+    }
+
+    let mut docs:Vec<HashSet<String>> = Vec::new();
+    for _ in 0..100 {
+        let doc = random_set(500);
+        docs.push(mutate(&doc, 0.05));
+        docs.push(doc);
+    }
+
+    // With the threshold at 0.7 (16 bands of 8 rows) a pair at 0.8 is missed
+    // 5% of the time. At 0.5 (32 bands of 4 rows) that drops below 1e-7, so
+    // every near duplicate must be a candidate.
+    let mut index:LshIndex<usize> = LshIndex::with_threshold(num_perm, 0.5);
+    let mut signatures:Vec<MinHash> = Vec::new();
+
+    for (i, doc) in docs.iter().enumerate() {
+        let minhash = MinHash::from_items(num_perm, doc.iter());
+        index.insert(i, &minhash).unwrap();
+        signatures.push(minhash);
+    }
+
+    let candidates:HashSet<(usize, usize)> = index.candidate_pairs().into_iter().collect();
+    let mut true_pairs:usize = 0;
+
+    for i in 0..docs.len() {
+        for j in i+1..docs.len() {
+            if exact_jaccard(&docs[i], &docs[j]) >= 0.8 {
+                true_pairs += 1;
+                assert!(candidates.contains(&(i, j)), "near duplicate pair ({}, {}) missed", i, j);
+            }
+        }
+    }
+
+    println!("Near duplicate pairs = {:?}, candidate pairs = {:?}", true_pairs, candidates.len());
+    println!("Candidates for doc 0 = {:?}", index.query(&signatures[0]).unwrap());
+}
@@ -0,0 +1,93 @@
+use std::time::Instant;
+use algorithms::compression::get_frequencies;
+use algorithms::compression::huffman::Huffman;
+use algorithms::compression::range_coder::{RangeCoder, MAX_DECODED_LEN};
+use rand::Rng;
+
+// Order-0 entropy of the data in bytes, the bound for both coders.
+fn get_entropy_bytes(data:&[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let n = data.len() as f64;
+    let bits:f64 = get_frequencies(data).iter().filter(|f| **f > 0).map(|f| *f as f64*(n/(*f as f64)).log2()).sum();
+    bits/8.0
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let n:usize = 1000000;
+
+    // Heavily skewed: one symbol with probability 0.95, where Huffman still
+    // spends a whole bit on it.
+    let skewed:Vec<u8> = (0..n).map(|_| if rng.gen_bool(0.95) { b'a' } else { rng.gen_range(b'b'..=b'e') }).collect();
+    let geometric:Vec<u8> = (0..n).map(|_| {
+        let mut x:u8 = 0;
+        while x < 255 && rng.gen_bool(0.7) {
+            x += 1;
+        }
+        x
+    }).collect();
+    let uniform:Vec<u8> = (0..n).map(|_| rng.gen()).collect();
+    let binary:Vec<u8> = (0..n).map(|_| if rng.gen_bool(0.99) { 0 } else { 1 }).collect();
+    let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+
+    // The first half and the second half use different alphabets, which only
+    // the adaptive model can follow.
+    let shifting:Vec<u8> = (0..n).map(|i| if i < n/2 { rng.gen_range(0..16) } else { rng.gen_range(16..32) }).collect();
+
+    let inputs:Vec<(&str, Vec<u8>)> = vec![
+        ("skewed", skewed),
+        ("geometric", geometric),
+        ("uniform", uniform),
+        ("binary", binary),
+        ("shifting", shifting),
+        ("executable", exe),
+        ("empty", vec![]),
+        ("single", vec![9u8; 100000]),
+    ];
+
+    println!("{:>12} {:>10} {:>10} {:>10} {:>10} {:>10}", "input", "size", "entropy", "huffman", "static", "adaptive");
+
+    for (name, data) in inputs.iter() {
+        let start = Instant::now();
+        let huffman = Huffman::compress(data);
+        let huffman_time = start.elapsed();
+
+        let start = Instant::now();
+        let range_static = RangeCoder::compress(data);
+        let static_time = start.elapsed();
+
+        let start = Instant::now();
+        let range_adaptive = RangeCoder::new_adaptive().encode(data);
+        let adaptive_time = start.elapsed();
+
+        assert_eq!(Huffman::decompress(&huffman).unwrap(), *data);
+        assert_eq!(RangeCoder::decompress(&range_static).unwrap(), *data);
+        assert_eq!(RangeCoder::decompress(&range_adaptive).unwrap(), *data);
+
+        println!("{:>12} {:>10} {:>10.0} {:>10} {:>10} {:>10}", name, data.len(), get_entropy_bytes(data), huffman.len(), range_static.len(), range_adaptive.len());
+        println!("{:>12} {:>10} {:>10} {:>10.1?} {:>10.1?} {:>10.1?}", "", "", "", huffman_time, static_time, adaptive_time);
+    }
+
+    // On a skewed source the range coder gets within a few percent of the
+    // entropy while Huffman cannot go below one bit per symbol.
+    let skewed = &inputs[0].1;
+    let entropy = get_entropy_bytes(skewed);
+    assert!((RangeCoder::compress(skewed).len() as f64) < 1.05*entropy);
+    assert!((Huffman::compress(skewed).len() as f64) > 1.5*entropy);
+
+    let encoded = RangeCoder::compress(skewed);
+    assert!(RangeCoder::decompress(&encoded[..encoded.len()/2]).is_err());
+
+    // A one symbol table codes any length in no bytes at all, so the length
+    // in the header is checked against a limit before decoding.
+    let mut encoded = RangeCoder::compress(&[7u8; 100]);
+    assert_eq!(RangeCoder::decode_with_limit(&encoded, 100).unwrap(), vec![7u8; 100]);
+    assert!(RangeCoder::decode_with_limit(&encoded, 99).is_err());
+    for n in [MAX_DECODED_LEN as u64 + 1, 1 << 32, u64::MAX] {
+        encoded[5..13].copy_from_slice(&n.to_le_bytes());
+        assert!(RangeCoder::decompress(&encoded).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use algorithms::cache::lru::LRUCache;
+use algorithms::cache::sharded::ShardedLRUCache;
+use rand::Rng;
+
+const THREADS:usize = 16;
+
+fn get_value(key:u64) -> u64 {
+    key.wrapping_mul(0x9E3779B97F4A7C15)
+}
+
+// Random gets and puts from many threads, every value seen must belong to its
+// key and the cache must never grow past its capacity.
+fn check_random_ops(cache:&ShardedLRUCache<u64, u64>) {
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let mut rng = rand::thread_rng();
+                for _ in 0..20000 {
+                    let key:u64 = rng.gen_range(0..2000);
+                    match rng.gen_range(0..4) {
+                        0 => { cache.put(key, get_value(key)); },
+                        1 => { cache.remove(&key); },
+                        _ => {
+                            if let Some(v) = cache.get(&key) {
+                                assert_eq!(v, get_value(key));
+                            }
+                        },
+                    }
+                }
+            });
+        }
+
+        s.spawn(|| {
+            for _ in 0..200 {
+                assert!(cache.len() <= cache.capacity());
+                thread::yield_now();
+            }
+        });
+    });
+
+    assert!(cache.len() <= cache.capacity());
+    println!("random ops: {} threads, {} entries left (capacity {})", THREADS, cache.len(), cache.capacity());
+}
+
+// All threads ask for the same keys at once, each key must be loaded once.
+fn check_loader_dedup() {
+    let cache:ShardedLRUCache<u64, u64> = ShardedLRUCache::with_shards(1000, 8);
+    let loads:Mutex<HashMap<u64, usize>> = Mutex::new(HashMap::new());
+    let barrier = Barrier::new(THREADS);
+
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                barrier.wait();
+                for key in 0..100 {
+                    let v = cache.get_or_insert_with(key, || {
+                        *loads.lock().unwrap().entry(key).or_insert(0) += 1;
+                        thread::sleep(Duration::from_micros(200));
+                        get_value(key)
+                    });
+                    assert_eq!(v, get_value(key));
+                }
+            });
+        }
+    });
+
+    let loads = loads.into_inner().unwrap();
+    assert_eq!(loads.len(), 100);
+    assert!(loads.values().all(|n| *n == 1));
+    println!("loader dedup: {} threads, {} loads for 100 keys", THREADS, loads.values().sum::<usize>());
+}
+
+// A panicking loader must not leave the other callers waiting forever.
+fn check_loader_panic() {
+    let cache:ShardedLRUCache<u64, u64> = ShardedLRUCache::with_shards(100, 4);
+    let attempts = AtomicUsize::new(0);
+    let barrier = Barrier::new(THREADS);
+
+    panic::set_hook(Box::new(|_| {}));
+    let results:Vec<thread::Result<u64>> = thread::scope(|s| {
+        let handles:Vec<_> = (0..THREADS).map(|_| s.spawn(|| {
+            barrier.wait();
+            cache.get_or_insert_with(7, || {
+                thread::sleep(Duration::from_millis(5));
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("first load fails");
+                }
+                get_value(7)
+            })
+        })).collect();
+        handles.into_iter().map(|h| h.join()).collect()
+    });
+    let _ = panic::take_hook();
+
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    assert_eq!(failed, 1);
+    assert!(results.iter().flatten().all(|v| *v == get_value(7)));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    println!("loader panic: {} caller failed, {} loads in total", failed, attempts.load(Ordering::SeqCst));
+}
+
+// Every shard evicts on its own, so the least recently used keys overall go
+// first only approximately. Recently used keys should still survive.
+fn check_approximate_lru() {
+    let cache:ShardedLRUCache<u64, u64> = ShardedLRUCache::with_shards(1024, 16);
+    for key in 0..1024 {
+        cache.put(key, get_value(key));
+    }
+    for key in 0..256 {
+        cache.get(&key);
+    }
+
+    // Shards fill unevenly, so compare survival among the keys still present.
+    let hot:Vec<u64> = (0..256).filter(|k| cache.contains(k)).collect();
+    let cold:Vec<u64> = (256..1024).filter(|k| cache.contains(k)).collect();
+    for key in 1024..1536 {
+        cache.put(key, get_value(key));
+    }
+
+    let hot_kept = hot.iter().filter(|k| cache.contains(k)).count() as f64/hot.len() as f64;
+    let cold_kept = cold.iter().filter(|k| cache.contains(k)).count() as f64/cold.len() as f64;
+    assert!(cache.len() <= cache.capacity());
+    assert!(hot_kept > 0.95 && cold_kept < 0.7);
+    println!("approximate LRU: {:.1}% of recently used and {:.1}% of older keys kept", 100.0*hot_kept, 100.0*cold_kept);
+}
+
+fn run_zipf_threads(get:&(dyn Fn(u64) -> u64 + Sync)) -> Duration {
+    let start = Instant::now();
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                let mut rng = rand::thread_rng();
+                for _ in 0..50000 {
+                    let key = (1.0/rng.gen::<f64>().max(1e-9)) as u64 % 100000;
+                    assert_eq!(get(key), get_value(key));
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+// Throughput against one LRUCache behind a single lock.
+fn compare_single_lock() {
+    let single:Mutex<LRUCache<u64, u64>> = Mutex::new(LRUCache::new(10000));
+    let single_time = run_zipf_threads(&|key| {
+        let mut cache = single.lock().unwrap();
+        if let Some(v) = cache.get(&key) {
+            return *v;
+        }
+        cache.put(key, get_value(key));
+        get_value(key)
+    });
+
+    let sharded:ShardedLRUCache<u64, u64> = ShardedLRUCache::new(10000);
+    let sharded_time = run_zipf_threads(&|key| sharded.get_or_insert_with(key, || get_value(key)));
+
+    println!("single lock: {:?}, {} shards: {:?}", single_time, sharded.num_shards(), sharded_time);
+}
+
+fn main() {
+    let cache:ShardedLRUCache<u64, u64> = ShardedLRUCache::with_shards(500, 8);
+    assert_eq!(cache.capacity(), 504);
+    assert!(cache.is_empty());
+
+    check_random_ops(&cache);
+    cache.clear();
+    assert!(cache.is_empty());
+
+    check_loader_dedup();
+    check_loader_panic();
+    check_approximate_lru();
+    compare_single_lock();
+}
@@ -0,0 +1,160 @@
+use algorithms::graph::disjoint_set::{DisjointSet, RollbackDisjointSet};
+use algorithms::graph::spanning_tree::{boruvka, kruskal, prim, SpanningForest};
+use algorithms::graph::Graph;
+use rand::Rng;
+
+// The edges must be a forest with one tree per connected component.
+fn check_forest<N>(graph:&Graph<N, i64>, forest:&SpanningForest<i64>) {
+    let mut components = DisjointSet::new(graph.num_nodes());
+    for edge in graph.edges().iter() {
+        components.union(edge.from, edge.to);
+    }
+
+    let mut trees = DisjointSet::new(graph.num_nodes());
+    for e in forest.edges.iter() {
+        let edge = graph.edge(*e);
+        assert!(trees.union(edge.from, edge.to));
+    }
+    assert_eq!(trees.num_sets(), components.num_sets());
+    assert_eq!(forest.weight, Some(forest.edges.iter().map(|e| graph.edge(*e).weight).sum::<i64>()));
+}
+
+// Lightest forest over all subsets of edges, for tiny graphs.
+fn get_brute_force_weight<N>(graph:&Graph<N, i64>) -> i64 {
+    let mut components = DisjointSet::new(graph.num_nodes());
+    for edge in graph.edges().iter() {
+        components.union(edge.from, edge.to);
+    }
+    let tree_edges = graph.num_nodes()-components.num_sets();
+
+    let mut best = i64::MAX;
+    for mask in 0u32..(1 << graph.num_edges()) {
+        if mask.count_ones() as usize != tree_edges {
+            continue;
+        }
+        let mut trees = DisjointSet::new(graph.num_nodes());
+        let mut weight:i64 = 0;
+        let mut is_forest:bool = true;
+
+        for e in (0..graph.num_edges()).filter(|e| mask & (1 << e) != 0) {
+            let edge = graph.edge(e);
+            is_forest &= trees.union(edge.from, edge.to);
+            weight += edge.weight;
+        }
+        if is_forest {
+            best = best.min(weight);
+        }
+    }
+    best
+}
+
+fn main() {
+    // Cable between buildings, the cost of each possible trench.
+    let mut campus:Graph<&str, i64> = Graph::undirected();
+    campus.add_edge("Library", "Lab", 4);
+    campus.add_edge("Library", "Gym", 8);
+    campus.add_edge("Lab", "Gym", 11);
+    campus.add_edge("Lab", "Hall", 8);
+    campus.add_edge("Gym", "Dorm", 7);
+    campus.add_edge("Gym", "Cafe", 1);
+    campus.add_edge("Hall", "Dorm", 2);
+    campus.add_edge("Hall", "Office", 7);
+    campus.add_edge("Hall", "Store", 4);
+    campus.add_edge("Dorm", "Cafe", 6);
+    campus.add_edge("Cafe", "Store", 2);
+    campus.add_edge("Office", "Store", 14);
+    campus.add_edge("Office", "Annex", 9);
+    campus.add_edge("Store", "Annex", 10);
+    campus.add_edge("Shed", "Barn", 3);
+
+    let forest = kruskal(&campus);
+    let trenches:Vec<(&str, &str)> = forest.edges.iter().map(|e| {
+        let edge = campus.edge(*e);
+        (*campus.key(edge.from), *campus.key(edge.to))
+    }).collect();
+    println!("Trenches = {:?}, cost = {:?}", trenches, forest.weight);
+
+    assert_eq!(forest.weight, Some(37 + 3));
+    assert_eq!(forest.edges.len(), campus.num_nodes()-2);
+    for spanning in [&forest, &prim(&campus), &boruvka(&campus)] {
+        check_forest(&campus, spanning);
+        assert_eq!(spanning.weight, forest.weight);
+    }
+
+    // Totals that do not fit in the weight type are reported, even when the
+    // sum only leaves its range halfway.
+    let mut heavy:Graph<u32, u8> = Graph::undirected();
+    heavy.add_edge(0, 1, 200);
+    heavy.add_edge(1, 2, 200);
+    let mut mixed:Graph<u32, i8> = Graph::undirected();
+    mixed.add_edge(0, 1, 100);
+    mixed.add_edge(1, 2, 100);
+    mixed.add_edge(2, 3, -100);
+    for (heavy, mixed) in [(kruskal(&heavy), kruskal(&mixed)), (prim(&heavy), prim(&mixed)), (boruvka(&heavy), boruvka(&mixed))] {
+        assert_eq!((heavy.weight, heavy.edges.len()), (None, 2));
+        assert_eq!((mixed.weight, mixed.edges.len()), (Some(100), 3));
+    }
+
+    // Random graphs with ties and several components.
+    let mut rng = rand::thread_rng();
+    for i in 0..500 {
+        let n = rng.gen_range(1..12);
+        let mut graph:Graph<usize, i64> = Graph::undirected();
+        for u in 0..n {
+            graph.add_node(u);
+        }
+        let m = if i < 300 { rng.gen_range(0..=12) } else { rng.gen_range(0..5*n) };
+        for _ in 0..m {
+            graph.add_edge_ids(rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-5..10));
+        }
+
+        let forests = [kruskal(&graph), prim(&graph), boruvka(&graph)];
+        for forest in forests.iter() {
+            check_forest(&graph, forest);
+            assert_eq!(forest.weight, forests[0].weight);
+        }
+        if graph.num_edges() <= 12 {
+            assert_eq!(forests[0].weight, Some(get_brute_force_weight(&graph)));
+        }
+    }
+
+    // Rolling back unions against rebuilding the sets from scratch.
+    let mut sets = RollbackDisjointSet::new(50);
+    let mut unions:Vec<(usize, usize)> = Vec::new();
+    let mut snapshots:Vec<(usize, usize)> = Vec::new();
+
+    for _ in 0..2000 {
+        match rng.gen_range(0..10) {
+            0 => snapshots.push((sets.snapshot(), unions.len())),
+            1 => {
+                if let Some((snapshot, len)) = snapshots.pop() {
+                    sets.rollback(snapshot);
+                    unions.truncate(len);
+                }
+            }
+            2 => {
+                if sets.undo() {
+                    unions.pop();
+                }
+                snapshots.retain(|(s, _)| *s <= sets.snapshot());
+            }
+            _ => {
+                let (a, b) = (rng.gen_range(0..50), rng.gen_range(0..50));
+                sets.union(a, b);
+                unions.push((a, b));
+            }
+        }
+
+        let mut expected = DisjointSet::new(50);
+        for (a, b) in unions.iter() {
+            expected.union(*a, *b);
+        }
+        assert_eq!(sets.num_sets(), expected.num_sets());
+        for _ in 0..10 {
+            let (a, b) = (rng.gen_range(0..50), rng.gen_range(0..50));
+            assert_eq!(sets.same_set(a, b), expected.same_set(a, b));
+            assert_eq!(sets.set_size(a), expected.set_size(a));
+        }
+    }
+    println!("Kruskal, Prim and Boruvka agree on 500 random graphs");
+}
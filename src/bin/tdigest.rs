@@ -0,0 +1,59 @@
+use algorithms::probabilistic::tdigest::TDigest;
+use std::f64::consts::PI;
+use rand::Rng;
+
+fn exact_quantile(sorted:&[f64], q:f64) -> f64 {
+    let i = ((q*sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[i-1]
+}
+
+fn exact_rank(sorted:&[f64], x:f64) -> f64 {
+    sorted.partition_point(|y| *y <= x) as f64/sorted.len() as f64
+}
+
+fn main() {
+    let mut shard_1 = TDigest::new(100.0);
+    let mut shard_2 = TDigest::new(100.0);
+    let mut latencies:Vec<f64> = Vec::new();
+
+    // Log-normal-ish latencies in milliseconds.
+    for i in 0..500000 {
+        let u:f64 = rand::thread_rng().gen_range(0.0..1.0);
+        let v:f64 = rand::thread_rng().gen_range(0.0..1.0);
+        let z = (-2.0*u.ln()).sqrt()*(2.0*PI*v).cos();
+        let x = (3.0 + 0.8*z).exp();
+
+        latencies.push(x);
+        if i % 2 == 0 {
+            shard_1.add(x);
+        }
+        else {
+            shard_2.add(x);
+        }
+    }
+
+    shard_1.merge(&shard_2);
+    let digest = TDigest::from_bytes(&shard_1.to_bytes()).unwrap();
+    latencies.sort_by(|a, b| a.total_cmp(b));
+
+    // Compare in rank space: the estimated quantile should sit at (almost)
+    // the requested rank of the exact data, tighter towards the tails. The k1
+    // scale keeps a centroid at q within 2π sqrt(q(1-q))/δ of the ranks, and
+    // interpolating inside it cannot be off by more.
+    for q in [0.001, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999] {
+        let estimate = digest.quantile(q).unwrap();
+        let rank_error = (exact_rank(&latencies, estimate)-q).abs();
+        let tolerance = (2.0*PI*(q*(1.0-q)).sqrt()/100.0).min(0.01);
+
+        println!("q = {:?}, exact = {:.3}, estimate = {:.3}, rank error = {:.5}", q, exact_quantile(&latencies, q), estimate, rank_error);
+        assert!(rank_error <= tolerance);
+    }
+
+    for x in [5.0, 20.0, 50.0, 200.0] {
+        let rank_error = (digest.rank(x).unwrap()-exact_rank(&latencies, x)).abs();
+        println!("rank({:?}) = {:.5}, exact = {:.5}", x, digest.rank(x).unwrap(), exact_rank(&latencies, x));
+        assert!(rank_error <= 0.01);
+    }
+
+    println!("Count = {:?}, serialized size = {:?} bytes", digest.count(), digest.to_bytes().len());
+}
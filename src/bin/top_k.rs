@@ -0,0 +1,51 @@
+use algorithms::probabilistic::top_k::TopK;
+use std::collections::HashMap;
+use rand::Rng;
+
+fn main() {
+    let mut exact:HashMap<String, u64> = HashMap::new();
+    let mut shard_1:TopK<String> = TopK::new(20);
+    let mut shard_2:TopK<String> = TopK::new(20);
+
+    for i in 0..200000 {
+        let u:f64 = rand::thread_rng().gen_range(0.0..1.0);
+        let page = format!("/page/{}", (1.0/(u*0.9999+0.0001)) as u64);
+
+        *exact.entry(page.clone()).or_insert(0) += 1;
+
+        if i % 2 == 0 {
+            shard_1.add(page);
+        }
+        else {
+            shard_2.add(page);
+        }
+    }
+
+    shard_1.merge(&shard_2).unwrap();
+    let restored:TopK<String> = TopK::from_bytes(&shard_1.to_bytes()).unwrap();
+
+    let mut true_top:Vec<(&String, &u64)> = exact.iter().collect();
+    true_top.sort_by(|a, b| b.1.cmp(a.1));
+
+    for (page, count, error) in restored.top().iter().take(5) {
+        let cnt = exact[page];
+        assert!(*count >= cnt && count-error <= cnt);
+        println!("{:?}: estimate = {:?}, error = {:?}, exact = {:?}", page, count, error, cnt);
+    }
+
+    for (page, _) in true_top.iter().take(3) {
+        assert!(restored.estimate(page).is_some(), "heavy hitter {} missing", page);
+    }
+
+    // Counts near the limit saturate when merged, as when added.
+    let mut left:TopK<String> = TopK::new(2);
+    let mut right:TopK<String> = TopK::new(2);
+    left.add_count("x".to_string(), u64::MAX-1);
+    left.add_count("y".to_string(), 5);
+    right.add_count("x".to_string(), 10);
+    right.add_count("z".to_string(), u64::MAX);
+    left.merge(&right).unwrap();
+    assert_eq!(left.estimate(&"x".to_string()).map(|e| e.0), Some(u64::MAX));
+    assert_eq!(left.estimate(&"z".to_string()).map(|e| e.0), Some(u64::MAX));
+    assert_eq!(left.total(), u64::MAX);
+}
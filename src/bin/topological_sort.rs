@@ -1,71 +1,38 @@
-use std::collections::HashMap;
+use algorithms::graph::topological_sort::topsort;
+use algorithms::graph::Graph;
 
-fn topsort<'a>(dependency:&mut HashMap<&'a str, Vec<&'a str>>)->Vec<&'a str> {
-    let mut in_deg:HashMap<&str, u32> = HashMap::new();
-
-    for (key, values) in &*dependency {
-        in_deg.entry(key).or_insert(0);
+fn main() {
+    let mut dependency:Graph<&str, ()> = Graph::directed();
 
-        for value in values.iter() {
-            let deg: &mut u32 = in_deg.entry(value).or_insert(0);
-            *deg += 1;
-        }
+    let edges = [("A", "D"), ("B", "D"), ("B", "E"), ("C", "E"), ("D", "F"), ("D", "G"), ("E", "G"), ("F", "H"), ("G", "H")];
+    for (u, v) in edges.iter() {
+        dependency.add_edge(*u, *v, ());
     }
 
-    let mut output:Vec<&str> = Vec::new();
+    let sorted:Vec<&str> = topsort(&dependency).unwrap().into_iter().map(|u| *dependency.key(u)).collect();
+    println!("Topological sorting = {:?}", sorted);
 
-    let mut my_vector:Vec<&str> = Vec::new();
-    for (key, deg) in &in_deg {
-        if *deg == 0 {
-            my_vector.push(key);
-        }
+    assert_eq!(sorted.len(), dependency.num_nodes());
+    let position = |key:&str| sorted.iter().position(|k| *k == key).unwrap();
+    for (u, v) in edges.iter() {
+        assert!(position(u) < position(v));
     }
 
-    while my_vector.len() > 0 {
-        let mut next_level:Vec<&str> = Vec::new();
-
-        for key in my_vector.iter() {
-            output.push(key);
-
-            match dependency.get(key) {
-                Some(children) => { 
-                    for child in children.iter() {
-                        match in_deg.get(child) {
-                            Some(deg) => {
-                                if *deg == 1 {
-                                    next_level.push(child);
-                                }
-                                in_deg.insert(child, *deg-1);
-                            }
-                            None => {}
-                        }
-                    }
-                }
-                None => {}
-            }
-        }
-
-        my_vector.clear();
+    // With a cycle there is no order, and the cycle is reported instead.
+    dependency.add_edge("H", "D", ());
+    let mut cycle:Vec<&str> = topsort(&dependency).unwrap_err().into_iter().map(|u| *dependency.key(u)).collect();
+    println!("Cycle = {:?}", cycle);
 
-        for key in next_level.iter() {
-            my_vector.push(key);
-        }
+    for i in 0..cycle.len() {
+        let (u, v) = (cycle[i], cycle[(i+1) % cycle.len()]);
+        let (u, v) = (dependency.id(u).unwrap(), dependency.id(v).unwrap());
+        assert!(dependency.neighbors(u).any(|(x, _)| x == v));
     }
+    cycle.sort();
+    assert!(cycle == vec!["D", "F", "H"] || cycle == vec!["D", "G", "H"]);
 
-    return output;
+    // A self loop is a cycle on its own.
+    dependency.add_edge("C", "C", ());
+    let cycle = topsort(&dependency).unwrap_err();
+    assert!(cycle.len() == 1 || cycle.len() == 3);
 }
-
-fn main() {
-    let mut dependency:HashMap<&str, Vec<&str>> = HashMap::new();
-
-    dependency.insert("A", vec!["D"]);
-    dependency.insert("B", vec!["D", "E"]);
-    dependency.insert("C", vec!["E"]);
-    dependency.insert("D", vec!["F", "G"]);
-    dependency.insert("E", vec!["G"]);
-    dependency.insert("F", vec!["H"]);
-    dependency.insert("G", vec!["H"]);
-
-    let sorted = topsort(&mut dependency);
-    println!("Topological sorting = {:?}", sorted);
-}
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::hash::Hash;
+use super::lru::LRUCache;
+use super::Cache;
+
+// Adaptive Replacement Cache (Megiddo & Modha). T1 holds keys seen once
+// recently and T2 keys seen at least twice, B1 and B2 remember the keys
+// recently evicted from each. A hit in a ghost list shows which side was
+// evicted too eagerly and moves the target size `p` of T1 towards it.
+#[allow(clippy::upper_case_acronyms)]
+pub struct ARCCache<K, V> {
+    capacity: usize,
+    p: usize,
+    t1: LRUCache<K, V>,
+    t2: LRUCache<K, V>,
+    b1: LRUCache<K, ()>,
+    b2: LRUCache<K, ()>,
+}
+
+impl<K: Hash + Eq + Clone, V> ARCCache<K, V> {
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        Self {
+            capacity,
+            p: 0,
+            t1: LRUCache::new(capacity),
+            t2: LRUCache::new(capacity),
+            b1: LRUCache::new(capacity),
+            b2: LRUCache::new(2*capacity),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> ARCCache<K, V> {
+    // Current target size of T1.
+    pub fn target_recent(&self) -> usize {
+        self.p
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> ARCCache<K, V> {
+    // Evicts from T1 if it is above its target, otherwise from T2, and keeps
+    // the key in the matching ghost list.
+    fn replace(&mut self, in_b2:bool) -> (K, V) {
+        let t1 = self.t1.len();
+
+        if t1 > 0 && (t1 > self.p || (in_b2 && t1 == self.p) || self.t2.is_empty()) {
+            let (key, val) = self.t1.pop_lru().unwrap();
+            self.b1.put(key.clone(), ());
+            (key, val)
+        }
+        else {
+            let (key, val) = self.t2.pop_lru().unwrap();
+            self.b2.put(key.clone(), ());
+            (key, val)
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for ARCCache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V> {
+        if let Some(val) = self.t1.remove(key) {
+            self.t2.put(key.clone(), val);
+            return self.t2.peek(key);
+        }
+        self.t2.get(key)
+    }
+
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        if let Some(old) = self.t1.remove(&key) {
+            self.t2.put(key.clone(), val);
+            return vec![(key, old)];
+        }
+        if self.t2.contains(&key) {
+            return self.t2.put(key, val);
+        }
+
+        let mut evicted:Vec<(K, V)> = Vec::new();
+        let full = self.len() >= self.capacity;
+
+        if self.b1.contains(&key) {
+            let delta = (self.b2.len()/self.b1.len()).max(1);
+            self.p = (self.p + delta).min(self.capacity);
+            if full {
+                evicted.push(self.replace(false));
+            }
+            self.b1.remove(&key);
+            self.t2.put(key, val);
+            return evicted;
+        }
+
+        if self.b2.contains(&key) {
+            let delta = (self.b1.len()/self.b2.len()).max(1);
+            self.p = self.p.saturating_sub(delta);
+            if full {
+                evicted.push(self.replace(true));
+            }
+            self.b2.remove(&key);
+            self.t2.put(key, val);
+            return evicted;
+        }
+
+        let l1 = self.t1.len() + self.b1.len();
+        let total = l1 + self.t2.len() + self.b2.len();
+
+        if l1 == self.capacity {
+            if self.t1.len() < self.capacity {
+                self.b1.pop_lru();
+                if full {
+                    evicted.push(self.replace(false));
+                }
+            }
+            else {
+                evicted.extend(self.t1.pop_lru());
+            }
+        }
+        else if total >= self.capacity {
+            if total == 2*self.capacity {
+                self.b2.pop_lru();
+            }
+            if full {
+                evicted.push(self.replace(false));
+            }
+        }
+
+        self.t1.put(key, val);
+        evicted
+    }
+
+    fn contains(&self, key:&K) -> bool {
+        self.t1.contains(key) || self.t2.contains(key)
+    }
+
+    fn len(&self) -> usize {
+        self.t1.len() + self.t2.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
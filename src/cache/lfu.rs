@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use super::lru::LRUCache;
+use super::Cache;
+
+// O(1) LFU (Shah, Mitra & Matani): keys are grouped into one bucket per
+// access count, and the smallest non-empty count is tracked so the victim is
+// found without searching. Within a bucket the least recently used key goes
+// first.
+pub struct LFUCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    buckets: HashMap<u64, LRUCache<K, ()>>,
+    min_freq: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> LFUCache<K, V> {
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            buckets: HashMap::new(),
+            min_freq: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LFUCache<K, V> {
+    fn add_to_bucket(&mut self, key:K, freq:u64) {
+        let capacity = self.capacity;
+        self.buckets.entry(freq).or_insert_with(|| LRUCache::new(capacity)).put(key, ());
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LFUCache<K, V> {
+    fn touch(&mut self, key:&K) {
+        let entry = self.entries.get_mut(key).unwrap();
+        let freq = entry.1;
+        entry.1 += 1;
+
+        let bucket = self.buckets.get_mut(&freq).unwrap();
+        bucket.remove(key);
+        if bucket.is_empty() {
+            self.buckets.remove(&freq);
+            if self.min_freq == freq {
+                self.min_freq += 1;
+            }
+        }
+
+        self.add_to_bucket(key.clone(), freq+1);
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LFUCache<K, V> {
+    fn evict(&mut self) -> Option<(K, V)> {
+        let bucket = self.buckets.get_mut(&self.min_freq)?;
+        let (key, _) = bucket.pop_lru()?;
+        if bucket.is_empty() {
+            self.buckets.remove(&self.min_freq);
+        }
+
+        let (val, _) = self.entries.remove(&key).unwrap();
+        Some((key, val))
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LFUCache<K, V> {
+    // Number of accesses recorded for a key, without counting this one.
+    pub fn frequency(&self, key:&K) -> Option<u64> {
+        self.entries.get(key).map(|e| e.1)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for LFUCache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V> {
+        if !self.entries.contains_key(key) {
+            return None;
+        }
+        self.touch(key);
+        self.entries.get(key).map(|e| &e.0)
+    }
+
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        if self.entries.contains_key(&key) {
+            self.touch(&key);
+            let old = std::mem::replace(&mut self.entries.get_mut(&key).unwrap().0, val);
+            return vec![(key, old)];
+        }
+
+        let mut evicted:Vec<(K, V)> = Vec::new();
+        if self.entries.len() == self.capacity {
+            evicted.extend(self.evict());
+        }
+
+        self.entries.insert(key.clone(), (val, 1));
+        self.add_to_bucket(key, 1);
+        self.min_freq = 1;
+        evicted
+    }
+
+    fn contains(&self, key:&K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
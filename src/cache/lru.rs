@@ -0,0 +1,673 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use super::{Clock, SystemClock};
+
+const NIL:usize = usize::MAX;
+
+pub type Weigher<K, V> = Arc<dyn Fn(&K, &V) -> usize + Send + Sync>;
+pub type EvictionListener<K, V> = Arc<dyn Fn(&K, &V, EvictionReason) + Send + Sync>;
+pub type WriteBack<K, V> = Arc<dyn Fn(&K, &V) + Send + Sync>;
+
+// Why an entry left the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvictionReason {
+    // Overwritten by a put under the same key.
+    Replaced,
+    // Taken out by remove, pop_lru or clear.
+    Removed,
+    // Made room for other entries, or too heavy to be stored at all.
+    Evicted,
+    // Its time to live ran out.
+    Expired,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub inserts: u64,
+    pub evictions: u64,
+    pub expirations: u64,
+    pub write_backs: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.hits + self.misses == 0 {
+            return 0.0;
+        }
+        self.hits as f64/(self.hits + self.misses) as f64
+    }
+}
+
+// The index is keyed by hashes already, so it does not hash them again.
+#[derive(Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes:&[u8]) {
+        for b in bytes.iter() {
+            self.0 = (self.0 << 8) | *b as u64;
+        }
+    }
+
+    fn write_u64(&mut self, x:u64) {
+        self.0 = x;
+    }
+}
+
+struct Entry<K, V> {
+    key: K,
+    val: V,
+    prev: usize,
+    next: usize,
+    // Next entry whose key has the same hash.
+    chain: usize,
+    weight: usize,
+    expires: Option<Instant>,
+    // Changed since it was last written back.
+    dirty: bool,
+}
+
+// LRU cache over a slab of entries linked into a doubly linked list by index,
+// most recently used at the head. The index maps the hash of a key to the
+// first slot of a chain of entries with that hash, so keys are stored once and
+// need neither Copy nor Clone.
+//
+// Capacity is a total weight: every entry weighs 1 unless a weigher is set
+// (e.g. the size in bytes), and least recently used entries are evicted until
+// a new entry fits. Entries may carry a time to live; expired entries are
+// invisible to lookups and are dropped lazily when touched, or all at once by
+// `purge_expired`.
+//
+// Lookups with get/get_mut, inserts and evictions are counted in `stats`. An
+// eviction listener sees every entry that leaves the cache. In write-back
+// mode, entries written with put or changed through a mutable reference are
+// dirty, and are handed to the write-back sink when they are evicted, expire
+// or get flushed.
+pub struct LRUCache<K, V> {
+    capacity: usize,
+    size: usize,
+    weigher: Weigher<K, V>,
+    clock: Arc<dyn Clock>,
+    default_ttl: Option<Duration>,
+    listener: Option<EvictionListener<K, V>>,
+    write_back: Option<WriteBack<K, V>>,
+    stats: CacheStats,
+    entries: Vec<Option<Entry<K, V>>>,
+    free: Vec<usize>,
+    index: HashMap<u64, usize, BuildHasherDefault<IdentityHasher>>,
+    hasher: RandomState,
+    head: usize,
+    tail: usize,
+    len: usize,
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        Self {
+            capacity,
+            size: 0,
+            weigher: Arc::new(|_, _| 1),
+            clock: Arc::new(SystemClock),
+            default_ttl: None,
+            listener: None,
+            write_back: None,
+            stats: CacheStats::default(),
+            entries: Vec::new(),
+            free: Vec::new(),
+            index: HashMap::default(),
+            hasher: RandomState::new(),
+            head: NIL,
+            tail: NIL,
+            len: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Weights are computed once on insert, set the weigher before adding entries.
+    pub fn with_weigher<F: Fn(&K, &V) -> usize + Send + Sync + 'static>(mut self, weigher:F) -> Self {
+        assert!(self.is_empty(), "weigher must be set on an empty cache");
+        self.weigher = Arc::new(weigher);
+        self
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn with_clock(mut self, clock:Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Time to live for entries added with `put`.
+    pub fn with_ttl(mut self, ttl:Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn with_eviction_listener<F: Fn(&K, &V, EvictionReason) + Send + Sync + 'static>(mut self, listener:F) -> Self {
+        self.listener = Some(Arc::new(listener));
+        self
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Turns on write-back mode. Entries already stored count as clean.
+    pub fn with_write_back<F: Fn(&K, &V) + Send + Sync + 'static>(mut self, sink:F) -> Self {
+        self.write_back = Some(Arc::new(sink));
+        self
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    fn entry(&self, i:usize) -> &Entry<K, V> {
+        self.entries[i].as_ref().unwrap()
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    fn entry_mut(&mut self, i:usize) -> &mut Entry<K, V> {
+        self.entries[i].as_mut().unwrap()
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    fn find<Q>(&self, key:&Q) -> Option<usize> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let mut i = *self.index.get(&self.hasher.hash_one(key))?;
+        while i != NIL {
+            let entry = self.entry(i);
+            if entry.key.borrow() == key {
+                return Some(i);
+            }
+            i = entry.chain;
+        }
+        None
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    fn is_expired(&self, i:usize, now:Instant) -> bool {
+        self.entry(i).expires.is_some_and(|t| t <= now)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Like `find` but drops the entry if it has expired.
+    fn find_live<Q>(&mut self, key:&Q) -> Option<usize> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.find(key)?;
+        if self.is_expired(i, self.clock.now()) {
+            self.discard(i, EvictionReason::Expired);
+            return None;
+        }
+        Some(i)
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    fn unlink(&mut self, i:usize) {
+        let (prev, next) = (self.entry(i).prev, self.entry(i).next);
+
+        if prev == NIL {
+            self.head = next;
+        }
+        else {
+            self.entry_mut(prev).next = next;
+        }
+
+        if next == NIL {
+            self.tail = prev;
+        }
+        else {
+            self.entry_mut(next).prev = prev;
+        }
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    fn push_front(&mut self, i:usize) {
+        let head = self.head;
+        let entry = self.entry_mut(i);
+        entry.prev = NIL;
+        entry.next = head;
+
+        if head == NIL {
+            self.tail = i;
+        }
+        else {
+            self.entry_mut(head).prev = i;
+        }
+        self.head = i;
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    fn remove_at(&mut self, i:usize) -> (K, V) {
+        self.unlink(i);
+
+        // Take the entry out of its hash chain.
+        let h = self.hasher.hash_one(&self.entry(i).key);
+        let chain = self.entry(i).chain;
+        let first = self.index[&h];
+
+        if first == i {
+            if chain == NIL {
+                self.index.remove(&h);
+            }
+            else {
+                self.index.insert(h, chain);
+            }
+        }
+        else {
+            let mut j = first;
+            while self.entry(j).chain != i {
+                j = self.entry(j).chain;
+            }
+            self.entry_mut(j).chain = chain;
+        }
+
+        let entry = self.entries[i].take().unwrap();
+        self.free.push(i);
+        self.len -= 1;
+        self.size -= entry.weight;
+        (entry.key, entry.val)
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Counts an entry leaving the cache, tells the listener and writes it back
+    // if it is dirty and was evicted or expired.
+    fn notify(&mut self, key:&K, val:&V, dirty:bool, reason:EvictionReason) {
+        match reason {
+            EvictionReason::Evicted => self.stats.evictions += 1,
+            EvictionReason::Expired => self.stats.expirations += 1,
+            _ => {}
+        }
+
+        if dirty && (reason == EvictionReason::Evicted || reason == EvictionReason::Expired) {
+            if let Some(sink) = &self.write_back {
+                sink(key, val);
+                self.stats.write_backs += 1;
+            }
+        }
+
+        if let Some(listener) = &self.listener {
+            listener(key, val, reason);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    fn discard(&mut self, i:usize, reason:EvictionReason) -> (K, V) {
+        let dirty = self.entry(i).dirty;
+        let (key, val) = self.remove_at(i);
+        self.notify(&key, &val, dirty, reason);
+        (key, val)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Drops the least recently used entry to make room.
+    fn evict_lru(&mut self) -> (K, V) {
+        let i = self.tail;
+        let reason = if self.is_expired(i, self.clock.now()) { EvictionReason::Expired } else { EvictionReason::Evicted };
+        self.discard(i, reason)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    fn insert_new(&mut self, key:K, val:V, weight:usize, expires:Option<Instant>, dirty:bool) -> usize {
+        let h = self.hasher.hash_one(&key);
+        let chain = self.index.get(&h).copied().unwrap_or(NIL);
+        let entry = Entry {
+            key,
+            val,
+            prev: NIL,
+            next: NIL,
+            chain,
+            weight,
+            expires,
+            dirty,
+        };
+
+        let i = match self.free.pop() {
+            Some(i) => {
+                self.entries[i] = Some(entry);
+                i
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len()-1
+            }
+        };
+
+        self.index.insert(h, i);
+        self.push_front(i);
+        self.len += 1;
+        self.size += weight;
+        i
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Inserts or replaces a value and makes it the most recently used, with
+    // the default time to live. Returns the entries that were displaced: the
+    // old entry under the same key, then the least recently used entries
+    // evicted to make room. An entry heavier than the whole capacity is not
+    // stored and comes straight back.
+    pub fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        self.put_entry(key, val, self.default_ttl, self.write_back.is_some())
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn put_with_ttl(&mut self, key:K, val:V, ttl:Duration) -> Vec<(K, V)> {
+        self.put_entry(key, val, Some(ttl), self.write_back.is_some())
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Like `put`, but the value already matches the backing store (e.g. it
+    // was just loaded from it), so it is not written back.
+    pub fn put_clean(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        self.put_entry(key, val, self.default_ttl, false)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    fn put_entry(&mut self, key:K, val:V, ttl:Option<Duration>, dirty:bool) -> Vec<(K, V)> {
+        let mut displaced:Vec<(K, V)> = Vec::new();
+
+        // The new value supersedes the old one, which is not written back.
+        if let Some(i) = self.find(&key) {
+            displaced.push(self.discard(i, EvictionReason::Replaced));
+        }
+
+        let weight = (self.weigher)(&key, &val);
+        if weight > self.capacity {
+            self.notify(&key, &val, dirty, EvictionReason::Evicted);
+            displaced.push((key, val));
+            return displaced;
+        }
+
+        while self.size + weight > self.capacity {
+            displaced.push(self.evict_lru());
+        }
+
+        let expires = ttl.map(|t| self.clock.now() + t);
+        self.insert_new(key, val, weight, expires, dirty);
+        self.stats.inserts += 1;
+        displaced
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // `find_live` that counts a hit or a miss.
+    fn lookup<Q>(&mut self, key:&Q) -> Option<usize> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.find_live(key);
+        if i.is_some() {
+            self.stats.hits += 1;
+        }
+        else {
+            self.stats.misses += 1;
+        }
+        i
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn get<Q>(&mut self, key:&Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.lookup(key)?;
+        self.unlink(i);
+        self.push_front(i);
+        Some(&self.entry(i).val)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Marks the entry dirty in write-back mode.
+    pub fn get_mut<Q>(&mut self, key:&Q) -> Option<&mut V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.lookup(key)?;
+        self.unlink(i);
+        self.push_front(i);
+        let dirty = self.write_back.is_some();
+        let entry = self.entry_mut(i);
+        entry.dirty |= dirty;
+        Some(&mut entry.val)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Looks up a value without touching its recency.
+    pub fn peek<Q>(&self, key:&Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let now = self.clock.now();
+        self.find(key).filter(|i| !self.is_expired(*i, now)).map(|i| &self.entry(i).val)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn peek_mut<Q>(&mut self, key:&Q) -> Option<&mut V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.find_live(key)?;
+        let dirty = self.write_back.is_some();
+        let entry = self.entry_mut(i);
+        entry.dirty |= dirty;
+        Some(&mut entry.val)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn contains<Q>(&self, key:&Q) -> bool where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.peek(key).is_some()
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn remove<Q>(&mut self, key:&Q) -> Option<V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let i = self.find_live(key)?;
+        Some(self.discard(i, EvictionReason::Removed).1)
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Expired entries at the back are dropped on the way.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let now = self.clock.now();
+        while self.tail != NIL && self.is_expired(self.tail, now) {
+            self.discard(self.tail, EvictionReason::Expired);
+        }
+
+        if self.tail == NIL {
+            return None;
+        }
+        Some(self.discard(self.tail, EvictionReason::Removed))
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Least recently used live entry without touching its recency.
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        let now = self.clock.now();
+        let mut i = self.tail;
+        while i != NIL && self.is_expired(i, now) {
+            i = self.entry(i).prev;
+        }
+
+        if i == NIL {
+            return None;
+        }
+        let entry = self.entry(i);
+        Some((&entry.key, &entry.val))
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Changes the capacity, evicting least recently used entries that no longer fit.
+    pub fn resize(&mut self, capacity:usize) -> Vec<(K, V)> {
+        assert!(capacity > 0, "capacity must be positive");
+
+        self.capacity = capacity;
+        let mut evicted:Vec<(K, V)> = Vec::new();
+        while self.size > self.capacity {
+            evicted.push(self.evict_lru());
+        }
+        evicted
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    // Drops every expired entry and returns them, least recently used first.
+    pub fn purge_expired(&mut self) -> Vec<(K, V)> {
+        let now = self.clock.now();
+        let mut expired:Vec<(K, V)> = Vec::new();
+        let mut i = self.tail;
+
+        while i != NIL {
+            let prev = self.entry(i).prev;
+            if self.is_expired(i, now) {
+                expired.push(self.discard(i, EvictionReason::Expired));
+            }
+            i = prev;
+        }
+
+        expired
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Dirty entries are not written back, call `flush` first to keep them.
+    pub fn clear(&mut self) {
+        if let Some(listener) = &self.listener {
+            for entry in self.entries.iter().flatten() {
+                listener(&entry.key, &entry.val, EvictionReason::Removed);
+            }
+        }
+        self.entries.clear();
+        self.free.clear();
+        self.index.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.len = 0;
+        self.size = 0;
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Writes every dirty entry to the write-back sink and marks it clean.
+    // Returns the number of entries written.
+    pub fn flush(&mut self) -> usize {
+        let sink = match &self.write_back {
+            Some(sink) => sink.clone(),
+            None => return 0,
+        };
+
+        let mut written:usize = 0;
+        for entry in self.entries.iter_mut().flatten() {
+            if entry.dirty {
+                sink(&entry.key, &entry.val);
+                entry.dirty = false;
+                written += 1;
+            }
+        }
+        self.stats.write_backs += written as u64;
+        written
+    }
+}
+
+impl<K: Hash + Eq, V> LRUCache<K, V> {
+    pub fn is_dirty<Q>(&self, key:&Q) -> bool where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.find(key).is_some_and(|i| self.entry(i).dirty)
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Counts expired entries that have not been dropped yet.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Total weight of the stored entries.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V> LRUCache<K, V> {
+    // Live entries from the most to the least recently used.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            cache: self,
+            curr: self.head,
+            now: self.clock.now(),
+        }
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    cache: &'a LRUCache<K, V>,
+    curr: usize,
+    now: Instant,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.curr != NIL {
+            let entry = self.cache.entry(self.curr);
+            self.curr = entry.next;
+            if entry.expires.is_none_or(|t| t > self.now) {
+                return Some((&entry.key, &entry.val));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a LRUCache<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
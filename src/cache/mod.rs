@@ -0,0 +1,128 @@
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub mod arc;
+pub mod lfu;
+pub mod lru;
+pub mod sharded;
+pub mod tiny_lfu;
+pub mod two_queue;
+
+// Time source for entry expiry, so tests can move time forward without sleeping.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Clock that only moves when told to. Clones share the same time.
+#[derive(Clone)]
+pub struct ManualClock {
+    start: Instant,
+    offset_nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            offset_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl ManualClock {
+    pub fn advance(&self, d:Duration) {
+        self.offset_nanos.fetch_add(d.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + Duration::from_nanos(self.offset_nanos.load(Ordering::SeqCst))
+    }
+}
+
+// Common interface of the eviction policies. `put` returns the entries that
+// left the cache: the old value under the same key or evicted entries.
+pub trait Cache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V>;
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)>;
+    fn contains(&self, key:&K) -> bool;
+    fn len(&self) -> usize;
+    fn capacity(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V> Cache<K, V> for lru::LRUCache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V> {
+        lru::LRUCache::get(self, key)
+    }
+
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        lru::LRUCache::put(self, key, val)
+    }
+
+    fn contains(&self, key:&K) -> bool {
+        lru::LRUCache::contains(self, key)
+    }
+
+    fn len(&self) -> usize {
+        lru::LRUCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        lru::LRUCache::capacity(self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReplayStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl ReplayStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.hits + self.misses == 0 {
+            return 0.0;
+        }
+        self.hits as f64/(self.hits + self.misses) as f64
+    }
+}
+
+// Replays a trace of keys against a read-through cache: every miss loads the
+// value with `load` and inserts it.
+pub fn replay<K: Clone, V, C: Cache<K, V> + ?Sized, F: FnMut(&K) -> V>(cache:&mut C, trace:&[K], mut load:F) -> ReplayStats {
+    let mut stats = ReplayStats::default();
+
+    for key in trace.iter() {
+        if cache.get(key).is_some() {
+            stats.hits += 1;
+        }
+        else {
+            stats.misses += 1;
+            let val = load(key);
+            cache.put(key.clone(), val);
+        }
+    }
+
+    stats
+}
@@ -0,0 +1,226 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use super::lru::LRUCache;
+
+enum LoadState<V> {
+    Loading,
+    Done(V),
+    Failed,
+}
+
+// A load in progress, other threads asking for the same key wait on it.
+struct PendingLoad<V> {
+    state: Mutex<LoadState<V>>,
+    ready: Condvar,
+}
+
+impl<V: Clone> PendingLoad<V> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(LoadState::Loading),
+            ready: Condvar::new(),
+        }
+    }
+}
+
+impl<V: Clone> PendingLoad<V> {
+    fn finish(&self, state:LoadState<V>) {
+        *self.state.lock().unwrap() = state;
+        self.ready.notify_all();
+    }
+}
+
+impl<V: Clone> PendingLoad<V> {
+    // The loaded value, or None if the loader panicked.
+    fn wait(&self) -> Option<V> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            match &*state {
+                LoadState::Loading => state = self.ready.wait(state).unwrap(),
+                LoadState::Done(v) => return Some(v.clone()),
+                LoadState::Failed => return None,
+            }
+        }
+    }
+}
+
+struct Shard<K, V> {
+    cache: LRUCache<K, V>,
+    pending: HashMap<K, Arc<PendingLoad<V>>>,
+}
+
+// Thread-safe LRU cache split into independently locked shards, a key always
+// lives in the shard picked by its hash. Every shard evicts its own least
+// recently used entries, which approximates a global LRU as long as keys
+// spread evenly. Values are handed out as clones, wrap large values in an Arc.
+pub struct ShardedLRUCache<K, V> {
+    shards: Vec<Mutex<Shard<K, V>>>,
+    hasher: RandomState,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    // Four shards per available core.
+    pub fn new(capacity:usize) -> Self {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        ShardedLRUCache::with_shards(capacity, 4*cores)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    // Every shard gets an equal part of the capacity, rounded up.
+    pub fn with_shards(capacity:usize, num_shards:usize) -> Self {
+        assert!(capacity > 0 && num_shards > 0, "capacity and number of shards must be positive");
+
+        let num_shards = num_shards.min(capacity);
+        let shard_capacity = capacity.div_ceil(num_shards);
+
+        Self {
+            shards: (0..num_shards).map(|_| Mutex::new(Shard { cache: LRUCache::new(shard_capacity), pending: HashMap::new() })).collect(),
+            hasher: RandomState::new(),
+            capacity: shard_capacity*num_shards,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    fn shard(&self, key:&K) -> MutexGuard<'_, Shard<K, V>> {
+        let i = (self.hasher.hash_one(key) % self.shards.len() as u64) as usize;
+        self.shards[i].lock().unwrap()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn get(&self, key:&K) -> Option<V> {
+        self.shard(key).cache.get(key).cloned()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn peek(&self, key:&K) -> Option<V> {
+        self.shard(key).cache.peek(key).cloned()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn put(&self, key:K, val:V) -> Vec<(K, V)> {
+        self.shard(&key).cache.put(key, val)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn remove(&self, key:&K) -> Option<V> {
+        self.shard(key).cache.remove(key)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn contains(&self, key:&K) -> bool {
+        self.shard(key).cache.contains(key)
+    }
+}
+
+// Clears the pending load if the loader panics, so that waiting threads retry
+// instead of blocking forever.
+struct LoadGuard<'a, K: Hash + Eq + Clone, V: Clone> {
+    cache: &'a ShardedLRUCache<K, V>,
+    key: &'a K,
+    pending: &'a PendingLoad<V>,
+    done: bool,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Drop for LoadGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.cache.shard(self.key).pending.remove(self.key);
+            self.pending.finish(LoadState::Failed);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    // Returns the cached value or computes it with `load`. Concurrent calls
+    // for the same missing key run the loader only once, the other callers
+    // block until its value is ready. The shard stays unlocked while loading.
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&self, key:K, load:F) -> V {
+        let mut load = Some(load);
+
+        loop {
+            let (pending, is_loader) = {
+                let mut shard = self.shard(&key);
+                if let Some(v) = shard.cache.get(&key) {
+                    return v.clone();
+                }
+
+                match shard.pending.get(&key) {
+                    Some(p) => (p.clone(), false),
+                    None => {
+                        let p = Arc::new(PendingLoad::new());
+                        shard.pending.insert(key.clone(), p.clone());
+                        (p, true)
+                    }
+                }
+            };
+
+            if !is_loader {
+                // A failed load is retried, possibly by this thread.
+                if let Some(v) = pending.wait() {
+                    return v;
+                }
+                continue;
+            }
+
+            let mut guard = LoadGuard {
+                cache: self,
+                key: &key,
+                pending: &pending,
+                done: false,
+            };
+
+            let val = (load.take().unwrap())();
+
+            {
+                let mut shard = self.shard(&key);
+                shard.pending.remove(&key);
+                shard.cache.put(key.clone(), val.clone());
+            }
+            pending.finish(LoadState::Done(val.clone()));
+            guard.done = true;
+            return val;
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.lock().unwrap().cache.len()).sum()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedLRUCache<K, V> {
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap().cache.clear();
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::hash::Hash;
+use crate::probabilistic::count_min::CountMinSketch;
+use super::lru::LRUCache;
+use super::Cache;
+
+const SKETCH_DEPTH:usize = 4;
+
+// W-TinyLFU (Einziger, Friedman & Manes). New keys go to a small LRU window,
+// and a key leaving the window only enters the main segmented LRU if a
+// count-min sketch says it is requested more often than the main victim it
+// would replace. The sketch is halved every 10*capacity accesses so that old
+// popularity fades. The main area is split into probation (first hit) and
+// protected (hit again while on probation) segments.
+pub struct TinyLFUCache<K, V> {
+    capacity: usize,
+    window_capacity: usize,
+    main_capacity: usize,
+    protected_capacity: usize,
+    window: LRUCache<K, V>,
+    probation: LRUCache<K, V>,
+    protected: LRUCache<K, V>,
+    sketch: CountMinSketch,
+    samples: usize,
+    sample_size: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> TinyLFUCache<K, V> {
+    // The window takes 1% of the capacity and protected 80% of the rest.
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        let window_capacity = (capacity/100).max(1);
+        let main_capacity = capacity-window_capacity;
+
+        Self {
+            capacity,
+            window_capacity,
+            main_capacity,
+            protected_capacity: main_capacity*4/5,
+            window: LRUCache::new(window_capacity+1),
+            probation: LRUCache::new(capacity),
+            protected: LRUCache::new(capacity),
+            sketch: CountMinSketch::new((4*capacity).max(64), SKETCH_DEPTH, true),
+            samples: 0,
+            sample_size: 10*capacity,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> TinyLFUCache<K, V> {
+    fn record(&mut self, key:&K) {
+        self.sketch.add(key, 1);
+        self.samples += 1;
+
+        if self.samples >= self.sample_size {
+            self.sketch.halve();
+            self.samples /= 2;
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> TinyLFUCache<K, V> {
+    // Moves a key hit on probation into protected, demoting the least
+    // recently used protected key back to probation if needed.
+    fn promote(&mut self, key:&K) {
+        let val = self.probation.remove(key).unwrap();
+        self.protected.put(key.clone(), val);
+
+        if self.protected.len() > self.protected_capacity {
+            let (k, v) = self.protected.pop_lru().unwrap();
+            self.probation.put(k, v);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> TinyLFUCache<K, V> {
+    // Decides between the key evicted from the window and the main victim.
+    fn admit(&mut self, key:K, val:V) -> Option<(K, V)> {
+        if self.main_capacity == 0 {
+            return Some((key, val));
+        }
+        if self.probation.len() + self.protected.len() < self.main_capacity {
+            self.probation.put(key, val);
+            return None;
+        }
+
+        let from_probation = !self.probation.is_empty();
+        let victim = if from_probation { self.probation.peek_lru() } else { self.protected.peek_lru() };
+        let victim_freq = self.sketch.estimate(victim.unwrap().0);
+
+        if self.sketch.estimate(&key) <= victim_freq {
+            return Some((key, val));
+        }
+
+        let evicted = if from_probation { self.probation.pop_lru() } else { self.protected.pop_lru() };
+        self.probation.put(key, val);
+        evicted
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for TinyLFUCache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V> {
+        self.record(key);
+
+        if self.window.contains(key) {
+            return self.window.get(key);
+        }
+        if self.probation.contains(key) {
+            self.promote(key);
+        }
+        // With a tiny protected segment the key may have been demoted again.
+        if self.protected.contains(key) {
+            return self.protected.get(key);
+        }
+        self.probation.peek(key)
+    }
+
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        if self.contains(&key) {
+            // Counts as an access, then the value is swapped in place.
+            self.get(&key);
+            let slot = if self.window.contains(&key) {
+                self.window.peek_mut(&key)
+            }
+            else if self.protected.contains(&key) {
+                self.protected.peek_mut(&key)
+            }
+            else {
+                self.probation.peek_mut(&key)
+            };
+            let old = std::mem::replace(slot.unwrap(), val);
+            return vec![(key, old)];
+        }
+
+        self.record(&key);
+        self.window.put(key, val);
+
+        let mut evicted:Vec<(K, V)> = Vec::new();
+        if self.window.len() > self.window_capacity {
+            let (k, v) = self.window.pop_lru().unwrap();
+            evicted.extend(self.admit(k, v));
+        }
+        evicted
+    }
+
+    fn contains(&self, key:&K) -> bool {
+        self.window.contains(key) || self.probation.contains(key) || self.protected.contains(key)
+    }
+
+    fn len(&self) -> usize {
+        self.window.len() + self.probation.len() + self.protected.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
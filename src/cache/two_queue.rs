@@ -0,0 +1,88 @@
+use std::hash::Hash;
+use super::lru::LRUCache;
+use super::Cache;
+
+// 2Q (Johnson & Shasha): new keys enter the FIFO A1in and only reach the main
+// LRU queue Am if they are requested again after leaving A1in, while A1out
+// still remembers them. A one-off scan therefore only churns A1in.
+pub struct TwoQueueCache<K, V> {
+    capacity: usize,
+    kin: usize,
+    a1in: LRUCache<K, V>,
+    a1out: LRUCache<K, ()>,
+    am: LRUCache<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V> TwoQueueCache<K, V> {
+    // A1in takes a quarter of the capacity and A1out remembers half as many
+    // keys as the cache holds, the sizes suggested in the paper.
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        Self {
+            capacity,
+            kin: (capacity/4).max(1),
+            a1in: LRUCache::new(capacity),
+            a1out: LRUCache::new((capacity/2).max(1)),
+            am: LRUCache::new(capacity),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> TwoQueueCache<K, V> {
+    fn reclaim(&mut self) -> (K, V) {
+        if self.a1in.len() > self.kin || self.am.is_empty() {
+            let (key, val) = self.a1in.pop_lru().unwrap();
+            self.a1out.put(key.clone(), ());
+            (key, val)
+        }
+        else {
+            self.am.pop_lru().unwrap()
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for TwoQueueCache<K, V> {
+    fn get(&mut self, key:&K) -> Option<&V> {
+        if self.am.contains(key) {
+            return self.am.get(key);
+        }
+        // A1in is a FIFO, hits do not reorder it.
+        self.a1in.peek(key)
+    }
+
+    fn put(&mut self, key:K, val:V) -> Vec<(K, V)> {
+        if self.am.contains(&key) {
+            return self.am.put(key, val);
+        }
+        if let Some(v) = self.a1in.peek_mut(&key) {
+            let old = std::mem::replace(v, val);
+            return vec![(key, old)];
+        }
+
+        let mut evicted:Vec<(K, V)> = Vec::new();
+        if self.len() >= self.capacity {
+            evicted.push(self.reclaim());
+        }
+
+        if self.a1out.remove(&key).is_some() {
+            self.am.put(key, val);
+        }
+        else {
+            self.a1in.put(key, val);
+        }
+        evicted
+    }
+
+    fn contains(&self, key:&K) -> bool {
+        self.am.contains(key) || self.a1in.contains(key)
+    }
+
+    fn len(&self) -> usize {
+        self.a1in.len() + self.am.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
@@ -0,0 +1,256 @@
+use super::bits::{BitReader, BitWriter};
+
+const ADAPTIVE_MAGIC:&[u8; 3] = b"AHF";
+const ADAPTIVE_VERSION:u8 = 1;
+
+// 256 byte leaves plus the NYT leaf give at most 2*257-1 nodes.
+const MAX_NODES:usize = 513;
+const NYT_SYMBOL:u16 = 256;
+
+struct AdaptiveNode {
+    weight: u64,
+    parent: Option<usize>,
+    children: Option<(usize, usize)>,
+    symbol: u16,
+    number: usize,
+}
+
+// Adaptive Huffman coding (FGK). The tree starts with a single NYT ("not yet
+// transmitted") leaf, a new byte is sent as the NYT code followed by its 8 raw
+// bits and then splits the NYT leaf. Nodes are numbered so that weights never
+// decrease with the number and siblings are adjacent (sibling property), and
+// every update swaps a node with the highest numbered node of the same weight
+// before incrementing it. Encoder and decoder run the same updates after every
+// symbol, so they stay in sync without a code table in the stream.
+pub struct AdaptiveHuffman {
+    nodes: Vec<AdaptiveNode>,
+    leaves: Vec<Option<usize>>,
+    order: Vec<usize>,
+    nyt: usize,
+}
+
+impl AdaptiveHuffman {
+    pub fn new() -> Self {
+        let root = AdaptiveNode {
+            weight: 0,
+            parent: None,
+            children: None,
+            symbol: NYT_SYMBOL,
+            number: MAX_NODES-1,
+        };
+
+        let mut order:Vec<usize> = vec![usize::MAX; MAX_NODES];
+        order[MAX_NODES-1] = 0;
+
+        Self {
+            nodes: vec![root],
+            leaves: vec![None; 256],
+            order,
+            nyt: 0,
+        }
+    }
+}
+
+impl Default for AdaptiveHuffman {
+    fn default() -> Self {
+        AdaptiveHuffman::new()
+    }
+}
+
+impl AdaptiveHuffman {
+    fn add_node(&mut self, parent:usize, symbol:u16, number:usize) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(AdaptiveNode {
+            weight: 0,
+            parent: Some(parent),
+            children: None,
+            symbol,
+            number,
+        });
+        self.order[number] = id;
+        id
+    }
+}
+
+impl AdaptiveHuffman {
+    // The old NYT leaf becomes an internal node with the new NYT leaf on the
+    // left and the leaf for `symbol` on the right.
+    fn split_nyt(&mut self, symbol:u8) -> usize {
+        let old = self.nyt;
+        let number = self.nodes[old].number;
+
+        let nyt = self.add_node(old, NYT_SYMBOL, number-2);
+        let leaf = self.add_node(old, symbol as u16, number-1);
+
+        self.nodes[old].children = Some((nyt, leaf));
+        self.leaves[symbol as usize] = Some(leaf);
+        self.nyt = nyt;
+        leaf
+    }
+}
+
+impl AdaptiveHuffman {
+    fn swap_nodes(&mut self, a:usize, b:usize) {
+        let pa = self.nodes[a].parent.unwrap();
+        let pb = self.nodes[b].parent.unwrap();
+
+        if pa == pb {
+            let (l, r) = self.nodes[pa].children.unwrap();
+            self.nodes[pa].children = Some((r, l));
+        }
+        else {
+            let replace = |c:(usize, usize), from:usize, to:usize| if c.0 == from { (to, c.1) } else { (c.0, to) };
+            self.nodes[pa].children = Some(replace(self.nodes[pa].children.unwrap(), a, b));
+            self.nodes[pb].children = Some(replace(self.nodes[pb].children.unwrap(), b, a));
+            self.nodes[a].parent = Some(pb);
+            self.nodes[b].parent = Some(pa);
+        }
+
+        let (na, nb) = (self.nodes[a].number, self.nodes[b].number);
+        self.nodes[a].number = nb;
+        self.nodes[b].number = na;
+        self.order[na] = b;
+        self.order[nb] = a;
+    }
+}
+
+impl AdaptiveHuffman {
+    fn update(&mut self, symbol:u8) {
+        let mut node = match self.leaves[symbol as usize] {
+            Some(leaf) => leaf,
+            None => self.split_nyt(symbol),
+        };
+
+        loop {
+            // Leader of the block: highest numbered node with the same weight.
+            let weight = self.nodes[node].weight;
+            let mut leader = self.nodes[node].number;
+            while leader+1 < MAX_NODES && self.nodes[self.order[leader+1]].weight == weight {
+                leader += 1;
+            }
+
+            let leader = self.order[leader];
+            if leader != node && Some(leader) != self.nodes[node].parent {
+                self.swap_nodes(node, leader);
+            }
+
+            self.nodes[node].weight += 1;
+
+            match self.nodes[node].parent {
+                Some(parent) => node = parent,
+                None => break,
+            }
+        }
+    }
+}
+
+impl AdaptiveHuffman {
+    fn write_path(&self, node:usize, writer:&mut BitWriter) {
+        let mut bits:Vec<bool> = Vec::new();
+        let mut curr = node;
+
+        while let Some(parent) = self.nodes[curr].parent {
+            bits.push(self.nodes[parent].children.unwrap().1 == curr);
+            curr = parent;
+        }
+
+        for bit in bits.into_iter().rev() {
+            writer.write_bit(bit);
+        }
+    }
+}
+
+impl AdaptiveHuffman {
+    pub fn encode_symbol(&mut self, symbol:u8, writer:&mut BitWriter) {
+        match self.leaves[symbol as usize] {
+            Some(leaf) => self.write_path(leaf, writer),
+            None => {
+                self.write_path(self.nyt, writer);
+                writer.write_bits(symbol as u64, 8);
+            }
+        }
+
+        self.update(symbol);
+    }
+}
+
+impl AdaptiveHuffman {
+    pub fn decode_symbol(&mut self, reader:&mut BitReader) -> Result<u8, String> {
+        let truncated = "Adaptive Huffman stream ends in the middle of a code !!!";
+        let mut node:usize = 0;
+
+        while let Some((left, right)) = self.nodes[node].children {
+            node = if reader.read_bit().ok_or(truncated)? { right } else { left };
+        }
+
+        let symbol = if node == self.nyt {
+            reader.read_bits(8).ok_or(truncated)? as u8
+        }
+        else {
+            self.nodes[node].symbol as u8
+        };
+
+        self.update(symbol);
+        Ok(symbol)
+    }
+}
+
+impl AdaptiveHuffman {
+    // Layout: magic "AHF", version, the payload length in bits (u64) and the
+    // payload. The model carries over between calls, so a stream can be sent
+    // as consecutive blocks as long as the decoder sees them in the same order.
+    pub fn encode(&mut self, data:&[u8]) -> Vec<u8> {
+        let mut payload = BitWriter::new();
+        for b in data.iter() {
+            self.encode_symbol(*b, &mut payload);
+        }
+
+        let mut out:Vec<u8> = Vec::new();
+        out.extend_from_slice(ADAPTIVE_MAGIC);
+        out.push(ADAPTIVE_VERSION);
+        out.extend_from_slice(&payload.bit_len().to_le_bytes());
+        out.extend(payload.finish());
+        out
+    }
+}
+
+impl AdaptiveHuffman {
+    pub fn decode(&mut self, encoded:&[u8]) -> Result<Vec<u8>, String> {
+        let header_len = ADAPTIVE_MAGIC.len()+9;
+        if encoded.len() < header_len || &encoded[..ADAPTIVE_MAGIC.len()] != ADAPTIVE_MAGIC {
+            return Err("Not a valid adaptive Huffman stream !!!".to_string());
+        }
+
+        let version = encoded[ADAPTIVE_MAGIC.len()];
+        if version != ADAPTIVE_VERSION {
+            return Err(format!("Unsupported adaptive Huffman version {} !!!", version));
+        }
+
+        let bit_len = u64::from_le_bytes(encoded[ADAPTIVE_MAGIC.len()+1..header_len].try_into().unwrap());
+        let payload = &encoded[header_len..];
+        if bit_len > 8*payload.len() as u64 {
+            return Err("Truncated adaptive Huffman payload !!!".to_string());
+        }
+
+        let mut reader = BitReader::with_limit(payload, bit_len);
+        let mut output:Vec<u8> = Vec::new();
+
+        while reader.remaining() > 0 {
+            output.push(self.decode_symbol(&mut reader)?);
+        }
+
+        Ok(output)
+    }
+}
+
+impl AdaptiveHuffman {
+    pub fn compress(data:&[u8]) -> Vec<u8> {
+        AdaptiveHuffman::new().encode(data)
+    }
+}
+
+impl AdaptiveHuffman {
+    pub fn decompress(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        AdaptiveHuffman::new().decode(encoded)
+    }
+}
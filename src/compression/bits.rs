@@ -0,0 +1,328 @@
+// Bits are packed most significant bit first within every byte.
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    curr: u8,
+    num_bits: u8,
+    total_bits: u64,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            curr: 0,
+            num_bits: 0,
+            total_bits: 0,
+        }
+    }
+}
+
+impl Default for BitWriter {
+    fn default() -> Self {
+        BitWriter::new()
+    }
+}
+
+impl BitWriter {
+    pub fn write_bit(&mut self, bit:bool) {
+        self.curr = (self.curr << 1) | bit as u8;
+        self.num_bits += 1;
+        self.total_bits += 1;
+
+        if self.num_bits == 8 {
+            self.bytes.push(self.curr);
+            self.curr = 0;
+            self.num_bits = 0;
+        }
+    }
+}
+
+impl BitWriter {
+    // Writes the lowest `n` bits of `value`, highest of them first.
+    pub fn write_bits(&mut self, value:u64, n:u8) {
+        assert!(n <= 64, "cannot write more than 64 bits at once");
+
+        for i in (0..n).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+}
+
+impl BitWriter {
+    pub fn bit_len(&self) -> u64 {
+        self.total_bits
+    }
+}
+
+impl BitWriter {
+    // Pads the last byte with zeros.
+    pub fn finish(mut self) -> Vec<u8> {
+        if self.num_bits > 0 {
+            self.bytes.push(self.curr << (8-self.num_bits));
+        }
+        self.bytes
+    }
+}
+
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: u64,
+    limit: u64,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes:&'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            limit: 8*bytes.len() as u64,
+        }
+    }
+}
+
+impl<'a> BitReader<'a> {
+    // Only the first `limit` bits are readable, the rest is padding.
+    pub fn with_limit(bytes:&'a [u8], limit:u64) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            limit: limit.min(8*bytes.len() as u64),
+        }
+    }
+}
+
+impl BitReader<'_> {
+    pub fn read_bit(&mut self) -> Option<bool> {
+        if self.pos >= self.limit {
+            return None;
+        }
+
+        let byte = self.bytes[(self.pos/8) as usize];
+        let bit = (byte >> (7-self.pos%8)) & 1 == 1;
+        self.pos += 1;
+        Some(bit)
+    }
+}
+
+impl BitReader<'_> {
+    pub fn read_bits(&mut self, n:u8) -> Option<u64> {
+        assert!(n <= 64, "cannot read more than 64 bits at once");
+
+        if self.remaining() < n as u64 {
+            return None;
+        }
+
+        let mut value:u64 = 0;
+        for _ in 0..n {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+        Some(value)
+    }
+}
+
+impl BitReader<'_> {
+    // Returns the next `n` bits without consuming them, bits past the end
+    // read as zeros.
+    pub fn peek_bits(&self, n:u8) -> u64 {
+        assert!(n <= 56, "cannot peek more than 56 bits at once");
+
+        if n == 0 {
+            return 0;
+        }
+
+        let start = (self.pos/8) as usize;
+        let mut window:u64 = 0;
+        for i in 0..8 {
+            window = (window << 8) | *self.bytes.get(start+i).unwrap_or(&0) as u64;
+        }
+
+        let mut value = (window << (self.pos%8)) >> (64-n);
+        if self.pos + n as u64 > self.limit {
+            let valid = self.limit.saturating_sub(self.pos);
+            value &= !((1u64 << (n as u64-valid))-1);
+        }
+        value
+    }
+}
+
+impl BitReader<'_> {
+    pub fn skip(&mut self, n:u64) -> bool {
+        if self.remaining() < n {
+            return false;
+        }
+        self.pos += n;
+        true
+    }
+}
+
+impl BitReader<'_> {
+    pub fn remaining(&self) -> u64 {
+        self.limit-self.pos
+    }
+}
+
+impl BitReader<'_> {
+    // Skips to the next byte boundary and returns the number of bytes consumed.
+    pub fn align(&mut self) -> usize {
+        self.pos = self.pos.div_ceil(8)*8;
+        self.pos = self.pos.min(self.limit);
+        self.pos.div_ceil(8) as usize
+    }
+}
+
+// Bits are packed least significant bit first within every byte, as in DEFLATE.
+pub struct LsbBitWriter {
+    bytes: Vec<u8>,
+    curr: u64,
+    num_bits: u8,
+    total_bits: u64,
+}
+
+impl LsbBitWriter {
+    pub fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            curr: 0,
+            num_bits: 0,
+            total_bits: 0,
+        }
+    }
+}
+
+impl Default for LsbBitWriter {
+    fn default() -> Self {
+        LsbBitWriter::new()
+    }
+}
+
+impl LsbBitWriter {
+    // Writes the lowest `n` bits of `value`, lowest of them first.
+    pub fn write_bits(&mut self, value:u64, n:u8) {
+        assert!(n <= 32, "cannot write more than 32 bits at once");
+
+        if n == 0 {
+            return;
+        }
+
+        self.curr |= (value & ((1u64 << n)-1)) << self.num_bits;
+        self.num_bits += n;
+        self.total_bits += n as u64;
+
+        while self.num_bits >= 8 {
+            self.bytes.push(self.curr as u8);
+            self.curr >>= 8;
+            self.num_bits -= 8;
+        }
+    }
+}
+
+impl LsbBitWriter {
+    // Pads with zeros up to the next byte boundary.
+    pub fn align(&mut self) {
+        if self.num_bits > 0 {
+            let pad = 8-self.num_bits;
+            self.write_bits(0, pad);
+        }
+    }
+}
+
+impl LsbBitWriter {
+    // Appends whole bytes, the writer must be byte aligned.
+    pub fn write_bytes(&mut self, data:&[u8]) {
+        assert!(self.num_bits == 0, "writer is not byte aligned");
+
+        self.bytes.extend_from_slice(data);
+        self.total_bits += 8*data.len() as u64;
+    }
+}
+
+impl LsbBitWriter {
+    pub fn bit_len(&self) -> u64 {
+        self.total_bits
+    }
+}
+
+impl LsbBitWriter {
+    pub fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+pub struct LsbBitReader<'a> {
+    bytes: &'a [u8],
+    pos: u64,
+}
+
+impl<'a> LsbBitReader<'a> {
+    pub fn new(bytes:&'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+        }
+    }
+}
+
+impl LsbBitReader<'_> {
+    // Returns the next `n` bits without consuming them, the first bit in the
+    // lowest position. Bits past the end read as zeros.
+    pub fn peek_bits(&self, n:u8) -> u64 {
+        assert!(n <= 56, "cannot peek more than 56 bits at once");
+
+        let start = (self.pos/8) as usize;
+        let mut window:u64 = 0;
+        for i in 0..8 {
+            window |= (*self.bytes.get(start+i).unwrap_or(&0) as u64) << (8*i);
+        }
+
+        (window >> (self.pos%8)) & ((1u64 << n)-1)
+    }
+}
+
+impl LsbBitReader<'_> {
+    pub fn read_bits(&mut self, n:u8) -> Option<u64> {
+        if self.remaining() < n as u64 {
+            return None;
+        }
+
+        let value = self.peek_bits(n);
+        self.pos += n as u64;
+        Some(value)
+    }
+}
+
+impl LsbBitReader<'_> {
+    pub fn skip(&mut self, n:u64) -> bool {
+        if self.remaining() < n {
+            return false;
+        }
+        self.pos += n;
+        true
+    }
+}
+
+impl LsbBitReader<'_> {
+    pub fn remaining(&self) -> u64 {
+        8*self.bytes.len() as u64-self.pos
+    }
+}
+
+impl LsbBitReader<'_> {
+    // Skips to the next byte boundary and returns the number of bytes consumed.
+    pub fn align(&mut self) -> usize {
+        self.pos = self.pos.div_ceil(8)*8;
+        (self.pos/8) as usize
+    }
+}
+
+impl<'a> LsbBitReader<'a> {
+    // Reads `n` whole bytes, the reader must be byte aligned.
+    pub fn read_bytes(&mut self, n:usize) -> Option<&'a [u8]> {
+        assert!(self.pos.is_multiple_of(8), "reader is not byte aligned");
+
+        let start = (self.pos/8) as usize;
+        let bytes = self.bytes.get(start..start+n)?;
+        self.pos += 8*n as u64;
+        Some(bytes)
+    }
+}
@@ -0,0 +1,59 @@
+// CRC-32 as used by zip, gzip and PNG (reflected polynomial 0xEDB88320).
+const CRC32_POLY:u32 = 0xEDB88320;
+
+const fn get_table() -> [u32; 256] {
+    let mut table:[u32; 256] = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 { CRC32_POLY ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+}
+
+const CRC32_TABLE:[u32; 256] = get_table();
+
+// Incremental CRC-32, feed data with `update` and read the checksum with `finish`.
+pub struct Crc32 {
+    value: u32,
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self {
+            value: 0xFFFFFFFF,
+        }
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32::new()
+    }
+}
+
+impl Crc32 {
+    pub fn update(&mut self, data:&[u8]) {
+        for b in data.iter() {
+            self.value = CRC32_TABLE[((self.value ^ *b as u32) & 0xFF) as usize] ^ (self.value >> 8);
+        }
+    }
+}
+
+impl Crc32 {
+    pub fn finish(&self) -> u32 {
+        self.value ^ 0xFFFFFFFF
+    }
+}
+
+pub fn crc32(data:&[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
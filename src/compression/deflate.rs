@@ -0,0 +1,505 @@
+use super::bits::{LsbBitReader, LsbBitWriter};
+use super::huffman::{get_canonical_codes, get_code_lengths};
+use super::lz77::{Lz77, Token};
+
+// Raw DEFLATE (RFC 1951) streams, without a zlib or gzip wrapper.
+
+const END_OF_BLOCK:usize = 256;
+const NUM_LITLEN:usize = 286;
+const NUM_DIST:usize = 30;
+const MAX_CODE_LEN:u8 = 15;
+const MAX_CL_CODE_LEN:u8 = 7;
+
+// Blocks never cover more input than a stored block can hold.
+const MAX_BLOCK_SIZE:usize = 65535;
+
+const LENGTH_BASE:[u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA:[u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE:[u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA:[u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// Order in which the code length code lengths are sent.
+const CL_ORDER:[usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+fn get_length_code(length:u16) -> usize {
+    LENGTH_BASE.partition_point(|b| *b <= length)-1
+}
+
+fn get_dist_code(distance:u16) -> usize {
+    DIST_BASE.partition_point(|b| *b <= distance)-1
+}
+
+fn get_fixed_lengths() -> (Vec<u8>, Vec<u8>) {
+    let mut litlen:Vec<u8> = vec![0; 288];
+    for (s, l) in litlen.iter_mut().enumerate() {
+        *l = match s {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (litlen, vec![5; 32])
+}
+
+// DEFLATE sends Huffman codes starting from their most significant bit while
+// everything else is packed least significant bit first.
+fn reverse_bits(code:u32, len:u8) -> u32 {
+    code.reverse_bits() >> (32-len as u32)
+}
+
+fn write_code(writer:&mut LsbBitWriter, codes:&[(u32, u8)], symbol:usize) {
+    let (code, len) = codes[symbol];
+    writer.write_bits(reverse_bits(code, len) as u64, len);
+}
+
+// Run-length encodes code lengths with the symbols 16 (repeat the previous
+// length 3-6 times), 17 (3-10 zeros) and 18 (11-138 zeros), as (symbol, extra bits value).
+fn get_cl_symbols(lengths:&[u8]) -> Vec<(u8, u8)> {
+    let mut symbols:Vec<(u8, u8)> = Vec::new();
+    let mut i:usize = 0;
+
+    while i < lengths.len() {
+        let l = lengths[i];
+        let mut run:usize = 1;
+        while i+run < lengths.len() && lengths[i+run] == l {
+            run += 1;
+        }
+        i += run;
+
+        if l == 0 {
+            while run >= 11 {
+                let r = run.min(138);
+                symbols.push((18, (r-11) as u8));
+                run -= r;
+            }
+            if run >= 3 {
+                symbols.push((17, (run-3) as u8));
+                run = 0;
+            }
+        }
+        else {
+            symbols.push((l, 0));
+            run -= 1;
+            while run >= 3 {
+                let r = run.min(6);
+                symbols.push((16, (r-3) as u8));
+                run -= r;
+            }
+        }
+
+        for _ in 0..run {
+            symbols.push((l, 0));
+        }
+    }
+
+    symbols
+}
+
+fn get_cl_extra_bits(symbol:u8) -> u8 {
+    match symbol {
+        16 => 2,
+        17 => 3,
+        18 => 7,
+        _ => 0,
+    }
+}
+
+// Symbol frequencies of one block plus the extra bits its lengths and
+// distances need, which are the same under any code.
+struct BlockStats {
+    litlen: Vec<u64>,
+    dist: Vec<u64>,
+    extra_bits: u64,
+}
+
+impl BlockStats {
+    fn new(tokens:&[Token]) -> Self {
+        let mut stats = BlockStats {
+            litlen: vec![0; NUM_LITLEN],
+            dist: vec![0; NUM_DIST],
+            extra_bits: 0,
+        };
+
+        for token in tokens.iter() {
+            match *token {
+                Token::Literal(b) => stats.litlen[b as usize] += 1,
+                Token::Match { length, distance } => {
+                    let lc = get_length_code(length);
+                    let dc = get_dist_code(distance);
+                    stats.litlen[257+lc] += 1;
+                    stats.dist[dc] += 1;
+                    stats.extra_bits += (LENGTH_EXTRA[lc] + DIST_EXTRA[dc]) as u64;
+                }
+            }
+        }
+
+        stats.litlen[END_OF_BLOCK] += 1;
+        stats
+    }
+}
+
+impl BlockStats {
+    fn get_cost(&self, litlen_lengths:&[u8], dist_lengths:&[u8]) -> u64 {
+        let lit:u64 = self.litlen.iter().zip(litlen_lengths.iter()).map(|(f, l)| f * *l as u64).sum();
+        let dist:u64 = self.dist.iter().zip(dist_lengths.iter()).map(|(f, l)| f * *l as u64).sum();
+        lit + dist + self.extra_bits
+    }
+}
+
+// Code lengths and the header describing them for a dynamic Huffman block.
+struct DynamicHeader {
+    litlen_lengths: Vec<u8>,
+    dist_lengths: Vec<u8>,
+    cl_lengths: Vec<u8>,
+    cl_symbols: Vec<(u8, u8)>,
+    hlit: usize,
+    hdist: usize,
+    hclen: usize,
+}
+
+impl DynamicHeader {
+    fn new(stats:&BlockStats) -> Self {
+        let litlen_lengths = get_code_lengths(&stats.litlen, MAX_CODE_LEN);
+        let mut dist_lengths = get_code_lengths(&stats.dist, MAX_CODE_LEN);
+
+        // A block without matches still sends one distance code.
+        if dist_lengths.iter().all(|l| *l == 0) {
+            dist_lengths[0] = 1;
+        }
+
+        let hlit = (litlen_lengths.iter().rposition(|l| *l > 0).unwrap()+1).max(257);
+        let hdist = dist_lengths.iter().rposition(|l| *l > 0).unwrap()+1;
+
+        let mut all_lengths = litlen_lengths[..hlit].to_vec();
+        all_lengths.extend_from_slice(&dist_lengths[..hdist]);
+        let cl_symbols = get_cl_symbols(&all_lengths);
+
+        let mut cl_freq:Vec<u64> = vec![0; 19];
+        for (s, _) in cl_symbols.iter() {
+            cl_freq[*s as usize] += 1;
+        }
+        let mut cl_lengths = get_code_lengths(&cl_freq, MAX_CL_CODE_LEN);
+
+        // Inflaters reject an incomplete code length code, so a lone symbol
+        // gets an unused sibling.
+        if cl_lengths.iter().filter(|l| **l > 0).count() == 1 {
+            let unused = if cl_lengths[0] == 0 { 0 } else { 1 };
+            cl_lengths[unused] = 1;
+        }
+        let hclen = (CL_ORDER.iter().rposition(|s| cl_lengths[*s] > 0).unwrap()+1).max(4);
+
+        Self {
+            litlen_lengths,
+            dist_lengths,
+            cl_lengths,
+            cl_symbols,
+            hlit,
+            hdist,
+            hclen,
+        }
+    }
+}
+
+impl DynamicHeader {
+    fn get_cost(&self) -> u64 {
+        let symbols:u64 = self.cl_symbols.iter().map(|(s, _)| (self.cl_lengths[*s as usize] + get_cl_extra_bits(*s)) as u64).sum();
+        14 + 3*self.hclen as u64 + symbols
+    }
+}
+
+impl DynamicHeader {
+    fn write(&self, writer:&mut LsbBitWriter) {
+        writer.write_bits((self.hlit-257) as u64, 5);
+        writer.write_bits((self.hdist-1) as u64, 5);
+        writer.write_bits((self.hclen-4) as u64, 4);
+
+        for s in CL_ORDER.iter().take(self.hclen) {
+            writer.write_bits(self.cl_lengths[*s] as u64, 3);
+        }
+
+        let cl_codes = get_canonical_codes(&self.cl_lengths);
+        for (s, extra) in self.cl_symbols.iter() {
+            write_code(writer, &cl_codes, *s as usize);
+            writer.write_bits(*extra as u64, get_cl_extra_bits(*s));
+        }
+    }
+}
+
+fn write_tokens(writer:&mut LsbBitWriter, tokens:&[Token], litlen_lengths:&[u8], dist_lengths:&[u8]) {
+    let litlen_codes = get_canonical_codes(litlen_lengths);
+    let dist_codes = get_canonical_codes(dist_lengths);
+
+    for token in tokens.iter() {
+        match *token {
+            Token::Literal(b) => write_code(writer, &litlen_codes, b as usize),
+            Token::Match { length, distance } => {
+                let lc = get_length_code(length);
+                write_code(writer, &litlen_codes, 257+lc);
+                writer.write_bits((length-LENGTH_BASE[lc]) as u64, LENGTH_EXTRA[lc]);
+
+                let dc = get_dist_code(distance);
+                write_code(writer, &dist_codes, dc);
+                writer.write_bits((distance-DIST_BASE[dc]) as u64, DIST_EXTRA[dc]);
+            }
+        }
+    }
+
+    write_code(writer, &litlen_codes, END_OF_BLOCK);
+}
+
+// Single level lookup table indexed by the next `max_len` bits of the stream
+// in reading order, the counterpart of `huffman::DecodeTable` for LSB-first
+// streams. Incomplete codes are allowed, as DEFLATE sends them for blocks with
+// a single distance code.
+struct InflateTable {
+    max_len: u8,
+    entries: Vec<(u16, u8)>,
+}
+
+impl InflateTable {
+    fn new(lengths:&[u8]) -> Result<Self, String> {
+        let max_len = lengths.iter().copied().max().unwrap_or(0);
+        if max_len > MAX_CODE_LEN {
+            return Err(format!("Huffman code length {} is too long !!!", max_len));
+        }
+
+        let kraft:u64 = lengths.iter().filter(|l| **l > 0).map(|l| 1u64 << (max_len-*l)).sum();
+        if kraft > 1u64 << max_len {
+            return Err("Huffman code lengths are over-subscribed !!!".to_string());
+        }
+
+        let codes = get_canonical_codes(lengths);
+        let mut entries:Vec<(u16, u8)> = vec![(0, 0); 1 << max_len];
+
+        for (s, (code, len)) in codes.iter().enumerate() {
+            if *len == 0 {
+                continue;
+            }
+            let start = reverse_bits(*code, *len) as usize;
+            for e in entries.iter_mut().skip(start).step_by(1 << len) {
+                *e = (s as u16, *len);
+            }
+        }
+
+        Ok(Self {
+            max_len,
+            entries,
+        })
+    }
+}
+
+impl InflateTable {
+    fn decode_symbol(&self, reader:&mut LsbBitReader) -> Result<usize, String> {
+        let (symbol, len) = self.entries[reader.peek_bits(self.max_len) as usize];
+        if len == 0 {
+            return Err("Invalid Huffman code !!!".to_string());
+        }
+        if !reader.skip(len as u64) {
+            return Err("Deflate stream ends in the middle of a code !!!".to_string());
+        }
+        Ok(symbol as usize)
+    }
+}
+
+fn read_bits(reader:&mut LsbBitReader, n:u8) -> Result<u64, String> {
+    reader.read_bits(n).ok_or("Truncated deflate stream !!!".to_string())
+}
+
+fn read_dynamic_tables(reader:&mut LsbBitReader) -> Result<(InflateTable, InflateTable), String> {
+    let hlit = read_bits(reader, 5)? as usize + 257;
+    let hdist = read_bits(reader, 5)? as usize + 1;
+    let hclen = read_bits(reader, 4)? as usize + 4;
+
+    if hlit > NUM_LITLEN || hdist > NUM_DIST {
+        return Err("Too many Huffman codes in deflate block !!!".to_string());
+    }
+
+    let mut cl_lengths:Vec<u8> = vec![0; 19];
+    for s in CL_ORDER.iter().take(hclen) {
+        cl_lengths[*s] = read_bits(reader, 3)? as u8;
+    }
+    let cl_table = InflateTable::new(&cl_lengths)?;
+
+    let mut lengths:Vec<u8> = Vec::with_capacity(hlit+hdist);
+    while lengths.len() < hlit+hdist {
+        let symbol = cl_table.decode_symbol(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let prev = *lengths.last().ok_or("Repeat without a previous code length !!!")?;
+                (prev, 3 + read_bits(reader, 2)? as usize)
+            }
+            17 => (0, 3 + read_bits(reader, 3)? as usize),
+            _ => (0, 11 + read_bits(reader, 7)? as usize),
+        };
+
+        if lengths.len()+repeat > hlit+hdist {
+            return Err("Code lengths overrun the deflate tables !!!".to_string());
+        }
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+
+    if lengths[END_OF_BLOCK] == 0 {
+        return Err("Deflate block has no end of block code !!!".to_string());
+    }
+
+    Ok((InflateTable::new(&lengths[..hlit])?, InflateTable::new(&lengths[hlit..])?))
+}
+
+fn inflate_block(reader:&mut LsbBitReader, litlen:&InflateTable, dist:&InflateTable, output:&mut Vec<u8>) -> Result<(), String> {
+    loop {
+        let symbol = litlen.decode_symbol(reader)?;
+
+        if symbol < END_OF_BLOCK {
+            output.push(symbol as u8);
+            continue;
+        }
+        if symbol == END_OF_BLOCK {
+            return Ok(());
+        }
+
+        let lc = symbol-257;
+        if lc >= LENGTH_BASE.len() {
+            return Err(format!("Invalid length code {} !!!", symbol));
+        }
+        let length = LENGTH_BASE[lc] as usize + read_bits(reader, LENGTH_EXTRA[lc])? as usize;
+
+        let dc = dist.decode_symbol(reader)?;
+        if dc >= DIST_BASE.len() {
+            return Err(format!("Invalid distance code {} !!!", dc));
+        }
+        let distance = DIST_BASE[dc] as usize + read_bits(reader, DIST_EXTRA[dc])? as usize;
+
+        if distance > output.len() {
+            return Err(format!("Match distance {} is out of range !!!", distance));
+        }
+        for _ in 0..length {
+            output.push(output[output.len()-distance]);
+        }
+    }
+}
+
+// LZ77 followed by Huffman coding. Every block is written in whichever of
+// the stored, fixed Huffman or dynamic Huffman forms is smallest.
+pub struct Deflate {
+    lz77: Lz77,
+}
+
+impl Deflate {
+    pub fn new(lz77:Lz77) -> Self {
+        Self {
+            lz77,
+        }
+    }
+}
+
+impl Default for Deflate {
+    fn default() -> Self {
+        Deflate::new(Lz77::default())
+    }
+}
+
+impl Deflate {
+    fn write_block(&self, writer:&mut LsbBitWriter, tokens:&[Token], raw:&[u8], is_final:bool) {
+        let stats = BlockStats::new(tokens);
+        let dynamic = DynamicHeader::new(&stats);
+        let (fixed_litlen, fixed_dist) = get_fixed_lengths();
+
+        let dynamic_cost = dynamic.get_cost() + stats.get_cost(&dynamic.litlen_lengths, &dynamic.dist_lengths);
+        let fixed_cost = stats.get_cost(&fixed_litlen, &fixed_dist);
+        let stored_cost = 7 + 32 + 8*raw.len() as u64;
+
+        writer.write_bits(is_final as u64, 1);
+
+        if stored_cost < dynamic_cost.min(fixed_cost) {
+            writer.write_bits(0, 2);
+            writer.align();
+            writer.write_bits(raw.len() as u64, 16);
+            writer.write_bits(!(raw.len() as u16) as u64, 16);
+            writer.write_bytes(raw);
+        }
+        else if fixed_cost <= dynamic_cost {
+            writer.write_bits(1, 2);
+            write_tokens(writer, tokens, &fixed_litlen, &fixed_dist);
+        }
+        else {
+            writer.write_bits(2, 2);
+            dynamic.write(writer);
+            write_tokens(writer, tokens, &dynamic.litlen_lengths, &dynamic.dist_lengths);
+        }
+    }
+}
+
+impl Deflate {
+    pub fn encode(&self, data:&[u8]) -> Vec<u8> {
+        let tokens = self.lz77.tokenize(data);
+        let mut writer = LsbBitWriter::new();
+
+        let mut start:usize = 0;
+        let mut pos:usize = 0;
+        let mut first_token:usize = 0;
+
+        for (i, token) in tokens.iter().enumerate() {
+            if pos+token.input_len()-start > MAX_BLOCK_SIZE {
+                self.write_block(&mut writer, &tokens[first_token..i], &data[start..pos], false);
+                start = pos;
+                first_token = i;
+            }
+            pos += token.input_len();
+        }
+
+        self.write_block(&mut writer, &tokens[first_token..], &data[start..], true);
+        writer.finish()
+    }
+}
+
+impl Deflate {
+    // Bytes after the final block are ignored.
+    pub fn decode(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        let mut reader = LsbBitReader::new(encoded);
+        let mut output:Vec<u8> = Vec::new();
+
+        loop {
+            let is_final = read_bits(&mut reader, 1)? == 1;
+
+            match read_bits(&mut reader, 2)? {
+                0 => {
+                    reader.align();
+                    let len = read_bits(&mut reader, 16)? as u16;
+                    let nlen = read_bits(&mut reader, 16)? as u16;
+                    if len != !nlen {
+                        return Err("Stored block length does not match its complement !!!".to_string());
+                    }
+                    let bytes = reader.read_bytes(len as usize).ok_or("Truncated stored block !!!")?;
+                    output.extend_from_slice(bytes);
+                }
+                1 => {
+                    let (litlen, dist) = get_fixed_lengths();
+                    inflate_block(&mut reader, &InflateTable::new(&litlen)?, &InflateTable::new(&dist)?, &mut output)?;
+                }
+                2 => {
+                    let (litlen, dist) = read_dynamic_tables(&mut reader)?;
+                    inflate_block(&mut reader, &litlen, &dist, &mut output)?;
+                }
+                _ => return Err("Invalid deflate block type !!!".to_string()),
+            }
+
+            if is_final {
+                return Ok(output);
+            }
+        }
+    }
+}
+
+impl Deflate {
+    pub fn compress(data:&[u8]) -> Vec<u8> {
+        Deflate::default().encode(data)
+    }
+}
+
+impl Deflate {
+    pub fn decompress(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        Deflate::decode(encoded)
+    }
+}
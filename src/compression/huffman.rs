@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use super::bits::{BitReader, BitWriter};
+use super::get_frequencies;
+
+const HUFFMAN_MAGIC:&[u8; 3] = b"HUF";
+const HUFFMAN_VERSION:u8 = 2;
+pub const DEFAULT_MAX_CODE_LEN:u8 = 15;
+
+// Optimal code lengths no longer than `max_len` using package-merge
+// (Larmore & Hirschberg). Symbols with zero frequency get length 0, a lone
+// symbol gets length 1 so that it still has a code.
+pub fn get_code_lengths(frequencies:&[u64], max_len:u8) -> Vec<u8> {
+    let mut lengths:Vec<u8> = vec![0; frequencies.len()];
+
+    let mut leaves:Vec<(u64, usize)> = frequencies.iter().enumerate().filter(|(_, f)| **f > 0).map(|(s, f)| (*f, s)).collect();
+    leaves.sort();
+
+    if leaves.len() <= 2 {
+        for (_, s) in leaves.iter() {
+            lengths[*s] = 1;
+        }
+        return lengths;
+    }
+
+    assert!(max_len < 64 && (1u64 << max_len) >= leaves.len() as u64, "{} symbols do not fit in codes of {} bits", leaves.len(), max_len);
+
+    // Every item is a weight and the leaves it covers, a leaf's code length is
+    // the number of selected items that contain it.
+    let leaf_items:Vec<(u64, Vec<usize>)> = leaves.iter().map(|(f, s)| (*f, vec![*s])).collect();
+    let mut items = leaf_items.clone();
+
+    for _ in 1..max_len {
+        let mut packages:Vec<(u64, Vec<usize>)> = Vec::with_capacity(items.len()/2);
+        for pair in items.chunks_exact(2) {
+            let mut symbols = pair[0].1.clone();
+            symbols.extend_from_slice(&pair[1].1);
+            packages.push((pair[0].0+pair[1].0, symbols));
+        }
+
+        let mut merged:Vec<(u64, Vec<usize>)> = Vec::with_capacity(leaf_items.len()+packages.len());
+        let (mut i, mut j) = (0, 0);
+        while i < leaf_items.len() || j < packages.len() {
+            if j == packages.len() || (i < leaf_items.len() && leaf_items[i].0 <= packages[j].0) {
+                merged.push(leaf_items[i].clone());
+                i += 1;
+            }
+            else {
+                merged.push(std::mem::take(&mut packages[j]));
+                j += 1;
+            }
+        }
+
+        items = merged;
+    }
+
+    for (_, symbols) in items.iter().take(2*leaves.len()-2) {
+        for s in symbols.iter() {
+            lengths[*s] += 1;
+        }
+    }
+
+    lengths
+}
+
+// Canonical codes (as in DEFLATE): shorter codes first, within the same length
+// in symbol order. Returns (code, length) per symbol with the first bit of the
+// code in the highest position.
+pub fn get_canonical_codes(lengths:&[u8]) -> Vec<(u32, u8)> {
+    let max_len = lengths.iter().copied().max().unwrap_or(0) as usize;
+    assert!(max_len <= 32, "canonical codes are limited to 32 bits");
+
+    let mut bl_count:Vec<u32> = vec![0; max_len+1];
+    for l in lengths.iter() {
+        if *l > 0 {
+            bl_count[*l as usize] += 1;
+        }
+    }
+
+    let mut next_code:Vec<u32> = vec![0; max_len+2];
+    let mut code:u32 = 0;
+    for len in 1..=max_len {
+        code = (code + bl_count[len-1]) << 1;
+        next_code[len] = code;
+    }
+
+    let mut codes:Vec<(u32, u8)> = vec![(0, 0); lengths.len()];
+    for (s, l) in lengths.iter().enumerate() {
+        if *l > 0 {
+            codes[s] = (next_code[*l as usize], *l);
+            next_code[*l as usize] += 1;
+        }
+    }
+
+    codes
+}
+
+// Single level lookup table indexed by the next `max_len` bits of the stream,
+// every entry holds the decoded symbol and the length of its code.
+pub struct DecodeTable {
+    max_len: u8,
+    entries: Vec<(u16, u8)>,
+}
+
+impl DecodeTable {
+    pub fn new(lengths:&[u8]) -> Result<Self, String> {
+        let max_len = lengths.iter().copied().max().unwrap_or(0);
+        if max_len > 20 {
+            return Err(format!("Huffman code length {} is too long !!!", max_len));
+        }
+
+        // Kraft inequality: an over-subscribed set of lengths is not a prefix code.
+        let kraft:u64 = lengths.iter().filter(|l| **l > 0).map(|l| 1u64 << (max_len-*l)).sum();
+        if kraft > 1u64 << max_len {
+            return Err("Huffman code lengths are over-subscribed !!!".to_string());
+        }
+
+        let codes = get_canonical_codes(lengths);
+        let mut entries:Vec<(u16, u8)> = vec![(0, 0); 1 << max_len];
+
+        for (s, (code, len)) in codes.iter().enumerate() {
+            if *len == 0 {
+                continue;
+            }
+            let shift = max_len-len;
+            let start = (*code as usize) << shift;
+            for e in entries[start..start + (1 << shift)].iter_mut() {
+                *e = (s as u16, *len);
+            }
+        }
+
+        Ok(Self {
+            max_len,
+            entries,
+        })
+    }
+}
+
+impl DecodeTable {
+    pub fn decode_symbol(&self, reader:&mut BitReader) -> Result<u16, String> {
+        let (symbol, len) = self.entries[reader.peek_bits(self.max_len) as usize];
+        if len == 0 {
+            return Err("Invalid Huffman code !!!".to_string());
+        }
+        if !reader.skip(len as u64) {
+            return Err("Huffman stream ends in the middle of a code !!!".to_string());
+        }
+        Ok(symbol)
+    }
+}
+
+pub struct Huffman {
+    max_code_len: u8,
+    lengths: Vec<u8>,
+    codes: Vec<(u32, u8)>,
+}
+
+impl Huffman {
+    pub fn new() -> Self {
+        Huffman::with_max_code_len(DEFAULT_MAX_CODE_LEN)
+    }
+}
+
+impl Default for Huffman {
+    fn default() -> Self {
+        Huffman::new()
+    }
+}
+
+impl Huffman {
+    pub fn with_max_code_len(max_code_len:u8) -> Self {
+        assert!((8..=20).contains(&max_code_len), "max code length must be between 8 and 20");
+
+        Self {
+            max_code_len,
+            lengths: vec![0; 256],
+            codes: vec![(0, 0); 256],
+        }
+    }
+}
+
+impl Huffman {
+    pub fn insert(&mut self, data:&[u8]) {
+        let frequencies = get_frequencies(data);
+        self.lengths = get_code_lengths(&frequencies, self.max_code_len);
+        self.codes = get_canonical_codes(&self.lengths);
+    }
+}
+
+impl Huffman {
+    pub fn print_codes(&self) {
+        for (s, (code, len)) in self.codes.iter().enumerate() {
+            if *len > 0 {
+                println!("{:?} {:0width$b}", s as u8 as char, code, width = *len as usize);
+            }
+        }
+    }
+}
+
+impl Huffman {
+    // Codes as (bits, length) with the first bit of the code in the highest position.
+    pub fn get_codes(&self) -> HashMap<u8, (u64, u8)> {
+        let mut codes:HashMap<u8, (u64, u8)> = HashMap::new();
+        for (s, (code, len)) in self.codes.iter().enumerate() {
+            if *len > 0 {
+                codes.insert(s as u8, (*code as u64, *len));
+            }
+        }
+        codes
+    }
+}
+
+impl Huffman {
+    pub fn get_code_lengths(&self) -> &[u8] {
+        &self.lengths
+    }
+}
+
+impl Huffman {
+    // Layout: magic "HUF", version, number of symbols (u16), a (symbol, code
+    // length) byte pair per symbol, the payload length in bits (u64) and the
+    // payload. Canonical codes are fully determined by the lengths.
+    pub fn encode(&self, data:&[u8]) -> Option<Vec<u8>> {
+        let mut payload = BitWriter::new();
+        for b in data.iter() {
+            let (code, len) = self.codes[*b as usize];
+            if len == 0 {
+                return None;
+            }
+            payload.write_bits(code as u64, len);
+        }
+
+        let symbols:Vec<usize> = (0..256).filter(|s| self.lengths[*s] > 0).collect();
+
+        let mut out:Vec<u8> = Vec::new();
+        out.extend_from_slice(HUFFMAN_MAGIC);
+        out.push(HUFFMAN_VERSION);
+        out.extend_from_slice(&(symbols.len() as u16).to_le_bytes());
+        for s in symbols {
+            out.push(s as u8);
+            out.push(self.lengths[s]);
+        }
+        out.extend_from_slice(&payload.bit_len().to_le_bytes());
+        out.extend(payload.finish());
+
+        Some(out)
+    }
+}
+
+impl Huffman {
+    pub fn decode(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        let header_len = HUFFMAN_MAGIC.len()+3;
+        if encoded.len() < header_len || &encoded[..HUFFMAN_MAGIC.len()] != HUFFMAN_MAGIC {
+            return Err("Not a valid Huffman stream !!!".to_string());
+        }
+
+        let version = encoded[HUFFMAN_MAGIC.len()];
+        if version != HUFFMAN_VERSION {
+            return Err(format!("Unsupported Huffman version {} !!!", version));
+        }
+
+        let num_symbols = u16::from_le_bytes([encoded[header_len-2], encoded[header_len-1]]) as usize;
+        if num_symbols > 256 {
+            return Err("Too many symbols in Huffman table !!!".to_string());
+        }
+
+        let table = encoded.get(header_len..header_len+2*num_symbols).ok_or("Truncated Huffman table !!!")?;
+        let mut lengths:Vec<u8> = vec![0; 256];
+        for pair in table.chunks_exact(2) {
+            if pair[1] == 0 || lengths[pair[0] as usize] != 0 {
+                return Err("Invalid Huffman table !!!".to_string());
+            }
+            lengths[pair[0] as usize] = pair[1];
+        }
+
+        let pos = header_len+2*num_symbols;
+        let bit_len_bytes = encoded.get(pos..pos+8).ok_or("Truncated Huffman stream !!!")?;
+        let bit_len = u64::from_le_bytes(bit_len_bytes.try_into().unwrap());
+
+        let payload = &encoded[pos+8..];
+        if bit_len > 8*payload.len() as u64 {
+            return Err("Truncated Huffman payload !!!".to_string());
+        }
+
+        let decode_table = DecodeTable::new(&lengths)?;
+        let mut reader = BitReader::with_limit(payload, bit_len);
+        let mut output:Vec<u8> = Vec::new();
+
+        while reader.remaining() > 0 {
+            output.push(decode_table.decode_symbol(&mut reader)? as u8);
+        }
+
+        Ok(output)
+    }
+}
+
+impl Huffman {
+    pub fn compress(data:&[u8]) -> Vec<u8> {
+        let mut hm = Huffman::new();
+        hm.insert(data);
+        hm.encode(data).unwrap()
+    }
+}
+
+impl Huffman {
+    pub fn decompress(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        Huffman::decode(encoded)
+    }
+}
@@ -0,0 +1,173 @@
+pub const MIN_MATCH:usize = 3;
+pub const MAX_MATCH:usize = 258;
+pub const MAX_WINDOW_SIZE:usize = 32768;
+pub const DEFAULT_MAX_CHAIN:usize = 128;
+
+const HASH_BITS:u32 = 15;
+const NONE:usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    // Copy `length` bytes starting `distance` bytes back, the copy may overlap
+    // the bytes it produces.
+    Match { length: u16, distance: u16 },
+}
+
+impl Token {
+    // Number of input bytes the token stands for.
+    pub fn input_len(&self) -> usize {
+        match self {
+            Token::Literal(_) => 1,
+            Token::Match { length, .. } => *length as usize,
+        }
+    }
+}
+
+// LZ77 matcher with hash chains: the head table maps the hash of the next
+// 3 bytes to the latest position with that hash, and `prev` links every
+// position to the previous one with the same hash. At most `max_chain`
+// candidates within the window are compared per position, and a match is
+// deferred by one byte when the next position has a longer one (lazy matching).
+pub struct Lz77 {
+    window_size: usize,
+    max_chain: usize,
+}
+
+impl Lz77 {
+    pub fn new(window_size:usize, max_chain:usize) -> Self {
+        assert!((1..=MAX_WINDOW_SIZE).contains(&window_size), "window size must be between 1 and {}", MAX_WINDOW_SIZE);
+        assert!(max_chain > 0, "chain length must be positive");
+
+        Self {
+            window_size,
+            max_chain,
+        }
+    }
+}
+
+impl Default for Lz77 {
+    fn default() -> Self {
+        Lz77::new(MAX_WINDOW_SIZE, DEFAULT_MAX_CHAIN)
+    }
+}
+
+impl Lz77 {
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+}
+
+fn get_hash(data:&[u8], i:usize) -> usize {
+    let x = (data[i] as u32) | (data[i+1] as u32) << 8 | (data[i+2] as u32) << 16;
+    (x.wrapping_mul(2654435761) >> (32-HASH_BITS)) as usize
+}
+
+struct HashChains {
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl HashChains {
+    fn insert(&mut self, data:&[u8], i:usize) {
+        if i+MIN_MATCH <= data.len() {
+            let h = get_hash(data, i);
+            self.prev[i] = self.head[h];
+            self.head[h] = i;
+        }
+    }
+}
+
+impl Lz77 {
+    // Longest earlier match for position `i` as (length, distance).
+    fn find_match(&self, chains:&HashChains, data:&[u8], i:usize) -> (usize, usize) {
+        let max_len = MAX_MATCH.min(data.len()-i);
+        if max_len < MIN_MATCH {
+            return (0, 0);
+        }
+
+        let mut best:(usize, usize) = (0, 0);
+        let mut candidate = chains.head[get_hash(data, i)];
+        let mut steps:usize = 0;
+
+        while candidate != NONE && i-candidate <= self.window_size && steps < self.max_chain {
+            // Cheap rejection: a longer match has to agree at the current best length.
+            if data[candidate+best.0.min(max_len-1)] == data[i+best.0.min(max_len-1)] {
+                let len = data[candidate..candidate+max_len].iter().zip(data[i..i+max_len].iter()).take_while(|(a, b)| a == b).count();
+                if len > best.0 {
+                    best = (len, i-candidate);
+                    if len == max_len {
+                        break;
+                    }
+                }
+            }
+
+            candidate = chains.prev[candidate];
+            steps += 1;
+        }
+
+        if best.0 < MIN_MATCH {
+            return (0, 0);
+        }
+        best
+    }
+}
+
+impl Lz77 {
+    pub fn tokenize(&self, data:&[u8]) -> Vec<Token> {
+        let mut chains = HashChains {
+            head: vec![NONE; 1 << HASH_BITS],
+            prev: vec![NONE; data.len()],
+        };
+
+        let mut tokens:Vec<Token> = Vec::new();
+        let mut i:usize = 0;
+
+        while i < data.len() {
+            let (len, dist) = self.find_match(&chains, data, i);
+            chains.insert(data, i);
+
+            if len == 0 {
+                tokens.push(Token::Literal(data[i]));
+                i += 1;
+                continue;
+            }
+
+            if len < MAX_MATCH && i+1 < data.len() && self.find_match(&chains, data, i+1).0 > len {
+                tokens.push(Token::Literal(data[i]));
+                i += 1;
+                continue;
+            }
+
+            tokens.push(Token::Match { length: len as u16, distance: dist as u16 });
+            for j in i+1..i+len {
+                chains.insert(data, j);
+            }
+            i += len;
+        }
+
+        tokens
+    }
+}
+
+// Expands tokens back into bytes.
+pub fn detokenize(tokens:&[Token]) -> Result<Vec<u8>, String> {
+    let mut output:Vec<u8> = Vec::new();
+
+    for token in tokens.iter() {
+        match *token {
+            Token::Literal(b) => output.push(b),
+            Token::Match { length, distance } => {
+                let distance = distance as usize;
+                if distance == 0 || distance > output.len() {
+                    return Err(format!("Match distance {} is out of range !!!", distance));
+                }
+                for _ in 0..length {
+                    output.push(output[output.len()-distance]);
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,17 @@
+pub mod adaptive_huffman;
+pub mod bits;
+pub mod crc32;
+pub mod deflate;
+pub mod huffman;
+pub mod lz77;
+pub mod range_coder;
+pub mod stream;
+
+// Byte frequencies, the common front end of the static entropy coders.
+pub fn get_frequencies(data:&[u8]) -> [u64; 256] {
+    let mut frequencies:[u64; 256] = [0; 256];
+    for b in data.iter() {
+        frequencies[*b as usize] += 1;
+    }
+    frequencies
+}
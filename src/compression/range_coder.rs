@@ -0,0 +1,476 @@
+use super::get_frequencies;
+
+const RANGE_MAGIC:&[u8; 3] = b"RNG";
+const RANGE_VERSION:u8 = 1;
+
+// Model totals are kept within 16 bits so that range/total never drops below
+// 2^8 after normalisation.
+pub const MAX_TOTAL:u32 = 1 << 16;
+const TOP:u32 = 1 << 24;
+
+// A skewed model codes a symbol in a tiny fraction of a bit, and a one symbol
+// table in none at all, so a short stream can claim any length. `decode`
+// refuses to produce more than this.
+pub const MAX_DECODED_LEN:usize = 1 << 30;
+
+const ADAPTIVE_INCREMENT:u32 = 24;
+
+// Cumulative frequencies over the 256 byte values. A symbol owns the interval
+// [cum, cum+freq) of [0, total).
+pub trait FrequencyModel {
+    fn get_total(&self) -> u32;
+    fn get_range(&self, symbol:u8) -> (u32, u32);
+    // Symbol whose interval contains `target`, with its (cum, freq).
+    fn find_symbol(&self, target:u32) -> (u8, u32, u32);
+    fn update(&mut self, symbol:u8);
+}
+
+// Frequencies fixed up front, scaled down to at most MAX_TOTAL with every
+// occurring symbol keeping a non-zero count.
+pub struct StaticModel {
+    freqs: Vec<u32>,
+    cum: Vec<u32>,
+}
+
+impl StaticModel {
+    pub fn new(frequencies:&[u64]) -> Self {
+        assert!(frequencies.len() == 256, "expected one frequency per byte value");
+
+        let sum:u64 = frequencies.iter().sum();
+        let mut freqs:Vec<u32> = frequencies.iter().map(|f| {
+            if *f == 0 {
+                0
+            }
+            else if sum <= MAX_TOTAL as u64 {
+                *f as u32
+            }
+            else {
+                ((*f as u128*MAX_TOTAL as u128/sum as u128) as u32).max(1)
+            }
+        }).collect();
+
+        // Rounding small counts up to 1 can overshoot, take it from the largest.
+        let total:u32 = freqs.iter().sum();
+        if total > MAX_TOTAL {
+            let largest = (0..256).max_by_key(|s| freqs[*s]).unwrap();
+            freqs[largest] -= total-MAX_TOTAL;
+        }
+
+        StaticModel::from_scaled(freqs)
+    }
+}
+
+impl StaticModel {
+    fn from_scaled(freqs:Vec<u32>) -> Self {
+        let mut cum:Vec<u32> = vec![0; 257];
+        for s in 0..256 {
+            cum[s+1] = cum[s] + freqs[s];
+        }
+
+        Self {
+            freqs,
+            cum,
+        }
+    }
+}
+
+impl StaticModel {
+    pub fn get_frequencies(&self) -> &[u32] {
+        &self.freqs
+    }
+}
+
+impl FrequencyModel for StaticModel {
+    fn get_total(&self) -> u32 {
+        self.cum[256]
+    }
+
+    fn get_range(&self, symbol:u8) -> (u32, u32) {
+        (self.cum[symbol as usize], self.freqs[symbol as usize])
+    }
+
+    fn find_symbol(&self, target:u32) -> (u8, u32, u32) {
+        let s = self.cum.partition_point(|c| *c <= target)-1;
+        (s as u8, self.cum[s], self.freqs[s])
+    }
+
+    fn update(&mut self, _symbol:u8) {}
+}
+
+// Starts from uniform counts and adds ADAPTIVE_INCREMENT to every coded
+// symbol, halving all counts when the total exceeds MAX_TOTAL so that recent
+// data weighs more. Cumulative counts live in a Fenwick tree.
+pub struct AdaptiveModel {
+    freqs: Vec<u32>,
+    tree: Vec<u32>,
+    total: u32,
+}
+
+impl AdaptiveModel {
+    pub fn new() -> Self {
+        let mut model = Self {
+            freqs: vec![1; 256],
+            tree: vec![0; 257],
+            total: 0,
+        };
+        model.rebuild();
+        model
+    }
+}
+
+impl Default for AdaptiveModel {
+    fn default() -> Self {
+        AdaptiveModel::new()
+    }
+}
+
+impl AdaptiveModel {
+    fn rebuild(&mut self) {
+        self.tree = vec![0; 257];
+        for i in 1..=256 {
+            self.tree[i] += self.freqs[i-1];
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= 256 {
+                self.tree[parent] += self.tree[i];
+            }
+        }
+        self.total = self.freqs.iter().sum();
+    }
+}
+
+impl AdaptiveModel {
+    fn get_prefix_sum(&self, symbol:usize) -> u32 {
+        let mut sum:u32 = 0;
+        let mut i = symbol;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+}
+
+impl FrequencyModel for AdaptiveModel {
+    fn get_total(&self) -> u32 {
+        self.total
+    }
+
+    fn get_range(&self, symbol:u8) -> (u32, u32) {
+        (self.get_prefix_sum(symbol as usize), self.freqs[symbol as usize])
+    }
+
+    fn find_symbol(&self, target:u32) -> (u8, u32, u32) {
+        let mut pos:usize = 0;
+        let mut rem = target;
+        let mut step:usize = 256;
+
+        while step > 0 {
+            if pos+step <= 256 && self.tree[pos+step] <= rem {
+                pos += step;
+                rem -= self.tree[pos];
+            }
+            step >>= 1;
+        }
+
+        (pos as u8, target-rem, self.freqs[pos])
+    }
+
+    fn update(&mut self, symbol:u8) {
+        self.freqs[symbol as usize] += ADAPTIVE_INCREMENT;
+        self.total += ADAPTIVE_INCREMENT;
+
+        if self.total > MAX_TOTAL {
+            for f in self.freqs.iter_mut() {
+                *f = (*f).div_ceil(2);
+            }
+            self.rebuild();
+            return;
+        }
+
+        let mut i = symbol as usize + 1;
+        while i <= 256 {
+            self.tree[i] += ADAPTIVE_INCREMENT;
+            i += i & i.wrapping_neg();
+        }
+    }
+}
+
+// Range coder with carry propagation (as in LZMA): `low` keeps one carry bit
+// above 32 bits, and a run of 0xFF bytes is held back in `cache` until it is
+// known whether a carry will ripple into it.
+pub struct RangeEncoder {
+    low: u64,
+    range: u32,
+    cache: u8,
+    cache_size: u64,
+    out: Vec<u8>,
+}
+
+impl RangeEncoder {
+    pub fn new() -> Self {
+        Self {
+            low: 0,
+            range: u32::MAX,
+            cache: 0,
+            cache_size: 1,
+            out: Vec::new(),
+        }
+    }
+}
+
+impl Default for RangeEncoder {
+    fn default() -> Self {
+        RangeEncoder::new()
+    }
+}
+
+impl RangeEncoder {
+    fn shift_low(&mut self) {
+        if self.low < 0xFF000000 || self.low > 0xFFFFFFFF {
+            let carry = (self.low >> 32) as u8;
+            let mut temp = self.cache;
+            loop {
+                self.out.push(temp.wrapping_add(carry));
+                temp = 0xFF;
+                self.cache_size -= 1;
+                if self.cache_size == 0 {
+                    break;
+                }
+            }
+            self.cache = (self.low >> 24) as u8;
+        }
+        self.cache_size += 1;
+        self.low = (self.low & 0x00FFFFFF) << 8;
+    }
+}
+
+impl RangeEncoder {
+    pub fn encode<M: FrequencyModel>(&mut self, model:&mut M, symbol:u8) {
+        let (cum, freq) = model.get_range(symbol);
+        assert!(freq > 0, "symbol {} has zero frequency", symbol);
+
+        let r = self.range/model.get_total();
+        self.low += (r*cum) as u64;
+        self.range = r*freq;
+
+        while self.range < TOP {
+            self.range <<= 8;
+            self.shift_low();
+        }
+
+        model.update(symbol);
+    }
+}
+
+impl RangeEncoder {
+    pub fn finish(mut self) -> Vec<u8> {
+        for _ in 0..5 {
+            self.shift_low();
+        }
+        self.out
+    }
+}
+
+pub struct RangeDecoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    code: u32,
+    range: u32,
+}
+
+impl<'a> RangeDecoder<'a> {
+    pub fn new(bytes:&'a [u8]) -> Self {
+        let mut decoder = Self {
+            bytes,
+            pos: 0,
+            code: 0,
+            range: u32::MAX,
+        };
+
+        // The first byte is the encoder's initial cache and always zero.
+        for _ in 0..5 {
+            decoder.code = (decoder.code << 8) | decoder.next_byte() as u32;
+        }
+        decoder
+    }
+}
+
+impl RangeDecoder<'_> {
+    // Bytes past the end read as zeros.
+    fn next_byte(&mut self) -> u8 {
+        let b = *self.bytes.get(self.pos).unwrap_or(&0);
+        self.pos += 1;
+        b
+    }
+}
+
+impl RangeDecoder<'_> {
+    pub fn decode<M: FrequencyModel>(&mut self, model:&mut M) -> Result<u8, String> {
+        let r = self.range/model.get_total();
+        let target = self.code/r;
+        if target >= model.get_total() {
+            return Err("Corrupted range coded stream !!!".to_string());
+        }
+
+        let (symbol, cum, freq) = model.find_symbol(target);
+        self.code -= r*cum;
+        self.range = r*freq;
+
+        while self.range < TOP {
+            self.code = (self.code << 8) | self.next_byte() as u32;
+            self.range <<= 8;
+        }
+
+        // The encoder's flush covers every byte the decoder needs, reading far
+        // past the end means the stream was cut short.
+        if self.pos > self.bytes.len()+4 {
+            return Err("Truncated range coded stream !!!".to_string());
+        }
+
+        model.update(symbol);
+        Ok(symbol)
+    }
+}
+
+// Byte-oriented entropy coder on top of the range coder, with either a static
+// model sent in the header or an adaptive model that both sides rebuild.
+pub struct RangeCoder {
+    adaptive: bool,
+}
+
+impl RangeCoder {
+    pub fn new_static() -> Self {
+        Self {
+            adaptive: false,
+        }
+    }
+}
+
+impl RangeCoder {
+    pub fn new_adaptive() -> Self {
+        Self {
+            adaptive: true,
+        }
+    }
+}
+
+impl RangeCoder {
+    // Layout: magic "RNG", version, model (0 static, 1 adaptive), number of
+    // symbols (u64), for the static model the number of distinct symbols (u16)
+    // and a (symbol, scaled frequency - 1 as u16) entry for each, then the
+    // range coded payload.
+    pub fn encode(&self, data:&[u8]) -> Vec<u8> {
+        let mut out:Vec<u8> = Vec::new();
+        out.extend_from_slice(RANGE_MAGIC);
+        out.push(RANGE_VERSION);
+        out.push(self.adaptive as u8);
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        let mut encoder = RangeEncoder::new();
+
+        if self.adaptive {
+            let mut model = AdaptiveModel::new();
+            for b in data.iter() {
+                encoder.encode(&mut model, *b);
+            }
+        }
+        else {
+            let mut model = StaticModel::new(&get_frequencies(data));
+            let symbols:Vec<usize> = (0..256).filter(|s| model.freqs[*s] > 0).collect();
+
+            out.extend_from_slice(&(symbols.len() as u16).to_le_bytes());
+            for s in symbols {
+                out.push(s as u8);
+                out.extend_from_slice(&((model.freqs[s]-1) as u16).to_le_bytes());
+            }
+
+            for b in data.iter() {
+                encoder.encode(&mut model, *b);
+            }
+        }
+
+        out.extend(encoder.finish());
+        out
+    }
+}
+
+impl RangeCoder {
+    pub fn decode(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        RangeCoder::decode_with_limit(encoded, MAX_DECODED_LEN)
+    }
+}
+
+impl RangeCoder {
+    // Rejects streams whose header claims more than max_len bytes, before
+    // decoding anything.
+    pub fn decode_with_limit(encoded:&[u8], max_len:usize) -> Result<Vec<u8>, String> {
+        let header_len = RANGE_MAGIC.len()+10;
+        if encoded.len() < header_len || &encoded[..RANGE_MAGIC.len()] != RANGE_MAGIC {
+            return Err("Not a valid range coded stream !!!".to_string());
+        }
+
+        let version = encoded[RANGE_MAGIC.len()];
+        if version != RANGE_VERSION {
+            return Err(format!("Unsupported range coder version {} !!!", version));
+        }
+
+        let adaptive = match encoded[RANGE_MAGIC.len()+1] {
+            0 => false,
+            1 => true,
+            m => return Err(format!("Unknown range coder model {} !!!", m)),
+        };
+
+        let n = u64::from_le_bytes(encoded[RANGE_MAGIC.len()+2..header_len].try_into().unwrap());
+        let n = match usize::try_from(n) {
+            Ok(n) if n <= max_len => n,
+            _ => return Err(format!("Range coded stream of {} bytes is over the limit of {} !!!", n, max_len)),
+        };
+        let mut output:Vec<u8> = Vec::with_capacity(n.min(1 << 24));
+
+        if adaptive {
+            let mut model = AdaptiveModel::new();
+            let mut decoder = RangeDecoder::new(&encoded[header_len..]);
+            for _ in 0..n {
+                output.push(decoder.decode(&mut model)?);
+            }
+            return Ok(output);
+        }
+
+        let truncated = "Truncated range coder table !!!";
+        let num_symbols = u16::from_le_bytes(encoded.get(header_len..header_len+2).ok_or(truncated)?.try_into().unwrap()) as usize;
+        let table = encoded.get(header_len+2..header_len+2+3*num_symbols).ok_or(truncated)?;
+
+        let mut freqs:Vec<u32> = vec![0; 256];
+        for entry in table.chunks_exact(3) {
+            if freqs[entry[0] as usize] != 0 {
+                return Err("Invalid range coder table !!!".to_string());
+            }
+            freqs[entry[0] as usize] = u16::from_le_bytes([entry[1], entry[2]]) as u32 + 1;
+        }
+
+        let total:u32 = freqs.iter().sum();
+        if total > MAX_TOTAL || (total == 0 && n > 0) {
+            return Err("Invalid range coder table !!!".to_string());
+        }
+
+        let mut model = StaticModel::from_scaled(freqs);
+        let mut decoder = RangeDecoder::new(&encoded[header_len+2+3*num_symbols..]);
+        for _ in 0..n {
+            output.push(decoder.decode(&mut model)?);
+        }
+
+        Ok(output)
+    }
+}
+
+impl RangeCoder {
+    pub fn compress(data:&[u8]) -> Vec<u8> {
+        RangeCoder::new_static().encode(data)
+    }
+}
+
+impl RangeCoder {
+    pub fn decompress(encoded:&[u8]) -> Result<Vec<u8>, String> {
+        RangeCoder::decode(encoded)
+    }
+}
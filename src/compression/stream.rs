@@ -0,0 +1,134 @@
+use std::io::{Error, ErrorKind, Read, Write};
+use super::crc32::Crc32;
+use super::huffman::Huffman;
+
+const STREAM_MAGIC:&[u8; 3] = b"HFZ";
+const STREAM_VERSION:u8 = 1;
+pub const DEFAULT_BLOCK_SIZE:usize = 1 << 20;
+// Encoded blocks can be slightly larger than their input, this keeps every
+// encoded length well inside its u32 field.
+pub const MAX_BLOCK_SIZE:usize = 1 << 31;
+
+// Sizes seen by `compress_stream` / `decompress_stream`.
+pub struct StreamStats {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+fn invalid_data(msg:String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+fn read_block<R: Read>(reader:&mut R, buf:&mut [u8]) -> std::io::Result<usize> {
+    let mut filled:usize = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+// Layout: magic "HFZ", version, then every block of at most `block_size` input
+// bytes as its encoded length (u32) followed by a self-contained Huffman
+// stream, a zero length marking the end, and finally the CRC-32 (u32) and the
+// length (u64) of the original data. Blocks are coded independently, so only
+// one block is ever held in memory.
+pub fn compress_stream<R: Read, W: Write>(reader:&mut R, writer:&mut W, block_size:usize) -> std::io::Result<StreamStats> {
+    if block_size == 0 || block_size > MAX_BLOCK_SIZE {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Block size must be between 1 and {} bytes !!!", MAX_BLOCK_SIZE)));
+    }
+
+    let mut stats = StreamStats { bytes_in: 0, bytes_out: 0 };
+    let mut crc = Crc32::new();
+    let mut buf:Vec<u8> = vec![0; block_size];
+
+    writer.write_all(STREAM_MAGIC)?;
+    writer.write_all(&[STREAM_VERSION])?;
+    stats.bytes_out += STREAM_MAGIC.len() as u64 + 1;
+
+    loop {
+        let n = read_block(reader, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+
+        crc.update(&buf[..n]);
+        let encoded = Huffman::compress(&buf[..n]);
+        let encoded_len = u32::try_from(encoded.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "Encoded block too large !!!"))?;
+
+        writer.write_all(&encoded_len.to_le_bytes())?;
+        writer.write_all(&encoded)?;
+
+        stats.bytes_in += n as u64;
+        stats.bytes_out += 4 + encoded.len() as u64;
+    }
+
+    writer.write_all(&0u32.to_le_bytes())?;
+    writer.write_all(&crc.finish().to_le_bytes())?;
+    writer.write_all(&stats.bytes_in.to_le_bytes())?;
+    stats.bytes_out += 16;
+
+    writer.flush()?;
+    Ok(stats)
+}
+
+pub fn decompress_stream<R: Read, W: Write>(reader:&mut R, writer:&mut W) -> std::io::Result<StreamStats> {
+    let mut stats = StreamStats { bytes_in: 0, bytes_out: 0 };
+    let mut crc = Crc32::new();
+
+    let mut header:[u8; 4] = [0; 4];
+    reader.read_exact(&mut header)?;
+    if &header[..3] != STREAM_MAGIC {
+        return Err(invalid_data("Not a Huffman compressed stream !!!".to_string()));
+    }
+    if header[3] != STREAM_VERSION {
+        return Err(invalid_data(format!("Unsupported stream version {} !!!", header[3])));
+    }
+    stats.bytes_in += 4;
+
+    loop {
+        let mut len_bytes:[u8; 4] = [0; 4];
+        reader.read_exact(&mut len_bytes)?;
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        stats.bytes_in += 4;
+
+        if len == 0 {
+            break;
+        }
+
+        let mut encoded:Vec<u8> = Vec::new();
+        reader.by_ref().take(len as u64).read_to_end(&mut encoded)?;
+        if encoded.len() != len {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Truncated block !!!"));
+        }
+
+        let decoded = Huffman::decompress(&encoded).map_err(invalid_data)?;
+        crc.update(&decoded);
+        writer.write_all(&decoded)?;
+
+        stats.bytes_in += len as u64;
+        stats.bytes_out += decoded.len() as u64;
+    }
+
+    let mut trailer:[u8; 12] = [0; 12];
+    reader.read_exact(&mut trailer)?;
+    stats.bytes_in += 12;
+
+    let expected_crc = u32::from_le_bytes(trailer[..4].try_into().unwrap());
+    let expected_len = u64::from_le_bytes(trailer[4..].try_into().unwrap());
+
+    if expected_len != stats.bytes_out {
+        return Err(invalid_data(format!("Length mismatch: expected {} bytes, decoded {} !!!", expected_len, stats.bytes_out)));
+    }
+    if expected_crc != crc.finish() {
+        return Err(invalid_data(format!("CRC mismatch: expected {:08x}, found {:08x} !!!", expected_crc, crc.finish())));
+    }
+
+    writer.flush()?;
+    Ok(stats)
+}
@@ -0,0 +1,211 @@
+use super::csr::Csr;
+use super::shortest_path::{bellman_ford, dijkstra, ShortestPaths};
+use super::{Adjacency, Edge, Weight, WideSum};
+
+// Distances between every pair of nodes, None if unreachable, and for every
+// source the node before each target on a shortest path.
+#[derive(Clone, Debug)]
+pub struct AllPairs<E> {
+    dist: Vec<Vec<Option<E>>>,
+    pred: Vec<Vec<Option<usize>>>,
+    overflowed: Vec<(usize, usize)>,
+}
+
+impl<E: Weight> AllPairs<E> {
+    pub fn num_nodes(&self) -> usize {
+        self.dist.len()
+    }
+}
+
+impl<E: Weight> AllPairs<E> {
+    pub fn distance(&self, u:usize, v:usize) -> Option<E> {
+        self.dist[u][v]
+    }
+}
+
+impl<E: Weight> AllPairs<E> {
+    // Row u holds the distances from u.
+    pub fn distances(&self) -> &[Vec<Option<E>>] {
+        &self.dist
+    }
+}
+
+impl<E: Weight> AllPairs<E> {
+    // Pairs joined by a path whose distance does not fit in the weight type,
+    // sorted. Their distance is None, but their path is kept.
+    pub fn overflowed(&self) -> &[(usize, usize)] {
+        &self.overflowed
+    }
+}
+
+impl<E: Weight> AllPairs<E> {
+    // Nodes on a shortest path from u to v, both included. None if v is
+    // unreachable, or if the predecessors do not lead back to u.
+    pub fn path(&self, u:usize, v:usize) -> Option<Vec<usize>> {
+        let mut path:Vec<usize> = vec![v];
+        let mut x = v;
+        while x != u {
+            x = self.pred[u][x]?;
+            path.push(x);
+            if path.len() > self.num_nodes() {
+                return None;
+            }
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+fn get_edges<E: Weight, G: Adjacency<E>>(graph:&G) -> Vec<Edge<E>> {
+    (0..graph.num_nodes()).flat_map(|u| graph.neighbors(u).map(move |(v, w)| Edge { from: u, to: v, weight: *w })).collect()
+}
+
+// Walks the predecessors on the paths from u backwards, starting from u
+// itself, whose distance to itself came out negative, and returns the cycle
+// it runs into in edge order. Should the walk stop short, Bellman-Ford from u
+// finds the cycle instead, its sums being exact too.
+fn get_negative_cycle<E: Weight, G: Adjacency<E>>(graph:&G, pred:&[Vec<Option<usize>>], u:usize) -> Vec<usize> {
+    let mut seen_at:Vec<Option<usize>> = vec![None; pred.len()];
+    let mut walk:Vec<usize> = Vec::new();
+    let mut x = u;
+
+    while seen_at[x].is_none() {
+        seen_at[x] = Some(walk.len());
+        walk.push(x);
+        x = match pred[u][x] {
+            Some(p) => p,
+            None => return bellman_ford(graph, u).1.map(|c| c.cycle).unwrap_or_default(),
+        };
+    }
+
+    let mut cycle = walk.split_off(seen_at[x].unwrap());
+    cycle.reverse();
+    cycle
+}
+
+// Floyd-Warshall in O(n^3) time and O(n^2) space, for dense graphs. Returns
+// the nodes of a negative cycle, in edge order, if there is one. Sums are
+// exact, so cycles are found whatever the weight type, and distances that do
+// not fit in it are reported by `overflowed`.
+pub fn floyd_warshall<E: Weight, G: Adjacency<E>>(graph:&G) -> Result<AllPairs<E>, Vec<usize>> {
+    let num_nodes = graph.num_nodes();
+    let mut sums:Vec<Vec<Option<WideSum<E>>>> = vec![vec![None; num_nodes]; num_nodes];
+    let mut pred:Vec<Vec<Option<usize>>> = vec![vec![None; num_nodes]; num_nodes];
+    let zero:WideSum<E> = WideSum::new(E::zero());
+
+    for (u, row) in sums.iter_mut().enumerate() {
+        row[u] = Some(zero);
+    }
+    for u in 0..num_nodes {
+        for (v, w) in graph.neighbors(u) {
+            let w = WideSum::new(*w);
+            if sums[u][v].is_none_or(|x| w < x) {
+                sums[u][v] = Some(w);
+                pred[u][v] = Some(u);
+            }
+        }
+    }
+
+    for k in 0..num_nodes {
+        for i in 0..num_nodes {
+            let dik = match sums[i][k] {
+                Some(x) => x,
+                None => continue,
+            };
+
+            for j in 0..num_nodes {
+                let dij = match sums[k][j] {
+                    Some(dkj) => dik.add_sum(dkj),
+                    None => continue,
+                };
+                if sums[i][j].is_none_or(|x| dij < x) {
+                    sums[i][j] = Some(dij);
+                    pred[i][j] = pred[k][j];
+                }
+            }
+        }
+
+        // Stop before the distances around a negative cycle run away.
+        if let Some(u) = (0..num_nodes).find(|u| sums[*u][*u].is_some_and(|x| x < zero)) {
+            return Err(get_negative_cycle(graph, &pred, u));
+        }
+    }
+
+    let dist:Vec<Vec<Option<E>>> = sums.iter().map(|row| row.iter().map(|d| d.and_then(|d| d.get())).collect()).collect();
+    let overflowed:Vec<(usize, usize)> = (0..num_nodes).flat_map(|u| (0..num_nodes).map(move |v| (u, v)))
+        .filter(|(u, v)| sums[*u][*v].is_some() && dist[*u][*v].is_none()).collect();
+
+    Ok(AllPairs { dist, pred, overflowed })
+}
+
+// w(u, v) + h(u) - h(v), None if it does not fit in the weight type. The
+// potentials are never positive, so subtracting h(v) first cannot overflow
+// for a negative w and adding h(u) first cannot for any other.
+fn reweight<E: Weight>(w:E, hu:E, hv:E) -> Option<E> {
+    if w < E::zero() {
+        w.checked_sub(hv)?.checked_add(hu)
+    }
+    else {
+        w.checked_add(hu)?.checked_sub(hv)
+    }
+}
+
+// Johnson's algorithm in O(nm log n) time, for sparse graphs with negative
+// edges. Bellman-Ford from an extra node with a zero edge to every node gives
+// potentials h such that w(u, v) + h(u) - h(v) is never negative, then
+// Dijkstra runs from every node on the reweighted graph. Returns the nodes of
+// a negative cycle, in edge order, if there is one. Falls back to
+// Floyd-Warshall when a potential or a reweighted distance does not fit in
+// the weight type.
+pub fn johnson<E: Weight, G: Adjacency<E>>(graph:&G) -> Result<AllPairs<E>, Vec<usize>> {
+    let num_nodes = graph.num_nodes();
+    let mut edges = get_edges(graph);
+    let num_edges = edges.len();
+
+    edges.extend((0..num_nodes).map(|v| Edge { from: num_nodes, to: v, weight: E::zero() }));
+    let augmented:Csr<E> = Csr::from_edges(num_nodes+1, &edges, true);
+
+    let (potentials, neg_cycle) = bellman_ford(&augmented, num_nodes);
+    if let Some(c) = neg_cycle {
+        return Err(c.cycle);
+    }
+    if !potentials.overflowed().is_empty() {
+        return floyd_warshall(graph);
+    }
+    let h:Vec<E> = potentials.distances()[..num_nodes].iter().map(|d| d.unwrap()).collect();
+
+    edges.truncate(num_edges);
+    for edge in edges.iter_mut() {
+        edge.weight = match reweight(edge.weight, h[edge.from], h[edge.to]) {
+            Some(w) => w,
+            None => return floyd_warshall(graph),
+        };
+    }
+    let reweighted:Csr<E> = Csr::from_edges(num_nodes, &edges, true);
+
+    let mut dist:Vec<Vec<Option<E>>> = Vec::with_capacity(num_nodes);
+    let mut pred:Vec<Vec<Option<usize>>> = Vec::with_capacity(num_nodes);
+
+    for u in 0..num_nodes {
+        let paths:ShortestPaths<E> = dijkstra(&reweighted, u);
+        if !paths.overflowed().is_empty() {
+            return floyd_warshall(graph);
+        }
+
+        // Undoing the reweighting, the reweighted distance is never negative
+        // and h(v) never positive, so adding h(v) first cannot overflow.
+        let mut row:Vec<Option<E>> = Vec::with_capacity(num_nodes);
+        for d in paths.distances().iter() {
+            row.push(match d.map(|d| d.checked_add(h[row.len()]).and_then(|x| x.checked_sub(h[u]))) {
+                Some(None) => return floyd_warshall(graph),
+                Some(x) => x,
+                None => None,
+            });
+        }
+        dist.push(row);
+        pred.push(paths.predecessors().to_vec());
+    }
+
+    Ok(AllPairs { dist, pred, overflowed: Vec::new() })
+}
@@ -0,0 +1,273 @@
+use super::Graph;
+
+// Strongly connected components contracted to single nodes. The DAG has one
+// node per component, with key and id equal to the component index, and one
+// edge for every pair of components joined by at least one edge.
+pub struct Condensation {
+    pub dag: Graph<usize, ()>,
+    pub members: Vec<Vec<usize>>,
+    pub component: Vec<usize>,
+}
+
+// Tarjan's algorithm in one depth first pass. A node is the root of its
+// component when nothing below it reaches higher up the search stack. The
+// search keeps an explicit stack of edge iterators instead of recursing, so
+// long paths cannot overflow the call stack. Components come out in
+// topological order of the condensation.
+pub fn tarjan_scc<N, E>(graph:&Graph<N, E>) -> Vec<Vec<usize>> {
+    assert!(graph.is_directed(), "strongly connected components need a directed graph");
+
+    let num_nodes = graph.num_nodes();
+    let mut index:Vec<Option<usize>> = vec![None; num_nodes];
+    let mut low:Vec<usize> = vec![0; num_nodes];
+    let mut on_stack:Vec<bool> = vec![false; num_nodes];
+    let mut stack:Vec<usize> = Vec::new();
+    let mut components:Vec<Vec<usize>> = Vec::new();
+    let mut counter:usize = 0;
+
+    for root in 0..num_nodes {
+        if index[root].is_some() {
+            continue;
+        }
+
+        index[root] = Some(counter);
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut search = vec![(root, graph.out_edges(root))];
+
+        while let Some((u, edges)) = search.last_mut() {
+            let u = *u;
+            match edges.next() {
+                Some((v, _)) => {
+                    if let Some(i) = index[v] {
+                        if on_stack[v] {
+                            low[u] = low[u].min(i);
+                        }
+                        continue;
+                    }
+
+                    index[v] = Some(counter);
+                    low[v] = counter;
+                    counter += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    search.push((v, graph.out_edges(v)));
+                }
+                None => {
+                    search.pop();
+                    if let Some((p, _)) = search.last() {
+                        low[*p] = low[*p].min(low[u]);
+                    }
+
+                    if index[u] == Some(low[u]) {
+                        let mut component:Vec<usize> = Vec::new();
+                        loop {
+                            let v = stack.pop().unwrap();
+                            on_stack[v] = false;
+                            component.push(v);
+                            if v == u {
+                                break;
+                            }
+                        }
+                        components.push(component);
+                    }
+                }
+            }
+        }
+    }
+
+    // Tarjan finds sink components first.
+    components.reverse();
+    components
+}
+
+// Kosaraju's algorithm: a first search orders the nodes by finishing time,
+// then searches over reversed edges, latest finish first, each collect one
+// component. Both searches are iterative. Components come out in topological
+// order of the condensation.
+pub fn kosaraju_scc<N, E>(graph:&Graph<N, E>) -> Vec<Vec<usize>> {
+    assert!(graph.is_directed(), "strongly connected components need a directed graph");
+
+    let num_nodes = graph.num_nodes();
+    let mut visited:Vec<bool> = vec![false; num_nodes];
+    let mut finished:Vec<usize> = Vec::with_capacity(num_nodes);
+
+    for root in 0..num_nodes {
+        if visited[root] {
+            continue;
+        }
+
+        visited[root] = true;
+        let mut search = vec![(root, graph.out_edges(root))];
+
+        while let Some((u, edges)) = search.last_mut() {
+            let u = *u;
+            match edges.next() {
+                Some((v, _)) => {
+                    if !visited[v] {
+                        visited[v] = true;
+                        search.push((v, graph.out_edges(v)));
+                    }
+                }
+                None => {
+                    search.pop();
+                    finished.push(u);
+                }
+            }
+        }
+    }
+
+    let mut assigned:Vec<bool> = vec![false; num_nodes];
+    let mut components:Vec<Vec<usize>> = Vec::new();
+
+    for root in finished.into_iter().rev() {
+        if assigned[root] {
+            continue;
+        }
+
+        assigned[root] = true;
+        let mut component:Vec<usize> = Vec::new();
+        let mut stack:Vec<usize> = vec![root];
+
+        while let Some(u) = stack.pop() {
+            component.push(u);
+            for (v, _) in graph.in_edges(u) {
+                if !assigned[v] {
+                    assigned[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+        components.push(component);
+    }
+
+    components
+}
+
+pub fn condensation<N, E>(graph:&Graph<N, E>) -> Condensation {
+    let members = tarjan_scc(graph);
+
+    let mut component:Vec<usize> = vec![0; graph.num_nodes()];
+    for (c, nodes) in members.iter().enumerate() {
+        for u in nodes.iter() {
+            component[*u] = c;
+        }
+    }
+
+    let mut dag:Graph<usize, ()> = Graph::directed();
+    for c in 0..members.len() {
+        dag.add_node(c);
+    }
+
+    // Last component with an edge into each one, to skip repeated edges.
+    let mut joined_from:Vec<Option<usize>> = vec![None; members.len()];
+    for (c, nodes) in members.iter().enumerate() {
+        for u in nodes.iter() {
+            for (v, _) in graph.out_edges(*u) {
+                let d = component[v];
+                if d != c && joined_from[d] != Some(c) {
+                    joined_from[d] = Some(c);
+                    dag.add_edge_ids(c, d, ());
+                }
+            }
+        }
+    }
+
+    Condensation { dag, members, component }
+}
+
+// Depth first search over an undirected graph recording, for every node, its
+// discovery time and the earliest discovery time reachable from its subtree
+// with one back edge. The edge to the parent is skipped by id, so a parallel
+// edge still counts as a back edge. Calls on_child(parent, child, edge, disc,
+// low) when the search returns over a tree edge, and returns the number of
+// children of every root.
+fn get_low_links<N, E>(graph:&Graph<N, E>, mut on_child:impl FnMut(usize, usize, usize, &[usize], &[usize])) -> Vec<usize> {
+    assert!(!graph.is_directed(), "bridges and articulation points need an undirected graph");
+
+    let num_nodes = graph.num_nodes();
+    let mut disc:Vec<usize> = vec![usize::MAX; num_nodes];
+    let mut low:Vec<usize> = vec![usize::MAX; num_nodes];
+    let mut root_children:Vec<usize> = vec![0; num_nodes];
+    let mut counter:usize = 0;
+
+    for root in 0..num_nodes {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+
+        disc[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        let mut search = vec![(root, None, graph.out_edges(root))];
+
+        while let Some((u, parent_edge, edges)) = search.last_mut() {
+            let (u, parent_edge) = (*u, *parent_edge);
+            match edges.next() {
+                Some((_, e)) if Some(e) == parent_edge => {}
+                Some((v, e)) => {
+                    if disc[v] != usize::MAX {
+                        low[u] = low[u].min(disc[v]);
+                        continue;
+                    }
+
+                    disc[v] = counter;
+                    low[v] = counter;
+                    counter += 1;
+                    search.push((v, Some(e), graph.out_edges(v)));
+                }
+                None => {
+                    search.pop();
+                    if let (Some((p, _, _)), Some(e)) = (search.last(), parent_edge) {
+                        let p = *p;
+                        low[p] = low[p].min(low[u]);
+                        if p == root {
+                            root_children[root] += 1;
+                        }
+                        on_child(p, u, e, &disc, &low);
+                    }
+                }
+            }
+        }
+    }
+
+    root_children
+}
+
+// Edges whose removal disconnects their endpoints, by id: tree edges that no
+// back edge from below jumps over.
+pub fn bridges<N, E>(graph:&Graph<N, E>) -> Vec<usize> {
+    let mut found:Vec<usize> = Vec::new();
+    get_low_links(graph, |p, v, e, disc, low| {
+        if low[v] > disc[p] {
+            found.push(e);
+        }
+    });
+
+    found.sort_unstable();
+    found
+}
+
+// Nodes whose removal splits their connected component: a root with more
+// than one child, or any other node with a child whose subtree reaches no
+// higher than it.
+pub fn articulation_points<N, E>(graph:&Graph<N, E>) -> Vec<usize> {
+    let mut is_cut:Vec<bool> = vec![false; graph.num_nodes()];
+    let root_children = get_low_links(graph, |p, v, _, disc, low| {
+        if low[v] >= disc[p] {
+            is_cut[p] = true;
+        }
+    });
+
+    // Every child of a root reaches no higher than it, so roots are decided
+    // by their number of children instead.
+    for (u, children) in root_children.into_iter().enumerate() {
+        if children > 0 {
+            is_cut[u] = children > 1;
+        }
+    }
+
+    (0..graph.num_nodes()).filter(|u| is_cut[*u]).collect()
+}
@@ -0,0 +1,81 @@
+use super::{Adjacency, Edge};
+
+// Compressed sparse row adjacency for graphs that no longer change: the
+// neighbors of node u are targets[offsets[u]..offsets[u+1]], stored in one
+// contiguous array instead of a vector per node.
+pub struct Csr<E> {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<E>,
+}
+
+impl<E: Clone> Csr<E> {
+    // An undirected edge is stored in both directions, a self loop once.
+    pub fn from_edges(num_nodes:usize, edges:&[Edge<E>], directed:bool) -> Self {
+        let mut offsets:Vec<usize> = vec![0; num_nodes+1];
+        for edge in edges.iter() {
+            offsets[edge.from+1] += 1;
+            if !directed && edge.from != edge.to {
+                offsets[edge.to+1] += 1;
+            }
+        }
+        for u in 0..num_nodes {
+            offsets[u+1] += offsets[u];
+        }
+
+        let mut next:Vec<usize> = offsets.clone();
+        let mut slots:Vec<Option<(usize, E)>> = vec![None; offsets[num_nodes]];
+
+        for edge in edges.iter() {
+            slots[next[edge.from]] = Some((edge.to, edge.weight.clone()));
+            next[edge.from] += 1;
+            if !directed && edge.from != edge.to {
+                slots[next[edge.to]] = Some((edge.from, edge.weight.clone()));
+                next[edge.to] += 1;
+            }
+        }
+
+        let (targets, weights) = slots.into_iter().map(|s| s.unwrap()).unzip();
+        Self {
+            offsets,
+            targets,
+            weights,
+        }
+    }
+}
+
+impl<E> Csr<E> {
+    pub fn num_nodes(&self) -> usize {
+        self.offsets.len()-1
+    }
+}
+
+impl<E> Csr<E> {
+    // Number of stored (node, neighbor) entries.
+    pub fn num_entries(&self) -> usize {
+        self.targets.len()
+    }
+}
+
+impl<E> Csr<E> {
+    pub fn degree(&self, u:usize) -> usize {
+        self.offsets[u+1]-self.offsets[u]
+    }
+}
+
+impl<E> Csr<E> {
+    pub fn neighbors(&self, u:usize) -> impl Iterator<Item = (usize, &E)> {
+        let range = self.offsets[u]..self.offsets[u+1];
+        self.targets[range.clone()].iter().copied().zip(self.weights[range].iter())
+    }
+}
+
+impl<E> Adjacency<E> for Csr<E> {
+    fn num_nodes(&self) -> usize {
+        Csr::num_nodes(self)
+    }
+
+    fn neighbors<'a>(&'a self, u:usize) -> impl Iterator<Item = (usize, &'a E)> where E: 'a {
+        Csr::neighbors(self, u)
+    }
+}
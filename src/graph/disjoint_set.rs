@@ -0,0 +1,206 @@
+// Union-find over the elements 0..n with union by rank and path compression,
+// so any sequence of operations runs in near constant amortized time each.
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    num_sets: usize,
+}
+
+impl DisjointSet {
+    pub fn new(n:usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            num_sets: n,
+        }
+    }
+}
+
+impl DisjointSet {
+    // Representative of the set holding x.
+    pub fn find(&mut self, x:usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Point everything on the way straight at the root.
+        let mut y = x;
+        while self.parent[y] != root {
+            let next = self.parent[y];
+            self.parent[y] = root;
+            y = next;
+        }
+        root
+    }
+}
+
+impl DisjointSet {
+    // Merges the sets of a and b, false if they were already one set.
+    pub fn union(&mut self, a:usize, b:usize) -> bool {
+        let (mut ra, mut rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+
+        if self.rank[ra] < self.rank[rb] {
+            std::mem::swap(&mut ra, &mut rb);
+        }
+        if self.rank[ra] == self.rank[rb] {
+            self.rank[ra] += 1;
+        }
+
+        self.parent[rb] = ra;
+        self.size[ra] += self.size[rb];
+        self.num_sets -= 1;
+        true
+    }
+}
+
+impl DisjointSet {
+    pub fn same_set(&mut self, a:usize, b:usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+impl DisjointSet {
+    pub fn set_size(&mut self, x:usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+impl DisjointSet {
+    pub fn num_sets(&self) -> usize {
+        self.num_sets
+    }
+}
+
+impl DisjointSet {
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+}
+
+impl DisjointSet {
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+}
+
+// Union-find whose unions can be undone in reverse order, e.g. for offline
+// dynamic connectivity or backtracking. Path compression would rewrite many
+// parents at once, so it only unions by size and finds take O(log n).
+pub struct RollbackDisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    num_sets: usize,
+    // Root that was attached under another root by each union, None if the
+    // union found both in one set already.
+    history: Vec<Option<usize>>,
+}
+
+impl RollbackDisjointSet {
+    pub fn new(n:usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            num_sets: n,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn find(&self, mut x:usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn union(&mut self, a:usize, b:usize) -> bool {
+        let (mut ra, mut rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            self.history.push(None);
+            return false;
+        }
+
+        if self.size[ra] < self.size[rb] {
+            std::mem::swap(&mut ra, &mut rb);
+        }
+
+        self.parent[rb] = ra;
+        self.size[ra] += self.size[rb];
+        self.num_sets -= 1;
+        self.history.push(Some(rb));
+        true
+    }
+}
+
+impl RollbackDisjointSet {
+    // Undoes the last union, false if there is none.
+    pub fn undo(&mut self) -> bool {
+        let rb = match self.history.pop() {
+            Some(Some(rb)) => rb,
+            Some(None) => return true,
+            None => return false,
+        };
+
+        let ra = self.parent[rb];
+        self.size[ra] -= self.size[rb];
+        self.parent[rb] = rb;
+        self.num_sets += 1;
+        true
+    }
+}
+
+impl RollbackDisjointSet {
+    // Number of unions so far, to roll back to later.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+}
+
+impl RollbackDisjointSet {
+    // Undoes every union made after the snapshot was taken.
+    pub fn rollback(&mut self, snapshot:usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn same_set(&self, a:usize, b:usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn set_size(&self, x:usize) -> usize {
+        self.size[self.find(x)]
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn num_sets(&self) -> usize {
+        self.num_sets
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+}
+
+impl RollbackDisjointSet {
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::{BinaryHeap, VecDeque};
+use super::csr::Csr;
+use super::shortest_path::bellman_ford;
+use super::{Edge, Graph, NodeDist, Weight};
+
+// Directed flow network kept as its residual graph. Edge i is stored as arc
+// 2i with its remaining capacity and arc 2i+1 in the opposite direction with
+// the flow that can be sent back, so arc a^1 is always the partner of arc a.
+// Costs are only needed for min-cost flow, and require a signed weight type.
+pub struct FlowNetwork<E> {
+    arcs: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    residual: Vec<E>,
+    cost: Vec<E>,
+    capacity: Vec<E>,
+}
+
+// Minimum s-t cut: the nodes still reachable from s in the residual graph
+// after a maximum flow, and the saturated edges leaving them, whose
+// capacities add up to the flow value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinCut<E> {
+    pub value: E,
+    pub source_side: Vec<usize>,
+    pub edges: Vec<usize>,
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn new(num_nodes:usize) -> Self {
+        Self {
+            arcs: vec![Vec::new(); num_nodes],
+            to: Vec::new(),
+            residual: Vec::new(),
+            cost: Vec::new(),
+            capacity: Vec::new(),
+        }
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Edge weights become capacities, with the same edge ids.
+    pub fn from_graph<N>(graph:&Graph<N, E>) -> Self {
+        assert!(graph.is_directed(), "flow networks need a directed graph, add an undirected edge in both directions");
+
+        let mut network:FlowNetwork<E> = FlowNetwork::new(graph.num_nodes());
+        for edge in graph.edges().iter() {
+            network.add_edge(edge.from, edge.to, edge.weight);
+        }
+        network
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn add_edge(&mut self, u:usize, v:usize, capacity:E) -> usize {
+        self.add_edge_with_cost(u, v, capacity, E::zero())
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Cost per unit of flow on the edge.
+    pub fn add_edge_with_cost(&mut self, u:usize, v:usize, capacity:E, cost:E) -> usize {
+        assert!(u < self.num_nodes() && v < self.num_nodes(), "node id out of range");
+        assert!(capacity >= E::zero(), "capacity must not be negative");
+
+        let id = self.capacity.len();
+        self.arcs[u].push(2*id);
+        self.arcs[v].push(2*id+1);
+        self.to.extend([v, u]);
+        self.residual.extend([capacity, E::zero()]);
+        self.cost.extend([cost, E::zero() - cost]);
+        self.capacity.push(capacity);
+        id
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn num_nodes(&self) -> usize {
+        self.arcs.len()
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn num_edges(&self) -> usize {
+        self.capacity.len()
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // (from, to) of an edge.
+    pub fn endpoints(&self, edge:usize) -> (usize, usize) {
+        (self.to[2*edge+1], self.to[2*edge])
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn capacity(&self, edge:usize) -> E {
+        self.capacity[edge]
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn flow(&self, edge:usize) -> E {
+        self.residual[2*edge+1]
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    pub fn cost(&self, edge:usize) -> E {
+        self.cost[2*edge]
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Removes all flow.
+    pub fn reset(&mut self) {
+        for (e, c) in self.capacity.iter().enumerate() {
+            self.residual[2*e] = *c;
+            self.residual[2*e+1] = E::zero();
+        }
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // A flow value or the excess of a node never exceeds what the arcs at s
+    // can carry, so once their total fits in the weight type nothing can
+    // overflow.
+    fn assert_capacity_fits(&self, s:usize) {
+        let total = self.arcs[s].iter().try_fold(E::zero(), |total, a| total.checked_add(self.capacity[a/2]));
+        assert!(total.is_some(), "total capacity at the source must fit in the weight type");
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Potentials are costs of simple paths and the running total cost is a
+    // sum of flow times cost, all within the sum C of capacity times |cost|
+    // over the edges. Reduced distances stay within 2C and adding up a
+    // reduced cost within 4C, which must fit in the weight type.
+    fn assert_costs_fit(&self) {
+        let total = (0..self.num_edges()).try_fold(E::zero(), |total, e| {
+            let cost = self.cost[2*e];
+            let cost = if cost < E::zero() { E::zero().checked_sub(cost)? } else { cost };
+            total.checked_add(self.capacity[e].checked_mul(cost)?)
+        });
+        let fits = total.and_then(|c| c.checked_add(c)).and_then(|c| c.checked_add(c)).is_some();
+        assert!(fits, "four times the total of capacity times cost must fit in the weight type");
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    fn push(&mut self, arc:usize, f:E) {
+        self.residual[arc] = self.residual[arc] - f;
+        self.residual[arc^1] = self.residual[arc^1] + f;
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Breadth first distances from s over arcs with capacity left.
+    fn get_levels(&self, s:usize) -> Vec<Option<usize>> {
+        let mut level:Vec<Option<usize>> = vec![None; self.num_nodes()];
+        let mut queue:VecDeque<usize> = VecDeque::from([s]);
+        level[s] = Some(0);
+
+        while let Some(u) = queue.pop_front() {
+            for a in self.arcs[u].iter() {
+                let v = self.to[*a];
+                if level[v].is_none() && self.residual[*a] > E::zero() {
+                    level[v] = level[u].map(|l| l+1);
+                    queue.push_back(v);
+                }
+            }
+        }
+        level
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Finds one path from s to t through the level graph and saturates it.
+    // next_arc[u] skips the arcs of u already known to lead nowhere, and the
+    // search keeps an explicit stack of arcs instead of recursing.
+    fn augment(&mut self, s:usize, t:usize, level:&[Option<usize>], next_arc:&mut [usize]) -> Option<E> {
+        let mut path:Vec<usize> = Vec::new();
+        let mut u = s;
+
+        loop {
+            if u == t {
+                let f = path.iter().map(|a| self.residual[*a]).min().unwrap();
+                for a in path.iter() {
+                    self.push(*a, f);
+                }
+                return Some(f);
+            }
+
+            let mut advanced:bool = false;
+            while next_arc[u] < self.arcs[u].len() {
+                let a = self.arcs[u][next_arc[u]];
+                let v = self.to[a];
+                if self.residual[a] > E::zero() && level[v] == level[u].map(|l| l+1) {
+                    path.push(a);
+                    u = v;
+                    advanced = true;
+                    break;
+                }
+                next_arc[u] += 1;
+            }
+
+            // Dead end, step back and skip the arc that led here.
+            if !advanced {
+                let a = path.pop()?;
+                u = self.to[a^1];
+                next_arc[u] += 1;
+            }
+        }
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Dinic's algorithm: saturates shortest augmenting paths one level graph
+    // at a time, O(V^2 E) and much faster on unit capacity graphs. Adds to any
+    // flow already in the network and returns the added amount. The total
+    // capacity of the edges at s must fit in the weight type.
+    pub fn dinic(&mut self, s:usize, t:usize) -> E {
+        assert!(s != t, "source and sink must differ");
+        self.assert_capacity_fits(s);
+        let mut total = E::zero();
+
+        loop {
+            let level = self.get_levels(s);
+            if level[t].is_none() {
+                break;
+            }
+
+            let mut next_arc:Vec<usize> = vec![0; self.num_nodes()];
+            while let Some(f) = self.augment(s, t, &level, &mut next_arc) {
+                total = total + f;
+            }
+        }
+        total
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // FIFO push-relabel with the gap heuristic, O(V^3). Nodes hold excess
+    // flow and push it downhill along residual arcs, and are lifted when they
+    // cannot. Starts from an empty network and returns the flow value. The
+    // total capacity of the edges at s must fit in the weight type.
+    pub fn push_relabel(&mut self, s:usize, t:usize) -> E {
+        assert!(s != t, "source and sink must differ");
+        self.assert_capacity_fits(s);
+        self.reset();
+
+        let n = self.num_nodes();
+        let mut height:Vec<usize> = vec![0; n];
+        let mut excess:Vec<E> = vec![E::zero(); n];
+        let mut count:Vec<usize> = vec![0; 2*n+1];
+        let mut current:Vec<usize> = vec![0; n];
+        let mut active:Vec<bool> = vec![false; n];
+        let mut queue:VecDeque<usize> = VecDeque::new();
+
+        height[s] = n;
+        count[0] = n-1;
+        count[n] = 1;
+
+        for i in 0..self.arcs[s].len() {
+            let a = self.arcs[s][i];
+            let (v, f) = (self.to[a], self.residual[a]);
+            if f > E::zero() {
+                self.push(a, f);
+                excess[v] = excess[v] + f;
+                if v != t && v != s && !active[v] {
+                    active[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        while let Some(u) = queue.pop_front() {
+            active[u] = false;
+
+            while excess[u] > E::zero() {
+                if current[u] == self.arcs[u].len() {
+                    // Relabel to just above the lowest neighbor with room.
+                    let old = height[u];
+                    height[u] = self.arcs[u].iter().filter(|a| self.residual[**a] > E::zero())
+                        .map(|a| height[self.to[*a]]+1).min().unwrap().min(2*n);
+                    current[u] = 0;
+                    count[old] -= 1;
+                    count[height[u]] += 1;
+
+                    // Nothing left at this height: every node above it, below
+                    // n, can no longer reach t.
+                    if count[old] == 0 && old < n {
+                        for v in 0..n {
+                            if v != s && height[v] > old && height[v] < n {
+                                count[height[v]] -= 1;
+                                height[v] = n+1;
+                                count[n+1] += 1;
+                                current[v] = 0;
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                let a = self.arcs[u][current[u]];
+                let v = self.to[a];
+                if self.residual[a] > E::zero() && height[u] == height[v]+1 {
+                    let f = excess[u].min(self.residual[a]);
+                    self.push(a, f);
+                    excess[u] = excess[u] - f;
+                    excess[v] = excess[v] + f;
+                    if v != s && v != t && !active[v] {
+                        active[v] = true;
+                        queue.push_back(v);
+                    }
+                }
+                else {
+                    current[u] += 1;
+                }
+            }
+        }
+
+        excess[t]
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Runs Dinic from an empty network and reads the cut off the residual
+    // graph.
+    pub fn min_cut(&mut self, s:usize, t:usize) -> MinCut<E> {
+        self.reset();
+        let value = self.dinic(s, t);
+
+        let level = self.get_levels(s);
+        let source_side:Vec<usize> = (0..self.num_nodes()).filter(|u| level[*u].is_some()).collect();
+        let edges:Vec<usize> = (0..self.num_edges()).filter(|e| {
+            let (u, v) = self.endpoints(*e);
+            level[u].is_some() && level[v].is_none() && self.capacity[*e] > E::zero()
+        }).collect();
+
+        MinCut { value, source_side, edges }
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Dijkstra over arcs with capacity left, on costs reduced by the
+    // potentials so that none is negative. Returns the arc used to reach
+    // each node.
+    fn get_cheapest_arcs(&self, s:usize, potential:&[E]) -> (Vec<Option<E>>, Vec<Option<usize>>) {
+        let n = self.num_nodes();
+        let mut dist:Vec<Option<E>> = vec![None; n];
+        let mut pred_arc:Vec<Option<usize>> = vec![None; n];
+        let mut ordered_nodes:BinaryHeap<NodeDist<E>> = BinaryHeap::new();
+
+        dist[s] = Some(E::zero());
+        ordered_nodes.push(NodeDist { node: s, dist: E::zero() });
+
+        while let Some(NodeDist { node: u, dist: d }) = ordered_nodes.pop() {
+            if dist[u].is_some_and(|x| d > x) {
+                continue;
+            }
+
+            for a in self.arcs[u].iter() {
+                if self.residual[*a] <= E::zero() {
+                    continue;
+                }
+                let v = self.to[*a];
+                let dv = d + self.cost[*a] + potential[u] - potential[v];
+                if dist[v].is_none_or(|x| dv < x) {
+                    dist[v] = Some(dv);
+                    pred_arc[v] = Some(*a);
+                    ordered_nodes.push(NodeDist { node: v, dist: dv });
+                }
+            }
+        }
+
+        (dist, pred_arc)
+    }
+}
+
+impl<E: Weight> FlowNetwork<E> {
+    // Maximum flow of least total cost, as (flow, cost), by successive
+    // shortest paths. Bellman-Ford gives the first potentials so that
+    // negative costs are allowed, after that every path is found by Dijkstra
+    // on reduced costs. Returns the nodes of a negative cost cycle, in edge
+    // order, if there is one, as the cheapest flow is then unbounded. Besides
+    // the capacity at s, four times the total of capacity times |cost| over
+    // the edges must fit in the weight type.
+    pub fn min_cost_max_flow(&mut self, s:usize, t:usize) -> Result<(E, E), Vec<usize>> {
+        assert!(s != t, "source and sink must differ");
+        self.assert_capacity_fits(s);
+        self.assert_costs_fit();
+        self.reset();
+
+        let n = self.num_nodes();
+        let residual_edges:Vec<Edge<E>> = (0..self.to.len()).filter(|a| self.residual[*a] > E::zero())
+            .map(|a| Edge { from: self.to[a^1], to: self.to[a], weight: self.cost[a] }).collect();
+        let (paths, neg_cycle) = bellman_ford(&Csr::from_edges(n, &residual_edges, true), s);
+        if let Some(c) = neg_cycle {
+            return Err(c.cycle);
+        }
+
+        let mut potential:Vec<E> = paths.distances().iter().map(|d| d.unwrap_or(E::zero())).collect();
+        let (mut total_flow, mut total_cost) = (E::zero(), E::zero());
+
+        loop {
+            let (dist, pred_arc) = self.get_cheapest_arcs(s, &potential);
+            if dist[t].is_none() {
+                break;
+            }
+            for (p, d) in potential.iter_mut().zip(dist.iter()) {
+                if let Some(d) = d {
+                    *p = *p + *d;
+                }
+            }
+
+            let mut path:Vec<usize> = Vec::new();
+            let mut v = t;
+            while let Some(a) = pred_arc[v] {
+                path.push(a);
+                v = self.to[a^1];
+            }
+
+            let f = path.iter().map(|a| self.residual[*a]).min().unwrap();
+            for a in path.iter() {
+                self.push(*a, f);
+                total_cost = total_cost + f*self.cost[*a];
+            }
+            total_flow = total_flow + f;
+        }
+
+        Ok((total_flow, total_cost))
+    }
+}
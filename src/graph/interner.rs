@@ -0,0 +1,69 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Maps keys to dense ids 0..n in insertion order and back.
+pub struct Interner<N> {
+    keys: Vec<N>,
+    ids: HashMap<N, usize>,
+}
+
+impl<N: Hash + Eq + Clone> Interner<N> {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Default for Interner<N> {
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+impl<N: Hash + Eq + Clone> Interner<N> {
+    // Id of the key, assigning the next free one if it is new.
+    pub fn intern(&mut self, key:N) -> usize {
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+
+        let id = self.keys.len();
+        self.keys.push(key.clone());
+        self.ids.insert(key, id);
+        id
+    }
+}
+
+impl<N: Hash + Eq + Clone> Interner<N> {
+    pub fn id<Q>(&self, key:&Q) -> Option<usize> where N: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.ids.get(key).copied()
+    }
+}
+
+impl<N> Interner<N> {
+    pub fn key(&self, id:usize) -> &N {
+        &self.keys[id]
+    }
+}
+
+impl<N> Interner<N> {
+    // All keys, indexed by id.
+    pub fn keys(&self) -> &[N] {
+        &self.keys
+    }
+}
+
+impl<N> Interner<N> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl<N> Interner<N> {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
@@ -0,0 +1,329 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Sub};
+
+pub mod all_pairs;
+pub mod components;
+pub mod csr;
+pub mod disjoint_set;
+pub mod flow;
+pub mod interner;
+pub mod search;
+pub mod shortest_path;
+pub mod spanning_tree;
+pub mod topological_sort;
+
+use csr::Csr;
+use interner::Interner;
+
+// Edge weights the path algorithms can add up and compare, also used for
+// capacities and costs in flow networks.
+pub trait Weight: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    // Clamps at the bounds of the type instead of overflowing.
+    fn saturating_add(self, other:Self) -> Self;
+    // None if the result does not fit in the type.
+    fn checked_add(self, other:Self) -> Option<Self>;
+    fn checked_sub(self, other:Self) -> Option<Self>;
+    fn checked_mul(self, other:Self) -> Option<Self>;
+    // Wraps around at the bounds of the type, and tells whether it did.
+    fn overflowing_add(self, other:Self) -> (Self, bool);
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => {
+        $(impl Weight for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn saturating_add(self, other:Self) -> Self {
+                <$t>::saturating_add(self, other)
+            }
+
+            fn checked_add(self, other:Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other:Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other:Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn overflowing_add(self, other:Self) -> (Self, bool) {
+                <$t>::overflowing_add(self, other)
+            }
+        })*
+    };
+}
+
+impl_weight!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// What the algorithms need from a graph: nodes are the ids 0..num_nodes and
+// every node lists its outgoing neighbors with the edge weight. Implemented
+// by `Graph` and by its static `Csr` form.
+pub trait Adjacency<E> {
+    fn num_nodes(&self) -> usize;
+    fn neighbors<'a>(&'a self, u:usize) -> impl Iterator<Item = (usize, &'a E)> where E: 'a;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge<E> {
+    pub from: usize,
+    pub to: usize,
+    pub weight: E,
+}
+
+// Graph over dense node ids, with the keys of type N interned to ids and
+// edges kept in one list. Every node lists the ids of its outgoing edges, and
+// in a directed graph also of its incoming ones. An undirected edge is stored
+// once and listed at both ends.
+pub struct Graph<N, E> {
+    directed: bool,
+    nodes: Interner<N>,
+    edges: Vec<Edge<E>>,
+    out_edges: Vec<Vec<usize>>,
+    in_edges: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn new(directed:bool) -> Self {
+        Self {
+            directed,
+            nodes: Interner::new(),
+            edges: Vec::new(),
+            out_edges: Vec::new(),
+            in_edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn directed() -> Self {
+        Graph::new(true)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn undirected() -> Self {
+        Graph::new(false)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    // Id of the node, adding it if it is new.
+    pub fn add_node(&mut self, key:N) -> usize {
+        let id = self.nodes.intern(key);
+        if id == self.out_edges.len() {
+            self.out_edges.push(Vec::new());
+            self.in_edges.push(Vec::new());
+        }
+        id
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    // Adds the nodes if needed and returns the id of the new edge. Parallel
+    // edges and self loops are allowed.
+    pub fn add_edge(&mut self, from:N, to:N, weight:E) -> usize {
+        let u = self.add_node(from);
+        let v = self.add_node(to);
+        self.add_edge_ids(u, v, weight)
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn add_edge_ids(&mut self, u:usize, v:usize, weight:E) -> usize {
+        assert!(u < self.num_nodes() && v < self.num_nodes(), "node id out of range");
+
+        let id = self.edges.len();
+        self.edges.push(Edge { from: u, to: v, weight });
+        self.out_edges[u].push(id);
+
+        if self.directed {
+            self.in_edges[v].push(id);
+        }
+        else if u != v {
+            self.out_edges[v].push(id);
+        }
+        id
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn id<Q>(&self, key:&Q) -> Option<usize> where N: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.nodes.id(key)
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn key(&self, id:usize) -> &N {
+        self.nodes.key(id)
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn keys(&self) -> &[N] {
+        self.nodes.keys()
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn num_nodes(&self) -> usize {
+        self.out_edges.len()
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn edge(&self, id:usize) -> &Edge<E> {
+        &self.edges[id]
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn edges(&self) -> &[Edge<E>] {
+        &self.edges
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    // (neighbor, edge id) for every edge leaving u, or touching u if the
+    // graph is undirected.
+    pub fn out_edges(&self, u:usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.out_edges[u].iter().map(move |e| {
+            let edge = &self.edges[*e];
+            (if edge.from == u { edge.to } else { edge.from }, *e)
+        })
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    // (neighbor, edge id) for every edge entering u. Same as `out_edges` in
+    // an undirected graph.
+    pub fn in_edges(&self, u:usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let ids = if self.directed { &self.in_edges[u] } else { &self.out_edges[u] };
+        ids.iter().map(move |e| {
+            let edge = &self.edges[*e];
+            (if edge.to == u { edge.from } else { edge.to }, *e)
+        })
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn neighbors(&self, u:usize) -> impl Iterator<Item = (usize, &E)> {
+        self.out_edges(u).map(|(v, e)| (v, &self.edges[e].weight))
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn in_neighbors(&self, u:usize) -> impl Iterator<Item = (usize, &E)> {
+        self.in_edges(u).map(|(v, e)| (v, &self.edges[e].weight))
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn out_degree(&self, u:usize) -> usize {
+        self.out_edges[u].len()
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn in_degree(&self, u:usize) -> usize {
+        if self.directed { self.in_edges[u].len() } else { self.out_edges[u].len() }
+    }
+}
+
+impl<N, E: Clone> Graph<N, E> {
+    // Static copy of the adjacency lists for faster traversal.
+    pub fn to_csr(&self) -> Csr<E> {
+        Csr::from_edges(self.num_nodes(), &self.edges, self.directed)
+    }
+}
+
+impl<N, E> Adjacency<E> for Graph<N, E> {
+    fn num_nodes(&self) -> usize {
+        Graph::num_nodes(self)
+    }
+
+    fn neighbors<'a>(&'a self, u:usize) -> impl Iterator<Item = (usize, &'a E)> where E: 'a {
+        Graph::neighbors(self, u)
+    }
+}
+
+// Heap entry ordered by distance, reversed so that BinaryHeap pops the
+// closest node first.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub(crate) struct NodeDist<E> {
+    pub node: usize,
+    pub dist: E,
+}
+
+impl<E: Ord> Ord for NodeDist<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.dist.cmp(&self.dist).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl<E: Ord> PartialOrd for NodeDist<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Exact sum of weights, whatever their type: the sum wrapped around like the
+// weight type, and how many times it wrapped, up or down. Field order makes
+// the derived order that of the exact sums.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Debug)]
+pub(crate) struct WideSum<E> {
+    wraps: i64,
+    low: E,
+}
+
+impl<E: Weight> WideSum<E> {
+    pub fn new(x:E) -> Self {
+        WideSum { wraps: 0, low: x }
+    }
+}
+
+impl<E: Weight> WideSum<E> {
+    pub fn add(self, w:E) -> Self {
+        let (low, wrapped) = self.low.overflowing_add(w);
+        let wraps = match (wrapped, w < E::zero()) {
+            (false, _) => self.wraps,
+            (true, false) => self.wraps+1,
+            (true, true) => self.wraps-1,
+        };
+        WideSum { wraps, low }
+    }
+}
+
+impl<E: Weight> WideSum<E> {
+    pub fn add_sum(self, other:Self) -> Self {
+        let sum = self.add(other.low);
+        WideSum { wraps: sum.wraps + other.wraps, low: sum.low }
+    }
+}
+
+impl<E: Weight> WideSum<E> {
+    // None if the sum does not fit in the weight type.
+    pub fn get(self) -> Option<E> {
+        (self.wraps == 0).then_some(self.low)
+    }
+}
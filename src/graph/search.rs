@@ -0,0 +1,138 @@
+use std::collections::BinaryHeap;
+use super::{Adjacency, Graph, NodeDist, Weight};
+
+// Follows the predecessors back from v to the start of the search.
+fn get_path(pred:&[Option<usize>], src:usize, mut v:usize) -> Vec<usize> {
+    let mut path:Vec<usize> = vec![v];
+    while v != src {
+        v = pred[v].unwrap();
+        path.push(v);
+    }
+    path.reverse();
+    path
+}
+
+// Shortest distance and path from src to target, or None if target is
+// unreachable. The heuristic estimates the distance from a node to target
+// and must never overestimate it, otherwise the path may not be shortest.
+// Weights must not be negative.
+pub fn astar<E: Weight, G: Adjacency<E>, H: Fn(usize) -> E>(graph:&G, src:usize, target:usize, heuristic:H) -> Option<(E, Vec<usize>)> {
+    let num_nodes = graph.num_nodes();
+    let mut dist:Vec<Option<E>> = vec![None; num_nodes];
+    let mut pred:Vec<Option<usize>> = vec![None; num_nodes];
+    let mut ordered_nodes:BinaryHeap<NodeDist<E>> = BinaryHeap::new();
+
+    dist[src] = Some(E::zero());
+    ordered_nodes.push(NodeDist { node: src, dist: heuristic(src) });
+
+    while let Some(NodeDist { node: u, dist: f }) = ordered_nodes.pop() {
+        let du = dist[u].unwrap();
+        // Stale entry, u was reached by a shorter path since.
+        if f > du.saturating_add(heuristic(u)) {
+            continue;
+        }
+        if u == target {
+            return Some((du, get_path(&pred, src, target)));
+        }
+
+        for (v, w) in graph.neighbors(u) {
+            let dv = du.saturating_add(*w);
+            if dist[v].is_none_or(|x| dv < x) {
+                dist[v] = Some(dv);
+                pred[v] = Some(u);
+                ordered_nodes.push(NodeDist { node: v, dist: dv.saturating_add(heuristic(v)) });
+            }
+        }
+    }
+
+    None
+}
+
+// One direction of the bidirectional search.
+struct Frontier<E> {
+    dist: Vec<Option<E>>,
+    pred: Vec<Option<usize>>,
+    ordered_nodes: BinaryHeap<NodeDist<E>>,
+}
+
+impl<E: Weight> Frontier<E> {
+    fn new(num_nodes:usize, start:usize) -> Self {
+        let mut dist:Vec<Option<E>> = vec![None; num_nodes];
+        dist[start] = Some(E::zero());
+
+        Self {
+            dist,
+            pred: vec![None; num_nodes],
+            ordered_nodes: BinaryHeap::from([NodeDist { node: start, dist: E::zero() }]),
+        }
+    }
+}
+
+impl<E: Weight> Frontier<E> {
+    fn peek_dist(&self) -> Option<E> {
+        self.ordered_nodes.peek().map(|n| n.dist)
+    }
+}
+
+impl<E: Weight> Frontier<E> {
+    // Settles the closest node, relaxing its edges given by `edges`, and
+    // updates the best meeting point with the other side.
+    fn step<'a, I: Iterator<Item = (usize, &'a E)>>(&mut self, other:&Frontier<E>, edges:impl Fn(usize) -> I, best:&mut Option<(E, usize)>) where E: 'a {
+        let NodeDist { node: u, dist: d } = self.ordered_nodes.pop().unwrap();
+        if self.dist[u].is_some_and(|x| d > x) {
+            return;
+        }
+
+        for (v, w) in edges(u) {
+            let dv = d.saturating_add(*w);
+            if self.dist[v].is_none_or(|x| dv < x) {
+                self.dist[v] = Some(dv);
+                self.pred[v] = Some(u);
+                self.ordered_nodes.push(NodeDist { node: v, dist: dv });
+
+                if let Some(x) = other.dist[v] {
+                    let total = dv.saturating_add(x);
+                    if best.is_none_or(|(b, _)| total < b) {
+                        *best = Some((total, v));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Point to point Dijkstra growing one search from src along outgoing edges
+// and one from target along incoming edges, always extending the side whose
+// next node is closer. It stops once the two nearest unsettled nodes are
+// together no closer than the best path found through a node both sides
+// have reached, which on road-like graphs settles far fewer nodes than a
+// single search. Weights must not be negative.
+pub fn bidirectional_dijkstra<N, E: Weight>(graph:&Graph<N, E>, src:usize, target:usize) -> Option<(E, Vec<usize>)> {
+    let num_nodes = graph.num_nodes();
+    let mut forward:Frontier<E> = Frontier::new(num_nodes, src);
+    let mut backward:Frontier<E> = Frontier::new(num_nodes, target);
+    let mut best:Option<(E, usize)> = if src == target { Some((E::zero(), src)) } else { None };
+
+    while let (Some(df), Some(db)) = (forward.peek_dist(), backward.peek_dist()) {
+        if best.is_some_and(|(b, _)| df.saturating_add(db) >= b) {
+            break;
+        }
+
+        if df <= db {
+            forward.step(&backward, |u| graph.neighbors(u), &mut best);
+        }
+        else {
+            backward.step(&forward, |u| graph.in_neighbors(u), &mut best);
+        }
+    }
+
+    let (total, meet) = best?;
+    let mut path = get_path(&forward.pred, src, meet);
+    let mut v = meet;
+    while v != target {
+        v = backward.pred[v].unwrap();
+        path.push(v);
+    }
+
+    Some((total, path))
+}
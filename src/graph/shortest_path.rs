@@ -0,0 +1,312 @@
+use std::collections::{BinaryHeap, VecDeque};
+use super::{Adjacency, NodeDist, Weight, WideSum};
+
+// Result of a single source search: the distance to every node, None if
+// unreachable, and the node before it on a shortest path.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<E> {
+    src: usize,
+    // Exact distances, found without overflow whatever the weight type.
+    sums: Vec<Option<WideSum<E>>>,
+    dist: Vec<Option<E>>,
+    pred: Vec<Option<usize>>,
+    overflowed: Vec<usize>,
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    fn new(num_nodes:usize, src:usize) -> Self {
+        let mut sums:Vec<Option<WideSum<E>>> = vec![None; num_nodes];
+        sums[src] = Some(WideSum::new(E::zero()));
+
+        Self {
+            src,
+            sums,
+            dist: vec![None; num_nodes],
+            pred: vec![None; num_nodes],
+            overflowed: Vec::new(),
+        }
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    // Records a shorter path to v through the edge u -> v of weight w, if it
+    // is one, and returns its length.
+    fn relax_edge(&mut self, u:usize, v:usize, w:E) -> Option<WideSum<E>> {
+        let dv = self.sums[u].unwrap().add(w);
+        if self.sums[v].is_some_and(|x| dv >= x) {
+            return None;
+        }
+        self.sums[v] = Some(dv);
+        self.pred[v] = Some(u);
+        Some(dv)
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    pub fn source(&self) -> usize {
+        self.src
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    pub fn distance(&self, v:usize) -> Option<E> {
+        self.dist[v]
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    pub fn distances(&self) -> &[Option<E>] {
+        &self.dist
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    pub fn is_reachable(&self, v:usize) -> bool {
+        self.sums[v].is_some()
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    // Reachable nodes whose distance does not fit in the weight type, sorted
+    // by id. Their distance is None, but their path is kept.
+    pub fn overflowed(&self) -> &[usize] {
+        &self.overflowed
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    // None for the source and for unreachable nodes.
+    pub fn predecessor(&self, v:usize) -> Option<usize> {
+        self.pred[v]
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    pub fn predecessors(&self) -> &[Option<usize>] {
+        &self.pred
+    }
+}
+
+impl<E: Weight> ShortestPaths<E> {
+    // Nodes on the path from the source to target, both included. None if
+    // target is unreachable, or if following the predecessors runs into a
+    // cycle, which only happens after a negative cycle was found.
+    pub fn path_to(&self, target:usize) -> Option<Vec<usize>> {
+        self.sums[target]?;
+
+        let mut path:Vec<usize> = vec![target];
+        let mut v = target;
+
+        while v != self.src {
+            v = self.pred[v]?;
+            path.push(v);
+            if path.len() > self.dist.len() {
+                return None;
+            }
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Shortest paths from src to every node. Weights must not be negative.
+pub fn dijkstra<E: Weight, G: Adjacency<E>>(graph:&G, src:usize) -> ShortestPaths<E> {
+    dijkstra_search(graph, src, None)
+}
+
+// Stops as soon as the distance to target is final. Only the path to target
+// is then guaranteed to be shortest, other nodes may keep longer paths.
+pub fn dijkstra_to<E: Weight, G: Adjacency<E>>(graph:&G, src:usize, target:usize) -> ShortestPaths<E> {
+    dijkstra_search(graph, src, Some(target))
+}
+
+fn dijkstra_search<E: Weight, G: Adjacency<E>>(graph:&G, src:usize, target:Option<usize>) -> ShortestPaths<E> {
+    let mut paths:ShortestPaths<E> = ShortestPaths::new(graph.num_nodes(), src);
+    let mut ordered_nodes:BinaryHeap<NodeDist<WideSum<E>>> = BinaryHeap::new();
+    ordered_nodes.push(NodeDist { node: src, dist: WideSum::new(E::zero()) });
+
+    while let Some(NodeDist { node: u, dist: d }) = ordered_nodes.pop() {
+        // Stale entry, u was reached by a shorter path since.
+        if paths.sums[u].is_some_and(|x| d > x) {
+            continue;
+        }
+        if target == Some(u) {
+            break;
+        }
+
+        for (v, w) in graph.neighbors(u) {
+            if let Some(dv) = paths.relax_edge(u, v, *w) {
+                ordered_nodes.push(NodeDist { node: v, dist: dv });
+            }
+        }
+    }
+
+    finish_search(graph, paths, None, Vec::new()).0
+}
+
+// A negative cycle reachable from the source, and every node whose distance
+// is unbounded below because it can be reached from a negative cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeCycle {
+    // Nodes in edge order, the last one leads back to the first.
+    pub cycle: Vec<usize>,
+    // Sorted by id.
+    pub unbounded: Vec<usize>,
+}
+
+// Follows the predecessors from start and returns the cycle it runs into, in
+// edge order. Any cycle among the predecessors of a relaxation based search
+// is a negative one.
+fn get_predecessor_cycle(pred:&[Option<usize>], start:usize) -> Option<Vec<usize>> {
+    let mut seen:Vec<bool> = vec![false; pred.len()];
+    let mut v = start;
+    while !seen[v] {
+        seen[v] = true;
+        v = pred[v]?;
+    }
+
+    let mut cycle:Vec<usize> = vec![v];
+    let mut u = pred[v].unwrap();
+    while u != v {
+        cycle.push(u);
+        u = pred[u].unwrap();
+    }
+
+    cycle.reverse();
+    Some(cycle)
+}
+
+// Marks every node reachable from start.
+fn mark_reachable<E, G: Adjacency<E>>(graph:&G, start:usize, marked:&mut [bool]) {
+    if marked[start] {
+        return;
+    }
+    marked[start] = true;
+    let mut stack:Vec<usize> = vec![start];
+
+    while let Some(u) = stack.pop() {
+        for (v, _) in graph.neighbors(u) {
+            if !marked[v] {
+                marked[v] = true;
+                stack.push(v);
+            }
+        }
+    }
+}
+
+// One round of relaxing every edge out of a reached node, returns the nodes
+// that got closer.
+fn relax_all<E: Weight, G: Adjacency<E>>(graph:&G, paths:&mut ShortestPaths<E>) -> Vec<usize> {
+    let mut relaxed:Vec<usize> = Vec::new();
+
+    for u in 0..graph.num_nodes() {
+        if paths.sums[u].is_none() {
+            continue;
+        }
+
+        for (v, w) in graph.neighbors(u) {
+            if paths.relax_edge(u, v, *w).is_some() {
+                relaxed.push(v);
+            }
+        }
+    }
+    relaxed
+}
+
+// Marks everything reachable from unbounded_from as unbounded, keeps the
+// distances that fit in the weight type and reports the others.
+fn finish_search<E: Weight, G: Adjacency<E>>(graph:&G, mut paths:ShortestPaths<E>, cycle:Option<Vec<usize>>, unbounded_from:Vec<usize>) -> (ShortestPaths<E>, Option<NegativeCycle>) {
+    let num_nodes = graph.num_nodes();
+    let mut unbounded:Vec<bool> = vec![false; num_nodes];
+    for v in unbounded_from.into_iter().chain(cycle.iter().flatten().copied()) {
+        mark_reachable(graph, v, &mut unbounded);
+    }
+
+    paths.dist = paths.sums.iter().map(|d| d.and_then(|d| d.get())).collect();
+    paths.overflowed = (0..num_nodes).filter(|v| !unbounded[*v] && paths.sums[*v].is_some() && paths.dist[*v].is_none()).collect();
+    let neg_cycle = cycle.map(|cycle| NegativeCycle {
+        cycle,
+        unbounded: (0..num_nodes).filter(|v| unbounded[*v]).collect(),
+    });
+
+    (paths, neg_cycle)
+}
+
+// Shortest paths from src allowing negative weights. Stops as soon as a round
+// relaxes nothing. If a negative cycle is reachable, the distances and paths
+// of the unbounded nodes it reports are meaningless. Sums are exact, so
+// cycles are found whatever the weight type, and distances that do not fit
+// in it are reported by `overflowed`.
+pub fn bellman_ford<E: Weight, G: Adjacency<E>>(graph:&G, src:usize) -> (ShortestPaths<E>, Option<NegativeCycle>) {
+    let num_nodes = graph.num_nodes();
+    let mut paths:ShortestPaths<E> = ShortestPaths::new(num_nodes, src);
+
+    // Shortest paths have at most n-1 edges, anything still relaxing in
+    // round n is reachable from a negative cycle, and every negative cycle
+    // reachable from src has a node that relaxes in it.
+    let mut relaxed:Vec<usize> = Vec::new();
+    for _ in 0..num_nodes {
+        relaxed = relax_all(graph, &mut paths);
+        if relaxed.is_empty() {
+            break;
+        }
+    }
+
+    // Such a node is now shorter than any path without a cycle, so its
+    // predecessors cannot lead back to src and must run into a cycle.
+    let cycle = relaxed.iter().find_map(|v| get_predecessor_cycle(&paths.pred, *v));
+
+    finish_search(graph, paths, cycle, relaxed)
+}
+
+// Bellman-Ford driven by a queue of the nodes whose distance changed, which
+// usually touches far fewer edges. A node whose path grows to n edges is
+// behind a negative cycle: it and everything reachable from it is marked
+// unbounded and left alone, so the search still settles the other nodes.
+pub fn spfa<E: Weight, G: Adjacency<E>>(graph:&G, src:usize) -> (ShortestPaths<E>, Option<NegativeCycle>) {
+    let num_nodes = graph.num_nodes();
+    let mut paths:ShortestPaths<E> = ShortestPaths::new(num_nodes, src);
+
+    let mut num_edges:Vec<usize> = vec![0; num_nodes];
+    let mut in_queue:Vec<bool> = vec![false; num_nodes];
+    let mut unbounded:Vec<bool> = vec![false; num_nodes];
+    let mut cycle:Option<Vec<usize>> = None;
+
+    let mut queue:VecDeque<usize> = VecDeque::from([src]);
+    in_queue[src] = true;
+
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+        if unbounded[u] {
+            continue;
+        }
+
+        for (v, w) in graph.neighbors(u) {
+            if unbounded[v] || paths.relax_edge(u, v, *w).is_none() {
+                continue;
+            }
+
+            num_edges[v] = num_edges[u]+1;
+            if num_edges[v] >= num_nodes {
+                if cycle.is_none() {
+                    cycle = get_predecessor_cycle(&paths.pred, v);
+                }
+                mark_reachable(graph, v, &mut unbounded);
+            }
+            else if !in_queue[v] {
+                in_queue[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+
+    // The predecessors need not have closed a cycle yet when a path first
+    // reached n edges, then Bellman-Ford looks for one.
+    let unbounded_from:Vec<usize> = (0..num_nodes).filter(|v| unbounded[*v]).collect();
+    if cycle.is_none() && !unbounded_from.is_empty() {
+        cycle = bellman_ford(graph, src).1.map(|c| c.cycle);
+    }
+
+    finish_search(graph, paths, cycle, unbounded_from)
+}
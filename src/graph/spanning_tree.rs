@@ -0,0 +1,128 @@
+use std::collections::BinaryHeap;
+use super::disjoint_set::DisjointSet;
+use super::{Graph, NodeDist, Weight, WideSum};
+
+// Minimum spanning forest: one tree per connected component, given by the ids
+// of its edges in the graph, and their total weight, None if it does not fit
+// in the weight type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanningForest<E> {
+    pub weight: Option<E>,
+    pub edges: Vec<usize>,
+}
+
+impl<E: Weight> SpanningForest<E> {
+    fn new<N>(graph:&Graph<N, E>, edges:Vec<usize>) -> Self {
+        let weight = edges.iter().fold(WideSum::new(E::zero()), |total, e| total.add(graph.edge(*e).weight)).get();
+        Self {
+            weight,
+            edges,
+        }
+    }
+}
+
+// Kruskal: edges by increasing weight, keeping those that join two trees.
+pub fn kruskal<N, E: Weight>(graph:&Graph<N, E>) -> SpanningForest<E> {
+    assert!(!graph.is_directed(), "spanning trees need an undirected graph");
+
+    let mut order:Vec<usize> = (0..graph.num_edges()).collect();
+    order.sort_by_key(|e| graph.edge(*e).weight);
+
+    let mut components = DisjointSet::new(graph.num_nodes());
+    let mut chosen:Vec<usize> = Vec::new();
+
+    for e in order.into_iter() {
+        let edge = graph.edge(e);
+        if components.union(edge.from, edge.to) {
+            chosen.push(e);
+            if components.num_sets() == 1 {
+                break;
+            }
+        }
+    }
+
+    SpanningForest::new(graph, chosen)
+}
+
+// Prim: grows a tree from each node not yet covered, always adding the
+// lightest edge leaving it. Nodes wait in a heap keyed by their lightest edge
+// to the tree, as in Dijkstra.
+pub fn prim<N, E: Weight>(graph:&Graph<N, E>) -> SpanningForest<E> {
+    assert!(!graph.is_directed(), "spanning trees need an undirected graph");
+
+    let num_nodes = graph.num_nodes();
+    let mut in_tree:Vec<bool> = vec![false; num_nodes];
+    let mut best_edge:Vec<Option<usize>> = vec![None; num_nodes];
+    let mut chosen:Vec<usize> = Vec::new();
+
+    for root in 0..num_nodes {
+        if in_tree[root] {
+            continue;
+        }
+
+        let mut ordered_nodes:BinaryHeap<NodeDist<E>> = BinaryHeap::new();
+        ordered_nodes.push(NodeDist { node: root, dist: E::zero() });
+
+        while let Some(NodeDist { node: u, dist: d }) = ordered_nodes.pop() {
+            if in_tree[u] || best_edge[u].is_some_and(|e| d > graph.edge(e).weight) {
+                continue;
+            }
+            in_tree[u] = true;
+            chosen.extend(best_edge[u]);
+
+            for (v, e) in graph.out_edges(u) {
+                let w = graph.edge(e).weight;
+                if !in_tree[v] && best_edge[v].is_none_or(|b| w < graph.edge(b).weight) {
+                    best_edge[v] = Some(e);
+                    ordered_nodes.push(NodeDist { node: v, dist: w });
+                }
+            }
+        }
+    }
+
+    SpanningForest::new(graph, chosen)
+}
+
+// Boruvka: every tree picks its lightest outgoing edge and all of them are
+// added at once, which at least halves the number of trees per round. Ties
+// are broken by edge id so that the picked edges never close a cycle.
+pub fn boruvka<N, E: Weight>(graph:&Graph<N, E>) -> SpanningForest<E> {
+    assert!(!graph.is_directed(), "spanning trees need an undirected graph");
+
+    let num_nodes = graph.num_nodes();
+    let mut components = DisjointSet::new(num_nodes);
+    let mut chosen:Vec<usize> = Vec::new();
+
+    loop {
+        let mut cheapest:Vec<Option<usize>> = vec![None; num_nodes];
+
+        for (e, edge) in graph.edges().iter().enumerate() {
+            let (a, b) = (components.find(edge.from), components.find(edge.to));
+            if a == b {
+                continue;
+            }
+
+            for root in [a, b] {
+                let lighter = cheapest[root].is_none_or(|c| (edge.weight, e) < (graph.edge(c).weight, c));
+                if lighter {
+                    cheapest[root] = Some(e);
+                }
+            }
+        }
+
+        let mut merged:bool = false;
+        for e in cheapest.into_iter().flatten() {
+            let edge = graph.edge(e);
+            if components.union(edge.from, edge.to) {
+                chosen.push(e);
+                merged = true;
+            }
+        }
+
+        if !merged {
+            break;
+        }
+    }
+
+    SpanningForest::new(graph, chosen)
+}
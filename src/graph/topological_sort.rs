@@ -0,0 +1,52 @@
+use super::Graph;
+
+// Kahn's algorithm, one level of nodes without remaining dependencies at a
+// time. Returns the nodes of a cycle, in edge order, if there is one.
+pub fn topsort<N, E>(graph:&Graph<N, E>) -> Result<Vec<usize>, Vec<usize>> {
+    assert!(graph.is_directed(), "topological sort needs a directed graph");
+
+    let mut in_deg:Vec<usize> = (0..graph.num_nodes()).map(|u| graph.in_degree(u)).collect();
+    let mut output:Vec<usize> = Vec::with_capacity(graph.num_nodes());
+    let mut level:Vec<usize> = (0..graph.num_nodes()).filter(|u| in_deg[*u] == 0).collect();
+
+    while !level.is_empty() {
+        let mut next_level:Vec<usize> = Vec::new();
+
+        for u in level.into_iter() {
+            output.push(u);
+
+            for (v, _) in graph.out_edges(u) {
+                in_deg[v] -= 1;
+                if in_deg[v] == 0 {
+                    next_level.push(v);
+                }
+            }
+        }
+
+        level = next_level;
+    }
+
+    match (0..graph.num_nodes()).find(|u| in_deg[*u] > 0) {
+        Some(u) => Err(get_cycle(graph, &in_deg, u)),
+        None => Ok(output),
+    }
+}
+
+// Every node left out by Kahn's algorithm still has an edge coming in from
+// another one left out, so walking those edges backwards from u must run into
+// a node seen before.
+fn get_cycle<N, E>(graph:&Graph<N, E>, in_deg:&[usize], u:usize) -> Vec<usize> {
+    let mut seen_at:Vec<Option<usize>> = vec![None; graph.num_nodes()];
+    let mut walk:Vec<usize> = Vec::new();
+    let mut x = u;
+
+    while seen_at[x].is_none() {
+        seen_at[x] = Some(walk.len());
+        walk.push(x);
+        x = graph.in_edges(x).map(|(v, _)| v).find(|v| in_deg[*v] > 0).unwrap();
+    }
+
+    let mut cycle = walk.split_off(seen_at[x].unwrap());
+    cycle.reverse();
+    cycle
+}
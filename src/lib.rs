@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod compression;
+pub mod graph;
+pub mod probabilistic;
@@ -0,0 +1,203 @@
+use std::hash::Hash;
+use super::{hash128, hash128_bytes, DEFAULT_SEED};
+
+// k positions from a single 128-bit murmur3 hash via h1 + i*h2 (Kirsch-Mitzenmacher).
+fn get_positions(hsh:u128, num_hashes:usize, size:usize) -> impl Iterator<Item = usize> {
+    let h1 = hsh as u64;
+    let h2 = (hsh >> 64) as u64;
+    (0..num_hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % size as u64) as usize)
+}
+
+// Number of bits and hash functions needed to hold n items at the given false positive rate.
+fn get_dimensions(n:usize, fp_rate:f64) -> (usize, usize) {
+    assert!(n > 0, "capacity must be positive");
+    assert!(fp_rate > 0.0 && fp_rate < 1.0, "false positive rate must be in (0, 1)");
+
+    let ln2 = std::f64::consts::LN_2;
+    let m = (-(n as f64)*fp_rate.ln()/(ln2*ln2)).ceil() as usize;
+    let k = ((m as f64/n as f64)*ln2).round() as usize;
+    (m.max(1), k.max(1))
+}
+
+pub struct BloomFilter {
+    num_bits: usize,
+    num_hashes: usize,
+    bits: Vec<u64>,
+    num_items: usize,
+}
+
+impl BloomFilter {
+    pub fn new(num_bits:usize, num_hashes:usize) -> Self {
+        assert!(num_bits > 0 && num_hashes > 0, "number of bits and hashes must be positive");
+
+        Self {
+            num_bits,
+            num_hashes,
+            bits: vec![0;num_bits.div_ceil(64)],
+            num_items: 0,
+        }
+    }
+}
+
+impl BloomFilter {
+    pub fn from_capacity(n:usize, fp_rate:f64) -> Self {
+        let (m, k) = get_dimensions(n, fp_rate);
+        BloomFilter::new(m, k)
+    }
+}
+
+impl BloomFilter {
+    pub fn add<T: Hash + ?Sized>(&mut self, data:&T) {
+        self.add_hash(hash128(data, DEFAULT_SEED));
+    }
+}
+
+impl BloomFilter {
+    pub fn add_bytes(&mut self, data:&[u8]) {
+        self.add_hash(hash128_bytes(data, DEFAULT_SEED));
+    }
+}
+
+impl BloomFilter {
+    fn add_hash(&mut self, hsh:u128) {
+        for p in get_positions(hsh, self.num_hashes, self.num_bits) {
+            self.bits[p/64] |= 1 << (p%64);
+        }
+        self.num_items += 1;
+    }
+}
+
+impl BloomFilter {
+    pub fn contains<T: Hash + ?Sized>(&self, data:&T) -> bool {
+        self.contains_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl BloomFilter {
+    pub fn contains_bytes(&self, data:&[u8]) -> bool {
+        self.contains_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl BloomFilter {
+    fn contains_hash(&self, hsh:u128) -> bool {
+        get_positions(hsh, self.num_hashes, self.num_bits).all(|p| self.bits[p/64] & (1 << (p%64)) != 0)
+    }
+}
+
+impl BloomFilter {
+    // (1 - e^(-kn/m))^k for the number of items added so far.
+    pub fn expected_fp_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        let x = -k*self.num_items as f64/self.num_bits as f64;
+        (1.0-x.exp()).powf(k)
+    }
+}
+
+impl BloomFilter {
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+}
+
+impl BloomFilter {
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+}
+
+// Same layout as `BloomFilter` but with a small counter per position, which
+// makes it possible to remove items that were added earlier.
+pub struct CountingBloomFilter {
+    num_counters: usize,
+    num_hashes: usize,
+    counters: Vec<u8>,
+}
+
+impl CountingBloomFilter {
+    pub fn new(num_counters:usize, num_hashes:usize) -> Self {
+        assert!(num_counters > 0 && num_hashes > 0, "number of counters and hashes must be positive");
+
+        Self {
+            num_counters,
+            num_hashes,
+            counters: vec![0;num_counters],
+        }
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn from_capacity(n:usize, fp_rate:f64) -> Self {
+        let (m, k) = get_dimensions(n, fp_rate);
+        CountingBloomFilter::new(m, k)
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn add<T: Hash + ?Sized>(&mut self, data:&T) {
+        self.add_hash(hash128(data, DEFAULT_SEED));
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn add_bytes(&mut self, data:&[u8]) {
+        self.add_hash(hash128_bytes(data, DEFAULT_SEED));
+    }
+}
+
+impl CountingBloomFilter {
+    fn add_hash(&mut self, hsh:u128) {
+        // A saturated counter is never decremented again, it stays a (safe) false positive.
+        for p in get_positions(hsh, self.num_hashes, self.num_counters) {
+            self.counters[p] = self.counters[p].saturating_add(1);
+        }
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn contains<T: Hash + ?Sized>(&self, data:&T) -> bool {
+        self.contains_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn contains_bytes(&self, data:&[u8]) -> bool {
+        self.contains_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CountingBloomFilter {
+    fn contains_hash(&self, hsh:u128) -> bool {
+        get_positions(hsh, self.num_hashes, self.num_counters).all(|p| self.counters[p] > 0)
+    }
+}
+
+impl CountingBloomFilter {
+    // Only removes items that are (possibly) present, removing an item that
+    // was never added could otherwise introduce false negatives for others.
+    pub fn remove<T: Hash + ?Sized>(&mut self, data:&T) -> bool {
+        self.remove_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CountingBloomFilter {
+    pub fn remove_bytes(&mut self, data:&[u8]) -> bool {
+        self.remove_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CountingBloomFilter {
+    fn remove_hash(&mut self, hsh:u128) -> bool {
+        if !self.contains_hash(hsh) {
+            return false;
+        }
+
+        for p in get_positions(hsh, self.num_hashes, self.num_counters) {
+            if self.counters[p] != u8::MAX {
+                self.counters[p] -= 1;
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,191 @@
+use std::cmp::{max, min};
+use std::hash::Hash;
+use super::{hash128, hash128_bytes, read_u32, read_u64, DEFAULT_SEED};
+
+const CMS_MAGIC:&[u8; 3] = b"CMS";
+const CMS_VERSION:u8 = 1;
+const CMS_FLAG_CONSERVATIVE:u8 = 1;
+
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counts: Vec<u64>,
+    total: u64,
+    conservative: bool,
+}
+
+impl CountMinSketch {
+    pub fn new(width:usize, depth:usize, conservative:bool) -> Self {
+        assert!(width > 0 && depth > 0, "width and depth must be positive");
+
+        Self {
+            width,
+            depth,
+            counts: vec![0;width*depth],
+            total: 0,
+            conservative,
+        }
+    }
+}
+
+impl CountMinSketch {
+    // Estimates exceed the true count by at most epsilon*total with probability 1-delta.
+    pub fn from_error(epsilon:f64, delta:f64, conservative:bool) -> Self {
+        assert!(epsilon > 0.0 && delta > 0.0 && delta < 1.0, "epsilon must be positive and delta in (0, 1)");
+
+        let width = (std::f64::consts::E/epsilon).ceil() as usize;
+        let depth = f64::ln(1.0/delta).ceil() as usize;
+        CountMinSketch::new(width, max(depth, 1), conservative)
+    }
+}
+
+impl CountMinSketch {
+    // Row i uses h1 + i*h2 (Kirsch-Mitzenmacher) from a single 128-bit murmur3 hash.
+    fn get_positions(&self, hsh:u128) -> Vec<usize> {
+        let h1 = hsh as u64;
+        let h2 = (hsh >> 64) as u64;
+
+        (0..self.depth)
+            .map(|i| i*self.width + (h1.wrapping_add((i as u64).wrapping_mul(h2)) % self.width as u64) as usize)
+            .collect()
+    }
+}
+
+impl CountMinSketch {
+    pub fn add<T: Hash + ?Sized>(&mut self, data:&T, count:u64) {
+        self.add_hash(hash128(data, DEFAULT_SEED), count);
+    }
+}
+
+impl CountMinSketch {
+    pub fn add_bytes(&mut self, data:&[u8], count:u64) {
+        self.add_hash(hash128_bytes(data, DEFAULT_SEED), count);
+    }
+}
+
+impl CountMinSketch {
+    fn add_hash(&mut self, hsh:u128, count:u64) {
+        let positions = self.get_positions(hsh);
+        self.total = self.total.saturating_add(count);
+
+        if self.conservative {
+            // Only raise the counters that would otherwise fall below the new estimate.
+            let estimate = positions.iter().map(|p| self.counts[*p]).min().unwrap_or(0);
+            let target = estimate.saturating_add(count);
+            for p in positions {
+                self.counts[p] = max(self.counts[p], target);
+            }
+        }
+        else {
+            for p in positions {
+                self.counts[p] = self.counts[p].saturating_add(count);
+            }
+        }
+    }
+}
+
+impl CountMinSketch {
+    pub fn estimate<T: Hash + ?Sized>(&self, data:&T) -> u64 {
+        self.estimate_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CountMinSketch {
+    pub fn estimate_bytes(&self, data:&[u8]) -> u64 {
+        self.estimate_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CountMinSketch {
+    fn estimate_hash(&self, hsh:u128) -> u64 {
+        let mut out = u64::MAX;
+        for p in self.get_positions(hsh) {
+            out = min(out, self.counts[p]);
+        }
+        out
+    }
+}
+
+impl CountMinSketch {
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl CountMinSketch {
+    // Halves every counter, ages old counts so that recent items dominate.
+    pub fn halve(&mut self) {
+        for x in self.counts.iter_mut() {
+            *x >>= 1;
+        }
+        self.total >>= 1;
+    }
+}
+
+impl CountMinSketch {
+    pub fn merge(&mut self, other:&CountMinSketch) -> Result<(), String> {
+        if self.width != other.width || self.depth != other.depth {
+            return Err(format!("Cannot merge {}x{} sketch into {}x{} sketch !!!", other.depth, other.width, self.depth, self.width));
+        }
+
+        for (x, y) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *x = x.saturating_add(*y);
+        }
+        self.total = self.total.saturating_add(other.total);
+
+        Ok(())
+    }
+}
+
+impl CountMinSketch {
+    // Layout: magic "CMS", version, flags, width, depth, total, then the
+    // counters row by row, all integers little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out:Vec<u8> = Vec::with_capacity(CMS_MAGIC.len() + 18 + 8*self.counts.len());
+        out.extend_from_slice(CMS_MAGIC);
+        out.push(CMS_VERSION);
+        out.push(if self.conservative { CMS_FLAG_CONSERVATIVE } else { 0 });
+        out.extend_from_slice(&(self.width as u32).to_le_bytes());
+        out.extend_from_slice(&(self.depth as u32).to_le_bytes());
+        out.extend_from_slice(&self.total.to_le_bytes());
+
+        for c in self.counts.iter() {
+            out.extend_from_slice(&c.to_le_bytes());
+        }
+
+        out
+    }
+}
+
+impl CountMinSketch {
+    pub fn from_bytes(bytes:&[u8]) -> Result<Self, String> {
+        if bytes.len() < CMS_MAGIC.len()+2 || &bytes[..CMS_MAGIC.len()] != CMS_MAGIC {
+            return Err("Not a valid Count-Min Sketch !!!".to_string());
+        }
+
+        let version = bytes[CMS_MAGIC.len()];
+        if version != CMS_VERSION {
+            return Err(format!("Unsupported Count-Min Sketch version {} !!!", version));
+        }
+
+        let flags = bytes[CMS_MAGIC.len()+1];
+        let mut pos = CMS_MAGIC.len()+2;
+
+        let width = read_u32(bytes, &mut pos).ok_or("Truncated Count-Min Sketch !!!")? as usize;
+        let depth = read_u32(bytes, &mut pos).ok_or("Truncated Count-Min Sketch !!!")? as usize;
+        let total = read_u64(bytes, &mut pos).ok_or("Truncated Count-Min Sketch !!!")?;
+
+        if width == 0 || depth == 0 || width.checked_mul(depth).and_then(|x| x.checked_mul(8)) != Some(bytes.len()-pos) {
+            return Err("Count-Min Sketch dimensions do not match payload !!!".to_string());
+        }
+
+        let mut sketch = CountMinSketch::new(width, depth, flags & CMS_FLAG_CONSERVATIVE != 0);
+        sketch.total = total;
+
+        for c in sketch.counts.iter_mut() {
+            *c = read_u64(bytes, &mut pos).unwrap();
+        }
+
+        Ok(sketch)
+    }
+}
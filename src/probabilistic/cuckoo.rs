@@ -0,0 +1,196 @@
+use std::hash::Hash;
+use rand::Rng;
+use super::{hash128, hash128_bytes, DEFAULT_SEED};
+
+const BUCKET_SIZE:usize = 4;
+const MAX_KICKS:usize = 500;
+
+// Cuckoo filter (Fan et al.) with 16 bit fingerprints and 4 slots per bucket,
+// an empty slot holds fingerprint 0.
+pub struct CuckooFilter {
+    num_buckets: usize,
+    buckets: Vec<[u16; BUCKET_SIZE]>,
+    num_items: usize,
+    victim: Option<(usize, u16)>,
+}
+
+impl CuckooFilter {
+    pub fn new(capacity:usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        // Buckets of 4 can be filled to ~95% before inserts start failing.
+        let num_buckets = ((capacity as f64/(BUCKET_SIZE as f64*0.95)).ceil() as usize).next_power_of_two();
+
+        Self {
+            num_buckets,
+            buckets: vec![[0; BUCKET_SIZE]; num_buckets],
+            num_items: 0,
+            victim: None,
+        }
+    }
+}
+
+impl CuckooFilter {
+    fn get_index_and_fingerprint(&self, hsh:u128) -> (usize, u16) {
+        let i = (hsh as u64 as usize) & (self.num_buckets-1);
+        let fp = ((hsh >> 64) as u16).max(1);
+        (i, fp)
+    }
+}
+
+impl CuckooFilter {
+    // Partial-key cuckoo hashing, the alternate bucket only depends on the
+    // current bucket and the fingerprint so it can be computed while relocating.
+    fn get_alt_index(&self, i:usize, fp:u16) -> usize {
+        let h = hash128_bytes(&fp.to_le_bytes(), DEFAULT_SEED) as u64 as usize;
+        (i ^ h) & (self.num_buckets-1)
+    }
+}
+
+impl CuckooFilter {
+    fn insert_into_bucket(&mut self, i:usize, fp:u16) -> bool {
+        for slot in self.buckets[i].iter_mut() {
+            if *slot == 0 {
+                *slot = fp;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl CuckooFilter {
+    pub fn add<T: Hash + ?Sized>(&mut self, data:&T) -> Result<(), String> {
+        self.add_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    pub fn add_bytes(&mut self, data:&[u8]) -> Result<(), String> {
+        self.add_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    fn add_hash(&mut self, hsh:u128) -> Result<(), String> {
+        if self.victim.is_some() {
+            return Err("Cuckoo filter is full !!!".to_string());
+        }
+
+        let (i1, mut fp) = self.get_index_and_fingerprint(hsh);
+        let i2 = self.get_alt_index(i1, fp);
+
+        if self.insert_into_bucket(i1, fp) || self.insert_into_bucket(i2, fp) {
+            self.num_items += 1;
+            return Ok(());
+        }
+
+        let mut i = if rand::thread_rng().gen_bool(0.5) { i1 } else { i2 };
+
+        for _ in 0..MAX_KICKS {
+            let slot = rand::thread_rng().gen_range(0..BUCKET_SIZE);
+            std::mem::swap(&mut fp, &mut self.buckets[i][slot]);
+
+            i = self.get_alt_index(i, fp);
+            if self.insert_into_bucket(i, fp) {
+                self.num_items += 1;
+                return Ok(());
+            }
+        }
+
+        // The item itself is stored, but the last displaced fingerprint has
+        // nowhere to go; keep it aside so that lookups stay correct.
+        self.victim = Some((i, fp));
+        self.num_items += 1;
+        Ok(())
+    }
+}
+
+impl CuckooFilter {
+    pub fn contains<T: Hash + ?Sized>(&self, data:&T) -> bool {
+        self.contains_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    pub fn contains_bytes(&self, data:&[u8]) -> bool {
+        self.contains_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    fn contains_hash(&self, hsh:u128) -> bool {
+        let (i1, fp) = self.get_index_and_fingerprint(hsh);
+        let i2 = self.get_alt_index(i1, fp);
+
+        if let Some((i, v)) = self.victim {
+            if v == fp && (i == i1 || i == i2) {
+                return true;
+            }
+        }
+
+        self.buckets[i1].contains(&fp) || self.buckets[i2].contains(&fp)
+    }
+}
+
+impl CuckooFilter {
+    pub fn remove<T: Hash + ?Sized>(&mut self, data:&T) -> bool {
+        self.remove_hash(hash128(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    pub fn remove_bytes(&mut self, data:&[u8]) -> bool {
+        self.remove_hash(hash128_bytes(data, DEFAULT_SEED))
+    }
+}
+
+impl CuckooFilter {
+    fn remove_hash(&mut self, hsh:u128) -> bool {
+        let (i1, fp) = self.get_index_and_fingerprint(hsh);
+        let i2 = self.get_alt_index(i1, fp);
+
+        if let Some((i, v)) = self.victim {
+            if v == fp && (i == i1 || i == i2) {
+                self.victim = None;
+                self.num_items -= 1;
+                return true;
+            }
+        }
+
+        for i in [i1, i2] {
+            if let Some(slot) = self.buckets[i].iter_mut().find(|x| **x == fp) {
+                *slot = 0;
+                self.num_items -= 1;
+
+                // Freed a slot, the victim may fit again.
+                if let Some((j, v)) = self.victim.take() {
+                    if !self.insert_into_bucket(j, v) && !self.insert_into_bucket(self.get_alt_index(j, v), v) {
+                        self.victim = Some((j, v));
+                    }
+                }
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl CuckooFilter {
+    pub fn len(&self) -> usize {
+        self.num_items
+    }
+}
+
+impl CuckooFilter {
+    pub fn is_empty(&self) -> bool {
+        self.num_items == 0
+    }
+}
+
+impl CuckooFilter {
+    pub fn load_factor(&self) -> f64 {
+        self.num_items as f64/(self.num_buckets*BUCKET_SIZE) as f64
+    }
+}
@@ -0,0 +1,697 @@
+use std::cmp::max;
+use std::hash::Hash;
+use super::{hash128, hash128_bytes, read_varint, write_varint, DEFAULT_SEED};
+
+const HLL_MAGIC:&[u8; 3] = b"HLL";
+const HLL_VERSION:u8 = 2;
+const HLL_FLAG_PLUS:u8 = 1;
+const HLL_FLAG_SPARSE:u8 = 2;
+
+// Precision used by the sparse representation of HLL++, each sparse entry
+// stores a 25 bit index followed by a 6 bit rank.
+const HLL_SPARSE_P:u8 = 25;
+const HLL_SPARSE_RANK_BITS:u8 = 6;
+
+// Cardinalities below which linear counting beats the bias corrected estimate,
+// indexed by precision-4 (from Heule et al., "HyperLogLog in Practice").
+const HLL_THRESHOLDS:[f64; 15] = [
+    10.0, 20.0, 40.0, 80.0, 220.0, 400.0, 900.0, 1800.0,
+    3100.0, 6500.0, 11500.0, 20000.0, 50000.0, 120000.0, 350000.0,
+];
+
+fn get_alpha(m:u32) -> f64 {
+    match m {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213/(1.0 + 1.079/m as f64),
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct HLL {
+    num_bits: u8,
+    p_bits: u8,
+    m: u32,
+    buckets: Vec<u8>,
+    alpha: f64,
+    plus: bool,
+    sparse: Option<Vec<u32>>,
+    sparse_buffer: Vec<u32>,
+}
+
+impl HLL {
+    pub fn new(p:u8) -> Self {
+        assert!((4..=18).contains(&p), "precision must be between 4 and 18");
+        let bsize:u32 = 1<<p;
+
+        Self {
+            num_bits: 64,
+            p_bits: p,
+            m: bsize,
+            buckets: vec![0;bsize as usize],
+            alpha: get_alpha(bsize),
+            plus: false,
+            sparse: None,
+            sparse_buffer: Vec::new(),
+        }
+    }
+}
+
+impl HLL {
+    // HLL++ starts out with the sparse representation and switches to the
+    // dense registers once that would take less memory.
+    pub fn new_plus(p:u8) -> Self {
+        let mut hll = HLL::new(p);
+        hll.buckets = Vec::new();
+        hll.plus = true;
+        hll.sparse = Some(Vec::new());
+        hll
+    }
+}
+
+impl HLL {
+    // Hashes any `Hash` value with the 64-bit murmur3 variant, so that strings,
+    // integers and tuples can all be counted without converting to `String` first.
+    pub fn add<T: Hash + ?Sized>(&mut self, data:&T) {
+        self.add_hash(hash128(data, DEFAULT_SEED) as u64);
+    }
+}
+
+impl HLL {
+    // Hashes the raw bytes only (no length prefix as with `Hash`), which keeps
+    // the register values stable for data coming from outside of Rust.
+    pub fn add_bytes(&mut self, data:&[u8]) {
+        self.add_hash(hash128_bytes(data, DEFAULT_SEED) as u64);
+    }
+}
+
+impl HLL {
+    fn add_hash(&mut self, hsh:u64) {
+        if self.sparse.is_some() {
+            self.sparse_buffer.push(HLL::encode_sparse(hsh));
+            if self.sparse_buffer.len() >= self.sparse_limit() {
+                self.flush_sparse_buffer();
+            }
+            return;
+        }
+
+        let v = self.num_bits-self.p_bits;
+        let q = hsh & ((1<<v)-1);
+        let r = hsh>>v;
+        let j = if q == 0 { v } else { q.trailing_zeros() as u8 };
+        self.buckets[r as usize] = max(self.buckets[r as usize], j+1);
+    }
+}
+
+impl HLL {
+    fn encode_sparse(hsh:u64) -> u32 {
+        let v = 64-HLL_SPARSE_P;
+        let q = hsh & ((1<<v)-1);
+        let r = (hsh>>v) as u32;
+        let j = if q == 0 { v } else { q.trailing_zeros() as u8 };
+        (r << HLL_SPARSE_RANK_BITS) | (j+1) as u32
+    }
+}
+
+impl HLL {
+    // Maps a sparse entry onto the dense register it falls into and the rank
+    // that the original hash would have produced at the dense precision.
+    fn decode_sparse(&self, entry:u32) -> (usize, u8) {
+        let extra_bits = HLL_SPARSE_P-self.p_bits;
+        let r = entry >> HLL_SPARSE_RANK_BITS;
+        let rank = (entry & ((1<<HLL_SPARSE_RANK_BITS)-1)) as u8;
+        let low = r & ((1<<extra_bits)-1);
+
+        let dense_rank = if rank <= 64-HLL_SPARSE_P {
+            rank
+        }
+        else if low == 0 {
+            self.num_bits-self.p_bits+1
+        }
+        else {
+            64-HLL_SPARSE_P+low.trailing_zeros() as u8+1
+        };
+
+        ((r >> extra_bits) as usize, dense_rank)
+    }
+}
+
+impl HLL {
+    pub fn is_sparse(&self) -> bool {
+        self.sparse.is_some()
+    }
+}
+
+impl HLL {
+    fn sparse_limit(&self) -> usize {
+        // A sparse entry takes 4 bytes against 1 byte per dense register.
+        max(self.m as usize/4, 16)
+    }
+}
+
+impl HLL {
+    // Returns the sparse entries sorted by index, keeping only the highest
+    // rank for every index.
+    fn sorted_sparse(&self) -> Vec<u32> {
+        let mut entries:Vec<u32> = Vec::new();
+
+        if let Some(sparse) = &self.sparse {
+            entries.extend_from_slice(sparse);
+            entries.extend_from_slice(&self.sparse_buffer);
+        }
+
+        entries.sort_unstable();
+
+        let mut out:Vec<u32> = Vec::with_capacity(entries.len());
+        for e in entries {
+            match out.last_mut() {
+                Some(last) if *last >> HLL_SPARSE_RANK_BITS == e >> HLL_SPARSE_RANK_BITS => {
+                    *last = e;
+                }
+                _ => {
+                    out.push(e);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl HLL {
+    fn flush_sparse_buffer(&mut self) {
+        let entries = self.sorted_sparse();
+        let num_entries = entries.len();
+        self.sparse_buffer.clear();
+        self.sparse = Some(entries);
+
+        if num_entries > self.sparse_limit() {
+            self.convert_to_dense();
+        }
+    }
+}
+
+impl HLL {
+    fn convert_to_dense(&mut self) {
+        if self.sparse.is_some() {
+            let entries = self.sorted_sparse();
+            self.sparse = None;
+            self.sparse_buffer.clear();
+            self.buckets = vec![0;self.m as usize];
+            for e in entries {
+                self.merge_sparse_entry(e);
+            }
+        }
+    }
+}
+
+impl HLL {
+    fn merge_sparse_entry(&mut self, entry:u32) {
+        let (r, rank) = self.decode_sparse(entry);
+        self.buckets[r] = max(self.buckets[r], rank);
+    }
+}
+
+impl HLL {
+    pub fn merge(&mut self, other:&HLL) -> Result<(), String> {
+        if self.p_bits != other.p_bits {
+            return Err(format!("Cannot merge HLL with precision {} into HLL with precision {} !!!", other.p_bits, self.p_bits));
+        }
+
+        if other.sparse.is_some() {
+            let entries = other.sorted_sparse();
+            if self.sparse.is_some() {
+                self.sparse_buffer.extend(entries);
+                self.flush_sparse_buffer();
+            }
+            else {
+                for e in entries {
+                    self.merge_sparse_entry(e);
+                }
+            }
+            return Ok(());
+        }
+
+        self.convert_to_dense();
+        for (x, y) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *x = max(*x, *y);
+        }
+
+        Ok(())
+    }
+}
+
+impl HLL {
+    // Layout: magic "HLL", version, precision, flags, followed by either one
+    // byte per register or, when sparse, the entry count and the varint encoded
+    // deltas between consecutive sorted entries.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out:Vec<u8> = Vec::with_capacity(HLL_MAGIC.len() + 3 + self.buckets.len());
+        out.extend_from_slice(HLL_MAGIC);
+        out.push(HLL_VERSION);
+        out.push(self.p_bits);
+
+        let mut flags:u8 = 0;
+        if self.plus {
+            flags |= HLL_FLAG_PLUS;
+        }
+
+        if self.sparse.is_some() {
+            flags |= HLL_FLAG_SPARSE;
+            out.push(flags);
+
+            let entries = self.sorted_sparse();
+            out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+
+            let mut prev:u32 = 0;
+            for e in entries {
+                write_varint(&mut out, e-prev);
+                prev = e;
+            }
+        }
+        else {
+            out.push(flags);
+            out.extend_from_slice(&self.buckets);
+        }
+
+        out
+    }
+}
+
+impl HLL {
+    pub fn from_bytes(bytes:&[u8]) -> Result<Self, String> {
+        let header_len = HLL_MAGIC.len() + 2;
+
+        if bytes.len() < header_len || &bytes[..HLL_MAGIC.len()] != HLL_MAGIC {
+            return Err("Not a valid HLL !!!".to_string());
+        }
+
+        let version = bytes[HLL_MAGIC.len()];
+        if version == 0 || version > HLL_VERSION {
+            return Err(format!("Unsupported HLL version {} !!!", version));
+        }
+
+        let p = bytes[HLL_MAGIC.len()+1];
+        if !(4..=18).contains(&p) {
+            return Err(format!("Invalid HLL precision {} !!!", p));
+        }
+
+        // Version 1 had no flags byte and was always dense.
+        let mut flags:u8 = 0;
+        let mut header_len = header_len;
+        if version >= 2 {
+            flags = *bytes.get(header_len).ok_or("Not a valid HLL !!!")?;
+            header_len += 1;
+        }
+
+        let mut hll = if flags & HLL_FLAG_PLUS != 0 { HLL::new_plus(p) } else { HLL::new(p) };
+
+        if flags & HLL_FLAG_SPARSE != 0 {
+            if hll.sparse.is_none() {
+                return Err("Sparse representation requires HLL++ !!!".to_string());
+            }
+
+            let count_bytes = bytes.get(header_len..header_len+4).ok_or("Not a valid HLL !!!")?;
+            let count = u32::from_le_bytes(count_bytes.try_into().unwrap()) as usize;
+            let mut pos = header_len+4;
+
+            // Every entry takes at least one byte, so a larger count is corrupt
+            // and must not be trusted for the allocation.
+            if count > bytes.len()-pos {
+                return Err("Truncated sparse HLL !!!".to_string());
+            }
+
+            let mut prev:u32 = 0;
+            let mut entries:Vec<u32> = Vec::with_capacity(count);
+
+            for _ in 0..count {
+                let delta = read_varint(bytes, &mut pos).ok_or("Truncated sparse HLL !!!")?;
+                prev = prev.checked_add(delta).ok_or("Sparse HLL entry out of range !!!")?;
+                let rank = prev & ((1 << HLL_SPARSE_RANK_BITS)-1);
+                if prev >> HLL_SPARSE_RANK_BITS >= 1 << HLL_SPARSE_P || !(1..=(64-HLL_SPARSE_P+1) as u32).contains(&rank) {
+                    return Err("Sparse HLL entry out of range !!!".to_string());
+                }
+                entries.push(prev);
+            }
+
+            if pos != bytes.len() {
+                return Err("Trailing bytes after sparse HLL !!!".to_string());
+            }
+
+            hll.sparse = Some(entries);
+            return Ok(hll);
+        }
+
+        if hll.plus {
+            hll.convert_to_dense();
+        }
+        let registers = &bytes[header_len..];
+
+        if registers.len() != hll.m as usize {
+            return Err(format!("Expected {} registers, found {} !!!", hll.m, registers.len()));
+        }
+
+        let max_rank = hll.num_bits-hll.p_bits+1;
+        if registers.iter().any(|x| *x > max_rank) {
+            return Err("HLL register out of range !!!".to_string());
+        }
+
+        hll.buckets.copy_from_slice(registers);
+        Ok(hll)
+    }
+}
+
+impl HLL {
+    fn get_bias(&self, estimate:f64) -> f64 {
+        let row = (self.p_bits-4) as usize;
+        let raw = &HLL_RAW_ESTIMATES[row];
+        let bias = &HLL_BIAS[row];
+
+        if estimate <= raw[0] {
+            return bias[0];
+        }
+
+        for i in 1..raw.len() {
+            if estimate <= raw[i] {
+                let t = (estimate-raw[i-1])/(raw[i]-raw[i-1]);
+                return bias[i-1] + t*(bias[i]-bias[i-1]);
+            }
+        }
+
+        bias[bias.len()-1]
+    }
+}
+
+impl HLL {
+    pub fn get_size(&self) -> f64 {
+        if self.sparse.is_some() {
+            // Linear counting over the 2^25 virtual registers of the sparse representation.
+            let mp = (1u64 << HLL_SPARSE_P) as f64;
+            let z = mp-self.sorted_sparse().len() as f64;
+            return mp*f64::ln(mp/z);
+        }
+
+        let m = self.m as f64;
+        let mut sum:f64 = 0.0;
+        let mut z:u32 = 0;
+
+        for x in self.buckets.iter() {
+            sum += 2.0f64.powi(-(*x as i32));
+            if *x == 0 {
+                z += 1;
+            }
+        }
+
+        let n:f64 = self.alpha*m*m/sum;
+
+        if self.plus {
+            let corrected = if n <= 5.0*m { n-self.get_bias(n) } else { n };
+
+            if z != 0 {
+                let h = m*f64::ln(m/z as f64);
+                if h <= HLL_THRESHOLDS[(self.p_bits-4) as usize] {
+                    return h;
+                }
+            }
+
+            return corrected;
+        }
+
+        // With 64-bit hashes collisions are negligible for any realistic
+        // cardinality, so only the small-range (linear counting) correction remains.
+        if n <= 2.5*m && z != 0 {
+            return m*f64::ln(m/z as f64);
+        }
+
+        n
+    }
+}
+
+impl HLL {
+    // Relative standard error of `get_size`.
+    pub fn standard_error(&self) -> f64 {
+        if self.sparse.is_some() {
+            return 1.04/((1u64 << HLL_SPARSE_P) as f64).sqrt();
+        }
+        1.04/(self.m as f64).sqrt()
+    }
+}
+
+impl HLL {
+    pub fn get_bounds(&self, num_std:f64) -> (f64, f64) {
+        let n = self.get_size();
+        let d = n*num_std*self.standard_error();
+        (f64::max(n-d, 0.0), n+d)
+    }
+}
+
+// Mean raw HLL estimate and its bias at 41 evenly spaced cardinalities in
+// [0, 5m], indexed by precision-4. Generated by averaging simulated streams of
+// uniformly random 64-bit hashes.
+const HLL_RAW_ESTIMATES:[[f64; 41]; 15] = [
+    [
+        10.8, 11.7, 12.7, 13.8, 15.0, 16.2, 17.4, 18.7,
+        20.1, 21.6, 23.1, 24.6, 26.2, 27.8, 29.5, 31.3,
+        33.1, 34.9, 36.6, 38.4, 40.3, 42.2, 44.1, 46.0,
+        48.0, 50.0, 51.9, 53.8, 55.6, 57.6, 59.6, 61.5,
+        63.5, 65.7, 67.7, 69.7, 71.8, 73.9, 75.7, 77.6,
+        79.7,
+    ],
+    [
+        22.3, 24.3, 26.3, 28.5, 30.8, 33.2, 35.8, 38.4,
+        41.2, 44.0, 47.0, 50.2, 53.4, 56.7, 60.1, 63.5,
+        66.9, 70.5, 74.2, 77.8, 81.5, 85.3, 89.0, 92.7,
+        96.5, 100.5, 104.5, 108.3, 112.2, 116.2, 120.1, 123.7,
+        127.6, 131.6, 135.4, 139.4, 143.4, 147.5, 151.7, 156.0,
+        159.9,
+    ],
+    [
+        45.4, 49.3, 53.4, 57.9, 62.5, 67.4, 72.5, 77.8,
+        83.4, 89.2, 95.2, 101.4, 107.7, 114.2, 120.8, 127.7,
+        134.5, 141.5, 148.7, 156.2, 163.7, 171.1, 178.6, 186.3,
+        194.0, 201.7, 209.5, 217.3, 225.0, 232.9, 240.8, 249.0,
+        257.3, 265.5, 273.3, 281.1, 288.6, 296.6, 304.4, 312.7,
+        321.0,
+    ],
+    [
+        91.6, 99.5, 107.8, 116.6, 125.9, 135.7, 145.9, 156.6,
+        167.6, 179.2, 191.2, 203.4, 216.0, 229.0, 242.2, 255.7,
+        269.5, 283.7, 297.9, 312.4, 327.1, 342.0, 357.0, 372.2,
+        387.5, 402.9, 418.4, 433.8, 449.1, 464.6, 480.2, 496.5,
+        512.3, 528.4, 544.0, 559.6, 575.8, 592.0, 607.7, 623.7,
+        639.2,
+    ],
+    [
+        183.9, 199.7, 216.4, 234.1, 252.6, 272.1, 292.6, 313.9,
+        336.1, 359.1, 382.9, 407.4, 432.8, 458.9, 485.6, 512.5,
+        539.8, 568.1, 596.6, 625.5, 655.2, 684.6, 714.9, 744.8,
+        775.2, 805.8, 836.4, 867.2, 899.2, 931.5, 962.8, 994.4,
+        1026.2, 1058.3, 1089.8, 1121.8, 1153.5, 1185.4, 1218.1, 1249.6,
+        1281.6,
+    ],
+    [
+        368.5, 400.2, 433.7, 469.0, 506.2, 545.3, 586.2, 628.7,
+        672.9, 718.7, 765.9, 814.9, 865.3, 917.0, 970.0, 1024.2,
+        1079.9, 1136.1, 1193.0, 1251.0, 1310.0, 1369.7, 1429.6, 1490.1,
+        1550.5, 1611.6, 1673.4, 1735.8, 1798.5, 1861.7, 1923.9, 1987.2,
+        2051.1, 2114.3, 2177.6, 2241.2, 2304.1, 2368.4, 2432.3, 2496.4,
+        2560.2,
+    ],
+    [
+        737.8, 801.2, 868.2, 938.9, 1013.2, 1091.4, 1173.1, 1258.5,
+        1347.2, 1439.4, 1534.8, 1632.8, 1733.8, 1837.3, 1943.5, 2052.1,
+        2162.6, 2274.4, 2388.4, 2504.1, 2621.4, 2739.8, 2859.5, 2980.8,
+        3103.0, 3225.3, 3348.9, 3474.6, 3599.4, 3724.1, 3849.6, 3976.5,
+        4102.5, 4229.9, 4356.5, 4485.7, 4613.7, 4742.2, 4870.3, 4998.5,
+        5126.2,
+    ],
+    [
+        1476.4, 1603.2, 1737.2, 1878.8, 2027.8, 2184.1, 2347.1, 2516.5,
+        2693.3, 2877.4, 3066.5, 3261.8, 3462.4, 3667.8, 3878.8, 4095.6,
+        4316.4, 4540.4, 4768.4, 4999.4, 5235.8, 5472.6, 5712.6, 5953.9,
+        6196.3, 6440.9, 6687.1, 6936.1, 7182.6, 7437.8, 7686.2, 7938.3,
+        8191.4, 8438.1, 8690.6, 8941.2, 9194.6, 9447.1, 9705.7, 9962.8,
+        10220.3,
+    ],
+    [
+        2953.7, 3207.3, 3475.7, 3758.6, 4056.8, 4369.1, 4695.7, 5036.8,
+        5391.4, 5758.2, 6137.2, 6530.4, 6935.5, 7349.9, 7775.0, 8205.5,
+        8648.2, 9101.1, 9556.5, 10021.0, 10492.9, 10969.4, 11453.9, 11935.8,
+        12423.8, 12917.5, 13414.0, 13908.3, 14403.3, 14907.5, 15411.0, 15910.6,
+        16420.5, 16925.1, 17429.2, 17938.1, 18444.8, 18952.8, 19459.1, 19978.4,
+        20493.8,
+    ],
+    [
+        5908.1, 6415.9, 6952.0, 7518.1, 8114.1, 8737.9, 9392.1, 10074.8,
+        10784.4, 11516.8, 12274.0, 13056.3, 13862.9, 14690.9, 15539.9, 16405.8,
+        17291.4, 18191.9, 19108.4, 20036.5, 20979.4, 21924.1, 22886.4, 23853.1,
+        24833.0, 25819.0, 26806.4, 27799.9, 28805.1, 29807.2, 30818.5, 31828.4,
+        32835.4, 33850.0, 34863.4, 35880.8, 36904.1, 37927.5, 38947.3, 39972.9,
+        40995.2,
+    ],
+    [
+        11817.0, 12831.0, 13904.3, 15036.4, 16227.9, 17475.6, 18781.7, 20144.1,
+        21562.1, 23034.8, 24552.5, 26117.7, 27729.1, 29383.7, 31086.0, 32818.7,
+        34585.0, 36386.3, 38221.1, 40070.2, 41941.0, 43842.5, 45750.6, 47689.7,
+        49646.1, 51602.7, 53582.6, 55578.1, 57582.8, 59599.5, 61616.9, 63631.0,
+        65645.5, 67671.4, 69705.4, 71735.2, 73772.4, 75815.3, 77851.3, 79892.9,
+        81943.9,
+    ],
+    [
+        23634.8, 25663.6, 27807.6, 30071.1, 32448.0, 34939.0, 37551.6, 40283.4,
+        43112.9, 46041.2, 49081.0, 52221.3, 55444.4, 58761.3, 62153.6, 65619.9,
+        69156.2, 72766.0, 76431.6, 80134.6, 83905.8, 87719.6, 91542.3, 95439.7,
+        99306.4, 103249.4, 107226.5, 111233.5, 115207.0, 119246.4, 123266.2, 127274.3,
+        131321.4, 135349.7, 139393.1, 143450.9, 147539.4, 151656.5, 155727.5, 159764.1,
+        163854.1,
+    ],
+    [
+        47270.3, 51324.4, 55616.8, 60151.0, 64921.8, 69932.8, 75156.6, 80603.5,
+        86272.3, 92158.5, 98245.4, 104519.2, 110979.1, 117610.7, 124375.1, 131299.5,
+        138375.7, 145547.0, 152897.8, 160343.8, 167858.1, 175488.0, 183182.0, 190940.8,
+        198815.1, 206687.6, 214613.3, 222548.0, 230542.9, 238618.8, 246662.6, 254766.1,
+        262855.3, 270988.4, 279063.1, 287177.3, 295332.8, 303465.7, 311723.9, 319925.4,
+        328091.8,
+    ],
+    [
+        94541.5, 102655.3, 111243.0, 120308.3, 129842.0, 139828.7, 150280.7, 161198.7,
+        172534.1, 184308.1, 196437.5, 208936.4, 221832.1, 235052.6, 248601.9, 262446.0,
+        276597.7, 290981.5, 305578.3, 320430.8, 335467.7, 350688.1, 366075.1, 381583.8,
+        397267.8, 413008.4, 428883.0, 444888.5, 460842.7, 476872.3, 492985.6, 509090.9,
+        525217.7, 541451.2, 557704.5, 573953.9, 590202.6, 606450.9, 622680.2, 638932.5,
+        655311.2,
+    ],
+    [
+        189083.7, 205313.5, 222483.2, 240589.4, 259667.7, 279649.5, 300567.1, 322381.8,
+        345057.4, 368578.8, 392900.3, 417969.7, 443822.9, 470349.0, 497479.8, 525272.2,
+        553555.1, 582367.3, 611613.9, 641342.3, 671421.3, 701817.9, 732595.8, 763591.1,
+        794939.0, 826332.0, 857971.3, 889735.4, 921837.8, 953928.8, 986131.2, 1018433.9,
+        1050950.7, 1083499.0, 1115946.2, 1148584.8, 1181194.1, 1213777.9, 1246382.4, 1279096.5,
+        1311710.5,
+    ],
+];
+
+const HLL_BIAS:[[f64; 41]; 15] = [
+    [
+        10.8, 9.7, 8.7, 7.8, 7.0, 6.2, 5.4, 4.7,
+        4.1, 3.6, 3.1, 2.6, 2.2, 1.8, 1.5, 1.3,
+        1.1, 0.9, 0.6, 0.4, 0.3, 0.2, 0.1, -0.0,
+        -0.0, 0.0, -0.1, -0.2, -0.4, -0.4, -0.4, -0.5,
+        -0.5, -0.3, -0.3, -0.3, -0.2, -0.1, -0.3, -0.4,
+        -0.3,
+    ],
+    [
+        22.3, 20.3, 18.3, 16.5, 14.8, 13.2, 11.8, 10.4,
+        9.2, 8.0, 7.0, 6.2, 5.4, 4.7, 4.1, 3.5,
+        2.9, 2.5, 2.2, 1.8, 1.5, 1.3, 1.0, 0.7,
+        0.5, 0.5, 0.5, 0.3, 0.2, 0.2, 0.1, -0.3,
+        -0.4, -0.4, -0.6, -0.6, -0.6, -0.5, -0.3, -0.0,
+        -0.1,
+    ],
+    [
+        45.4, 41.3, 37.4, 33.9, 30.5, 27.4, 24.5, 21.8,
+        19.4, 17.2, 15.2, 13.4, 11.7, 10.2, 8.8, 7.7,
+        6.5, 5.5, 4.7, 4.2, 3.7, 3.1, 2.6, 2.3,
+        2.0, 1.7, 1.5, 1.3, 1.0, 0.9, 0.8, 1.0,
+        1.3, 1.5, 1.3, 1.1, 0.6, 0.6, 0.4, 0.7,
+        1.0,
+    ],
+    [
+        91.6, 83.5, 75.8, 68.6, 61.9, 55.7, 49.9, 44.6,
+        39.6, 35.2, 31.2, 27.4, 24.0, 21.0, 18.2, 15.7,
+        13.5, 11.7, 9.9, 8.4, 7.1, 6.0, 5.0, 4.2,
+        3.5, 2.9, 2.4, 1.8, 1.1, 0.6, 0.2, 0.5,
+        0.3, 0.4, -0.0, -0.4, -0.2, 0.0, -0.3, -0.3,
+        -0.8,
+    ],
+    [
+        183.9, 167.7, 152.4, 138.1, 124.6, 112.1, 100.6, 89.9,
+        80.1, 71.1, 62.9, 55.4, 48.8, 42.9, 37.6, 32.5,
+        27.8, 24.1, 20.6, 17.5, 15.2, 12.6, 10.9, 8.8,
+        7.2, 5.8, 4.4, 3.2, 3.2, 3.5, 2.8, 2.4,
+        2.2, 2.3, 1.8, 1.8, 1.5, 1.4, 2.1, 1.6,
+        1.6,
+    ],
+    [
+        368.5, 336.2, 305.7, 277.0, 250.2, 225.3, 202.2, 180.7,
+        160.9, 142.7, 125.9, 110.9, 97.3, 85.0, 74.0, 64.2,
+        55.9, 48.1, 41.0, 35.0, 30.0, 25.7, 21.6, 18.1,
+        14.5, 11.6, 9.4, 7.8, 6.5, 5.7, 3.9, 3.2,
+        3.1, 2.3, 1.6, 1.2, 0.1, 0.4, 0.3, 0.4,
+        0.2,
+    ],
+    [
+        737.8, 673.2, 612.2, 554.9, 501.2, 451.4, 405.1, 362.5,
+        323.2, 287.4, 254.8, 224.8, 197.8, 173.3, 151.5, 132.1,
+        114.6, 98.4, 84.4, 72.1, 61.4, 51.8, 43.5, 36.8,
+        31.0, 25.3, 20.9, 18.6, 15.4, 12.1, 9.6, 8.5,
+        6.5, 5.9, 4.5, 5.7, 5.7, 6.2, 6.3, 6.5,
+        6.2,
+    ],
+    [
+        1476.4, 1347.2, 1225.2, 1110.8, 1003.8, 904.1, 811.1, 724.5,
+        645.3, 573.4, 506.5, 445.8, 390.4, 339.8, 294.8, 255.6,
+        220.4, 188.4, 160.4, 135.4, 115.8, 96.6, 80.6, 65.9,
+        52.3, 40.9, 31.1, 24.1, 14.6, 13.8, 6.2, 2.3,
+        -0.6, -9.9, -13.4, -18.8, -21.4, -24.9, -22.3, -21.2,
+        -19.7,
+    ],
+    [
+        2953.7, 2695.3, 2451.7, 2222.6, 2008.8, 1809.1, 1623.7, 1452.8,
+        1295.4, 1150.2, 1017.2, 898.4, 791.5, 693.9, 607.0, 525.5,
+        456.2, 397.1, 340.5, 293.0, 252.9, 217.4, 189.9, 159.8,
+        135.8, 117.5, 102.0, 84.3, 67.3, 59.5, 51.0, 38.6,
+        36.5, 29.1, 21.2, 18.1, 12.8, 8.8, 3.1, 10.4,
+        13.8,
+    ],
+    [
+        5908.1, 5391.9, 4904.0, 4446.1, 4018.1, 3617.9, 3248.1, 2906.8,
+        2592.4, 2300.8, 2034.0, 1792.3, 1574.9, 1378.9, 1203.9, 1045.8,
+        907.4, 783.9, 676.4, 580.5, 499.4, 420.1, 358.4, 301.1,
+        257.0, 219.0, 182.4, 151.9, 133.1, 111.2, 98.5, 84.4,
+        67.4, 58.0, 47.4, 40.8, 40.1, 39.5, 35.3, 36.9,
+        35.2,
+    ],
+    [
+        11817.0, 10783.0, 9808.3, 8892.4, 8035.9, 7235.6, 6493.7, 5808.1,
+        5178.1, 4602.8, 4072.5, 3589.7, 3153.1, 2759.7, 2414.0, 2098.7,
+        1817.0, 1570.3, 1357.1, 1158.2, 981.0, 834.5, 694.6, 585.7,
+        494.1, 402.7, 334.6, 282.1, 238.8, 207.5, 176.9, 143.0,
+        109.5, 87.4, 73.4, 55.2, 44.4, 39.3, 27.3, 20.9,
+        23.9,
+    ],
+    [
+        23634.8, 21567.6, 19615.6, 17783.1, 16064.0, 14459.0, 12975.6, 11611.4,
+        10344.9, 9177.2, 8121.0, 7165.3, 6292.4, 5513.3, 4809.6, 4179.9,
+        3620.2, 3134.0, 2703.6, 2310.6, 1985.8, 1703.6, 1430.3, 1231.7,
+        1002.4, 849.4, 730.5, 641.5, 519.0, 462.4, 386.2, 298.3,
+        249.4, 181.7, 129.1, 90.9, 83.4, 104.5, 79.5, 20.1,
+        14.1,
+    ],
+    [
+        47270.3, 43132.4, 39232.8, 35575.0, 32153.8, 28972.8, 26004.6, 23259.5,
+        20736.3, 18430.5, 16325.4, 14407.2, 12675.1, 11114.7, 9687.1, 8419.5,
+        7303.7, 6283.0, 5441.8, 4695.8, 4018.1, 3456.0, 2958.0, 2524.8,
+        2207.1, 1887.6, 1621.3, 1364.0, 1166.9, 1050.8, 902.6, 814.1,
+        711.3, 652.4, 535.1, 457.3, 420.8, 361.7, 427.9, 437.4,
+        411.8,
+    ],
+    [
+        94541.5, 86271.3, 78475.0, 71156.3, 64306.0, 57908.7, 51976.7, 46510.7,
+        41462.1, 36852.1, 32597.5, 28712.4, 25224.1, 22060.6, 19225.9, 16686.0,
+        14453.7, 12453.5, 10666.3, 9134.8, 7787.7, 6624.1, 5627.1, 4751.8,
+        4051.8, 3408.4, 2899.0, 2520.5, 2090.7, 1736.3, 1465.6, 1186.9,
+        929.7, 779.2, 648.5, 513.9, 378.6, 242.9, 88.2, -43.5,
+        -48.8,
+    ],
+    [
+        189083.7, 172545.5, 156947.2, 142285.4, 128595.7, 115809.5, 103959.1, 93005.8,
+        82913.4, 73666.8, 65220.3, 57521.7, 50606.9, 44365.0, 38727.8, 33752.2,
+        29267.1, 25311.3, 21789.9, 18750.3, 16061.3, 13689.9, 11699.8, 9927.1,
+        8507.0, 7132.0, 6003.3, 4999.4, 4333.8, 3656.8, 3091.2, 2625.9,
+        2374.7, 2155.0, 1834.2, 1704.8, 1546.1, 1361.9, 1198.4, 1144.5,
+        990.5,
+    ],
+];
+
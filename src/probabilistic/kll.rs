@@ -0,0 +1,238 @@
+use rand::Rng;
+use super::{read_u32, read_u64};
+
+const KLL_MAGIC:&[u8; 3] = b"KLL";
+const KLL_VERSION:u8 = 1;
+const KLL_C:f64 = 2.0/3.0;
+
+// KLL sketch (Karnin, Lang & Liberty): a stack of compactors where an item at
+// level h stands for 2^h input values. Compactors shrink geometrically by 2/3
+// going down, so the top levels hold most of the weight with the most items.
+#[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct KLL {
+    k: usize,
+    compactors: Vec<Vec<f64>>,
+    count: u64,
+}
+
+impl KLL {
+    pub fn new(k:usize) -> Self {
+        assert!(k >= 8, "k must be at least 8");
+
+        Self {
+            k,
+            compactors: vec![Vec::new()],
+            count: 0,
+        }
+    }
+}
+
+impl KLL {
+    fn get_capacity(&self, level:usize) -> usize {
+        let depth = self.compactors.len()-level-1;
+        ((self.k as f64*KLL_C.powi(depth as i32)).ceil() as usize).max(2)
+    }
+}
+
+impl KLL {
+    fn get_size(&self) -> usize {
+        self.compactors.iter().map(|c| c.len()).sum()
+    }
+}
+
+impl KLL {
+    fn get_max_size(&self) -> usize {
+        (0..self.compactors.len()).map(|h| self.get_capacity(h)).sum()
+    }
+}
+
+impl KLL {
+    pub fn add(&mut self, x:f64) {
+        if x.is_nan() {
+            return;
+        }
+
+        self.compactors[0].push(x);
+        self.count += 1;
+
+        if self.get_size() >= self.get_max_size() {
+            self.compress();
+        }
+    }
+}
+
+impl KLL {
+    fn compress(&mut self) {
+        while self.get_size() >= self.get_max_size() {
+            let Some(h) = (0..self.compactors.len()).find(|h| self.compactors[*h].len() >= self.get_capacity(*h)) else {
+                break;
+            };
+
+            if h+1 == self.compactors.len() {
+                self.compactors.push(Vec::new());
+            }
+
+            // Keep either the odd or the even positions of the sorted compactor
+            // and promote them, an odd item out stays behind at this level.
+            let mut items = std::mem::take(&mut self.compactors[h]);
+            items.sort_by(|a, b| a.total_cmp(b));
+
+            if items.len() % 2 == 1 {
+                let last = items.pop().unwrap();
+                self.compactors[h].push(last);
+            }
+
+            let offset = rand::thread_rng().gen_range(0..2);
+            let promoted:Vec<f64> = items.into_iter().skip(offset).step_by(2).collect();
+            self.compactors[h+1].extend(promoted);
+        }
+    }
+}
+
+impl KLL {
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl KLL {
+    fn get_weighted_items(&self) -> Vec<(f64, u64)> {
+        let mut items:Vec<(f64, u64)> = Vec::with_capacity(self.get_size());
+        for (h, c) in self.compactors.iter().enumerate() {
+            for x in c.iter() {
+                items.push((*x, 1 << h));
+            }
+        }
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+        items
+    }
+}
+
+impl KLL {
+    pub fn quantile(&self, q:f64) -> Option<f64> {
+        if self.count == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let items = self.get_weighted_items();
+        let total:u64 = items.iter().map(|x| x.1).sum();
+        let target = q*total as f64;
+        let mut cum:u64 = 0;
+
+        for (x, w) in items.iter() {
+            cum += w;
+            if cum as f64 >= target {
+                return Some(*x);
+            }
+        }
+
+        items.last().map(|x| x.0)
+    }
+}
+
+impl KLL {
+    // Estimated fraction of the values that are <= x.
+    pub fn rank(&self, x:f64) -> Option<f64> {
+        if self.count == 0 || x.is_nan() {
+            return None;
+        }
+
+        let mut below:u64 = 0;
+        let mut total:u64 = 0;
+
+        for (h, c) in self.compactors.iter().enumerate() {
+            for y in c.iter() {
+                total += 1 << h;
+                if *y <= x {
+                    below += 1 << h;
+                }
+            }
+        }
+
+        Some(below as f64/total as f64)
+    }
+}
+
+impl KLL {
+    pub fn merge(&mut self, other:&KLL) -> Result<(), String> {
+        if self.k != other.k {
+            return Err(format!("Cannot merge KLL with k = {} into KLL with k = {} !!!", other.k, self.k));
+        }
+
+        while self.compactors.len() < other.compactors.len() {
+            self.compactors.push(Vec::new());
+        }
+
+        for (h, c) in other.compactors.iter().enumerate() {
+            self.compactors[h].extend_from_slice(c);
+        }
+
+        self.count += other.count;
+        self.compress();
+        Ok(())
+    }
+}
+
+impl KLL {
+    // Layout: magic "KLL", version, k, count, number of levels, then for every
+    // level its length followed by the values, all little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out:Vec<u8> = Vec::with_capacity(KLL_MAGIC.len() + 17 + 4*self.compactors.len() + 8*self.get_size());
+        out.extend_from_slice(KLL_MAGIC);
+        out.push(KLL_VERSION);
+        out.extend_from_slice(&(self.k as u32).to_le_bytes());
+        out.extend_from_slice(&self.count.to_le_bytes());
+        out.extend_from_slice(&(self.compactors.len() as u32).to_le_bytes());
+
+        for c in self.compactors.iter() {
+            out.extend_from_slice(&(c.len() as u32).to_le_bytes());
+            for x in c.iter() {
+                out.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+
+        out
+    }
+}
+
+impl KLL {
+    pub fn from_bytes(bytes:&[u8]) -> Result<Self, String> {
+        if bytes.len() < KLL_MAGIC.len()+1 || &bytes[..KLL_MAGIC.len()] != KLL_MAGIC {
+            return Err("Not a valid KLL sketch !!!".to_string());
+        }
+
+        let version = bytes[KLL_MAGIC.len()];
+        if version != KLL_VERSION {
+            return Err(format!("Unsupported KLL version {} !!!", version));
+        }
+
+        let truncated = "Truncated KLL sketch !!!";
+        let mut pos = KLL_MAGIC.len()+1;
+        let k = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+        let count = read_u64(bytes, &mut pos).ok_or(truncated)?;
+        let levels = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+
+        if k < 8 || levels == 0 || levels > 64 {
+            return Err("Invalid KLL dimensions !!!".to_string());
+        }
+
+        let mut sketch = KLL::new(k);
+        sketch.count = count;
+        sketch.compactors = vec![Vec::new(); levels];
+
+        for h in 0..levels {
+            let n = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+            for _ in 0..n {
+                let x = f64::from_bits(read_u64(bytes, &mut pos).ok_or(truncated)?);
+                sketch.compactors[h].push(x);
+            }
+        }
+
+        if pos != bytes.len() {
+            return Err("Trailing bytes after KLL sketch !!!".to_string());
+        }
+
+        Ok(sketch)
+    }
+}
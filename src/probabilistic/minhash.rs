@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use super::{hash128, hash128_bytes};
+
+// One murmur3 seed per permutation, the signature keeps the minimum hash seen
+// for every seed.
+#[derive(Clone)]
+pub struct MinHash {
+    signature: Vec<u64>,
+}
+
+impl MinHash {
+    pub fn new(num_perm:usize) -> Self {
+        assert!(num_perm > 0, "number of permutations must be positive");
+
+        Self {
+            signature: vec![u64::MAX;num_perm],
+        }
+    }
+}
+
+impl MinHash {
+    pub fn add<T: Hash + ?Sized>(&mut self, item:&T) {
+        for (i, x) in self.signature.iter_mut().enumerate() {
+            *x = (*x).min(hash128(item, i as u32) as u64);
+        }
+    }
+}
+
+impl MinHash {
+    pub fn add_bytes(&mut self, item:&[u8]) {
+        for (i, x) in self.signature.iter_mut().enumerate() {
+            *x = (*x).min(hash128_bytes(item, i as u32) as u64);
+        }
+    }
+}
+
+impl MinHash {
+    pub fn from_items<'a, T: Hash + ?Sized + 'a, I: IntoIterator<Item = &'a T>>(num_perm:usize, items:I) -> Self {
+        let mut minhash = MinHash::new(num_perm);
+        for item in items {
+            minhash.add(item);
+        }
+        minhash
+    }
+}
+
+impl MinHash {
+    pub fn num_perm(&self) -> usize {
+        self.signature.len()
+    }
+}
+
+impl MinHash {
+    pub fn signature(&self) -> &[u64] {
+        &self.signature
+    }
+}
+
+impl MinHash {
+    pub fn is_empty(&self) -> bool {
+        self.signature.iter().all(|x| *x == u64::MAX)
+    }
+}
+
+impl MinHash {
+    // Fraction of permutations where both sets share the same minimum.
+    pub fn jaccard(&self, other:&MinHash) -> Result<f64, String> {
+        if self.num_perm() != other.num_perm() {
+            return Err(format!("Cannot compare MinHash with {} permutations against {} !!!", other.num_perm(), self.num_perm()));
+        }
+
+        let same = self.signature.iter().zip(other.signature.iter()).filter(|(x, y)| x == y).count();
+        Ok(same as f64/self.num_perm() as f64)
+    }
+}
+
+impl MinHash {
+    // Signature of the union of both sets.
+    pub fn merge(&mut self, other:&MinHash) -> Result<(), String> {
+        if self.num_perm() != other.num_perm() {
+            return Err(format!("Cannot merge MinHash with {} permutations into {} !!!", other.num_perm(), self.num_perm()));
+        }
+
+        for (x, y) in self.signature.iter_mut().zip(other.signature.iter()) {
+            *x = (*x).min(*y);
+        }
+
+        Ok(())
+    }
+}
+
+// Banded LSH: the signature is cut into `bands` bands of `rows` values, two
+// sets become candidates when they agree on every value of at least one band.
+pub struct LshIndex<K> {
+    bands: usize,
+    rows: usize,
+    tables: Vec<HashMap<u64, Vec<usize>>>,
+    keys: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    pub fn new(bands:usize, rows:usize) -> Self {
+        assert!(bands > 0 && rows > 0, "bands and rows must be positive");
+
+        Self {
+            bands,
+            rows,
+            tables: vec![HashMap::new(); bands],
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    // Picks bands*rows == num_perm so that the S-curve threshold (1/b)^(1/r)
+    // is as close as possible to the requested Jaccard similarity.
+    pub fn with_threshold(num_perm:usize, threshold:f64) -> Self {
+        assert!(threshold > 0.0 && threshold < 1.0, "threshold must be in (0, 1)");
+
+        let mut best:(usize, usize) = (num_perm, 1);
+        let mut best_diff = f64::MAX;
+
+        for rows in 1..=num_perm {
+            if !num_perm.is_multiple_of(rows) {
+                continue;
+            }
+
+            let bands = num_perm/rows;
+            let t = (1.0/bands as f64).powf(1.0/rows as f64);
+            if (t-threshold).abs() < best_diff {
+                best_diff = (t-threshold).abs();
+                best = (bands, rows);
+            }
+        }
+
+        LshIndex::new(best.0, best.1)
+    }
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    fn get_band_hash(&self, minhash:&MinHash, band:usize) -> u64 {
+        let mut bytes:Vec<u8> = Vec::with_capacity(8*self.rows);
+        for x in minhash.signature[band*self.rows..(band+1)*self.rows].iter() {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        hash128_bytes(&bytes, band as u32) as u64
+    }
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    pub fn insert(&mut self, key:K, minhash:&MinHash) -> Result<(), String> {
+        if minhash.num_perm() != self.bands*self.rows {
+            return Err(format!("Expected MinHash with {} permutations, found {} !!!", self.bands*self.rows, minhash.num_perm()));
+        }
+
+        let id = self.keys.len();
+        self.keys.push(key);
+
+        for band in 0..self.bands {
+            let h = self.get_band_hash(minhash, band);
+            self.tables[band].entry(h).or_default().push(id);
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    pub fn query(&self, minhash:&MinHash) -> Result<Vec<K>, String> {
+        if minhash.num_perm() != self.bands*self.rows {
+            return Err(format!("Expected MinHash with {} permutations, found {} !!!", self.bands*self.rows, minhash.num_perm()));
+        }
+
+        let mut ids:Vec<usize> = Vec::new();
+
+        for band in 0..self.bands {
+            if let Some(bucket) = self.tables[band].get(&self.get_band_hash(minhash, band)) {
+                ids.extend_from_slice(bucket);
+            }
+        }
+
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids.into_iter().map(|id| self.keys[id].clone()).collect())
+    }
+}
+
+impl<K: Clone + Eq + Hash> LshIndex<K> {
+    // Every pair of inserted keys sharing at least one band bucket, each pair
+    // reported once in insertion order.
+    pub fn candidate_pairs(&self) -> Vec<(K, K)> {
+        let mut pairs:HashSet<(usize, usize)> = HashSet::new();
+
+        for table in self.tables.iter() {
+            for bucket in table.values() {
+                for i in 0..bucket.len() {
+                    for j in i+1..bucket.len() {
+                        pairs.insert((bucket[i], bucket[j]));
+                    }
+                }
+            }
+        }
+
+        let mut pairs:Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs.into_iter().map(|(i, j)| (self.keys[i].clone(), self.keys[j].clone())).collect()
+    }
+}
+
+impl<K> LshIndex<K> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl<K> LshIndex<K> {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
@@ -0,0 +1,130 @@
+use fasthash::murmur3;
+use fasthash::{FastHasher, HasherExt};
+use std::hash::Hash;
+
+pub mod bloom;
+pub mod count_min;
+pub mod cuckoo;
+pub mod hyperloglog;
+pub mod kll;
+pub mod minhash;
+pub mod tdigest;
+pub mod top_k;
+
+pub const DEFAULT_SEED:u32 = 42;
+
+// All sketches hash through 128-bit murmur3 so that values hashed by one of
+// them can be compared against or combined with another.
+pub fn hash128<T: Hash + ?Sized>(data:&T, seed:u32) -> u128 {
+    let mut hasher = murmur3::Hasher128_x64::with_seed(seed);
+    data.hash(&mut hasher);
+    hasher.finish_ext()
+}
+
+pub fn hash128_bytes(data:&[u8], seed:u32) -> u128 {
+    murmur3::hash128_with_seed(data, seed)
+}
+
+pub(crate) fn write_varint(out:&mut Vec<u8>, mut v:u32) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+pub(crate) fn read_varint(bytes:&[u8], pos:&mut usize) -> Option<u32> {
+    let mut v:u32 = 0;
+    let mut shift:u32 = 0;
+
+    while shift < 32 {
+        let b = *bytes.get(*pos)?;
+        *pos += 1;
+        v |= ((b & 0x7f) as u32) << shift;
+        if b & 0x80 == 0 {
+            return Some(v);
+        }
+        shift += 7;
+    }
+
+    None
+}
+
+pub(crate) fn read_u32(bytes:&[u8], pos:&mut usize) -> Option<u32> {
+    let b = bytes.get(*pos..*pos+4)?;
+    *pos += 4;
+    Some(u32::from_le_bytes(b.try_into().unwrap()))
+}
+
+pub(crate) fn read_u64(bytes:&[u8], pos:&mut usize) -> Option<u64> {
+    let b = bytes.get(*pos..*pos+8)?;
+    *pos += 8;
+    Some(u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+// Keys that a sketch keeps verbatim (e.g. the monitored items of `TopK`) and
+// therefore has to be able to write out and read back.
+pub trait SketchKey: Hash + Eq + Clone {
+    fn to_key_bytes(&self) -> Vec<u8>;
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self>;
+}
+
+impl SketchKey for String {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl SketchKey for Vec<u8> {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+impl SketchKey for u32 {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+impl SketchKey for u64 {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+impl SketchKey for i32 {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        Some(i32::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+impl SketchKey for i64 {
+    fn to_key_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn from_key_bytes(bytes:&[u8]) -> Option<Self> {
+        Some(i64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
@@ -0,0 +1,291 @@
+use std::f64::consts::PI;
+use super::{read_u32, read_u64};
+
+const TDIGEST_MAGIC:&[u8; 3] = b"TDG";
+const TDIGEST_VERSION:u8 = 1;
+
+#[derive(Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+// Merging t-digest (Dunning & Ertl) with the k1 scale function, which keeps
+// the centroids near the tails small so extreme quantiles stay accurate.
+#[derive(Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<Centroid>,
+    total_weight: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression:f64) -> Self {
+        assert!(compression >= 10.0, "compression must be at least 10");
+
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            total_weight: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl TDigest {
+    fn get_k(&self, q:f64) -> f64 {
+        self.compression/(2.0*PI)*(2.0*q-1.0).asin()
+    }
+}
+
+impl TDigest {
+    fn get_q(&self, k:f64) -> f64 {
+        ((k*2.0*PI/self.compression).sin()+1.0)/2.0
+    }
+}
+
+impl TDigest {
+    pub fn add(&mut self, x:f64) {
+        self.add_weighted(x, 1.0);
+    }
+}
+
+impl TDigest {
+    fn add_weighted(&mut self, x:f64, weight:f64) {
+        if x.is_nan() {
+            return;
+        }
+
+        self.buffer.push(Centroid { mean: x, weight });
+        self.total_weight += weight;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+
+        if self.buffer.len() >= 5*self.compression as usize {
+            self.compress();
+        }
+    }
+}
+
+impl TDigest {
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut all:Vec<Centroid> = Vec::with_capacity(self.centroids.len()+self.buffer.len());
+        all.append(&mut self.centroids);
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = self.total_weight;
+        let mut merged:Vec<Centroid> = Vec::new();
+        let mut curr = all[0];
+        let mut so_far:f64 = 0.0;
+        let mut q_limit = self.get_q(self.get_k(0.0)+1.0);
+
+        for c in all.into_iter().skip(1) {
+            let q = (so_far+curr.weight+c.weight)/total;
+
+            if q <= q_limit {
+                curr.mean += (c.mean-curr.mean)*c.weight/(curr.weight+c.weight);
+                curr.weight += c.weight;
+            }
+            else {
+                so_far += curr.weight;
+                merged.push(curr);
+                q_limit = self.get_q(self.get_k(so_far/total)+1.0);
+                curr = c;
+            }
+        }
+
+        merged.push(curr);
+        self.centroids = merged;
+    }
+}
+
+impl TDigest {
+    fn get_merged(&self) -> Vec<Centroid> {
+        if self.buffer.is_empty() {
+            return self.centroids.clone();
+        }
+
+        let mut digest = self.clone();
+        digest.compress();
+        digest.centroids
+    }
+}
+
+impl TDigest {
+    pub fn count(&self) -> f64 {
+        self.total_weight
+    }
+}
+
+impl TDigest {
+    pub fn quantile(&self, q:f64) -> Option<f64> {
+        if self.total_weight == 0.0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let centroids = self.get_merged();
+        let n = centroids.len();
+        let target = q*self.total_weight;
+
+        if n == 1 || q == 0.0 {
+            return Some(if q == 1.0 { self.max } else { self.min });
+        }
+
+        // Each centroid's mean is placed at the middle of its weight.
+        let first_center = centroids[0].weight/2.0;
+        if target < first_center {
+            return Some(self.min + (centroids[0].mean-self.min)*target/first_center);
+        }
+
+        let mut cum:f64 = 0.0;
+        for i in 0..n-1 {
+            let left = cum + centroids[i].weight/2.0;
+            let right = cum + centroids[i].weight + centroids[i+1].weight/2.0;
+
+            if target <= right {
+                let t = (target-left)/(right-left);
+                return Some(centroids[i].mean + t*(centroids[i+1].mean-centroids[i].mean));
+            }
+
+            cum += centroids[i].weight;
+        }
+
+        let last_center = self.total_weight-centroids[n-1].weight/2.0;
+        let t = (target-last_center)/(self.total_weight-last_center);
+        Some(centroids[n-1].mean + t*(self.max-centroids[n-1].mean))
+    }
+}
+
+impl TDigest {
+    // Estimated fraction of the values that are <= x.
+    pub fn rank(&self, x:f64) -> Option<f64> {
+        if self.total_weight == 0.0 || x.is_nan() {
+            return None;
+        }
+        if x < self.min {
+            return Some(0.0);
+        }
+        if x >= self.max {
+            return Some(1.0);
+        }
+
+        let centroids = self.get_merged();
+        let n = centroids.len();
+
+        if n == 1 {
+            return Some((x-self.min)/(self.max-self.min));
+        }
+
+        if x < centroids[0].mean {
+            let t = (x-self.min)/(centroids[0].mean-self.min);
+            return Some(t*centroids[0].weight/2.0/self.total_weight);
+        }
+
+        let mut cum:f64 = 0.0;
+        for i in 0..n-1 {
+            if x < centroids[i+1].mean {
+                let left = cum + centroids[i].weight/2.0;
+                let right = cum + centroids[i].weight + centroids[i+1].weight/2.0;
+                let width = centroids[i+1].mean-centroids[i].mean;
+                let t = if width > 0.0 { (x-centroids[i].mean)/width } else { 1.0 };
+                return Some((left + t*(right-left))/self.total_weight);
+            }
+            cum += centroids[i].weight;
+        }
+
+        let last_center = self.total_weight-centroids[n-1].weight/2.0;
+        let t = (x-centroids[n-1].mean)/(self.max-centroids[n-1].mean);
+        Some((last_center + t*(self.total_weight-last_center))/self.total_weight)
+    }
+}
+
+impl TDigest {
+    pub fn merge(&mut self, other:&TDigest) {
+        for c in other.centroids.iter().chain(other.buffer.iter()) {
+            self.buffer.push(*c);
+            self.total_weight += c.weight;
+        }
+
+        if other.total_weight > 0.0 {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+
+        self.compress();
+    }
+}
+
+impl TDigest {
+    // Layout: magic "TDG", version, compression, min, max, number of centroids,
+    // then (mean, weight) for every centroid, all little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let centroids = self.get_merged();
+        let mut out:Vec<u8> = Vec::with_capacity(TDIGEST_MAGIC.len() + 29 + 16*centroids.len());
+        out.extend_from_slice(TDIGEST_MAGIC);
+        out.push(TDIGEST_VERSION);
+        out.extend_from_slice(&self.compression.to_le_bytes());
+        out.extend_from_slice(&self.min.to_le_bytes());
+        out.extend_from_slice(&self.max.to_le_bytes());
+        out.extend_from_slice(&(centroids.len() as u32).to_le_bytes());
+
+        for c in centroids.iter() {
+            out.extend_from_slice(&c.mean.to_le_bytes());
+            out.extend_from_slice(&c.weight.to_le_bytes());
+        }
+
+        out
+    }
+}
+
+impl TDigest {
+    pub fn from_bytes(bytes:&[u8]) -> Result<Self, String> {
+        if bytes.len() < TDIGEST_MAGIC.len()+1 || &bytes[..TDIGEST_MAGIC.len()] != TDIGEST_MAGIC {
+            return Err("Not a valid t-digest !!!".to_string());
+        }
+
+        let version = bytes[TDIGEST_MAGIC.len()];
+        if version != TDIGEST_VERSION {
+            return Err(format!("Unsupported t-digest version {} !!!", version));
+        }
+
+        let truncated = "Truncated t-digest !!!";
+        let mut pos = TDIGEST_MAGIC.len()+1;
+        let read_f64 = |pos:&mut usize| read_u64(bytes, pos).map(f64::from_bits);
+
+        let compression = read_f64(&mut pos).ok_or(truncated)?;
+        if compression.is_nan() || compression < 10.0 {
+            return Err("Invalid t-digest compression !!!".to_string());
+        }
+
+        let mut digest = TDigest::new(compression);
+        digest.min = read_f64(&mut pos).ok_or(truncated)?;
+        digest.max = read_f64(&mut pos).ok_or(truncated)?;
+        let n = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+
+        for _ in 0..n {
+            let mean = read_f64(&mut pos).ok_or(truncated)?;
+            let weight = read_f64(&mut pos).ok_or(truncated)?;
+            if mean.is_nan() || weight.is_nan() || weight <= 0.0 {
+                return Err("Invalid t-digest centroid !!!".to_string());
+            }
+            digest.centroids.push(Centroid { mean, weight });
+            digest.total_weight += weight;
+        }
+
+        if pos != bytes.len() {
+            return Err("Trailing bytes after t-digest !!!".to_string());
+        }
+
+        Ok(digest)
+    }
+}
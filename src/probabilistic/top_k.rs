@@ -0,0 +1,224 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use super::{read_u32, read_u64, SketchKey};
+
+const TOPK_MAGIC:&[u8; 4] = b"TOPK";
+const TOPK_VERSION:u8 = 1;
+
+struct Counter {
+    count: u64,
+    error: u64,
+    seq: u64,
+}
+
+// Space-Saving: keeps at most k monitored items, a new item replaces the one
+// with the smallest count and inherits that count as its error.
+pub struct TopK<T: SketchKey> {
+    k: usize,
+    counters: HashMap<T, Counter>,
+    order: BTreeMap<(u64, u64), T>,
+    next_seq: u64,
+    total: u64,
+}
+
+impl<T: SketchKey> TopK<T> {
+    pub fn new(k:usize) -> Self {
+        assert!(k > 0, "k must be positive");
+
+        Self {
+            k,
+            counters: HashMap::new(),
+            order: BTreeMap::new(),
+            next_seq: 0,
+            total: 0,
+        }
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    pub fn add(&mut self, item:T) {
+        self.add_count(item, 1);
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    pub fn add_count(&mut self, item:T, count:u64) {
+        self.total = self.total.saturating_add(count);
+
+        if let Some(c) = self.counters.get_mut(&item) {
+            let key = self.order.remove(&(c.count, c.seq)).unwrap();
+            c.count = c.count.saturating_add(count);
+            self.order.insert((c.count, c.seq), key);
+            return;
+        }
+
+        let mut error:u64 = 0;
+
+        if self.counters.len() == self.k {
+            let ((min_count, _), evicted) = self.order.pop_first().unwrap();
+            self.counters.remove(&evicted);
+            error = min_count;
+        }
+
+        self.insert_counter(item, error.saturating_add(count), error);
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    fn insert_counter(&mut self, item:T, count:u64, error:u64) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.insert((count, seq), item.clone());
+        self.counters.insert(item, Counter { count, error, seq });
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    // Returns (count, error), the true count lies within [count-error, count].
+    pub fn estimate(&self, item:&T) -> Option<(u64, u64)> {
+        self.counters.get(item).map(|c| (c.count, c.error))
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    // Monitored items with (count, error), most frequent first.
+    pub fn top(&self) -> Vec<(T, u64, u64)> {
+        self.order
+            .iter()
+            .rev()
+            .map(|(_, item)| {
+                let c = &self.counters[item];
+                (item.clone(), c.count, c.error)
+            })
+            .collect()
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    fn min_count(&self) -> u64 {
+        if self.counters.len() < self.k {
+            return 0;
+        }
+        self.order.keys().next().map(|x| x.0).unwrap_or(0)
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    // Mergeable summaries (Agarwal et al.): an item missing from a full summary
+    // may have been seen up to that summary's minimum count times.
+    pub fn merge(&mut self, other:&TopK<T>) -> Result<(), String> {
+        if self.k != other.k {
+            return Err(format!("Cannot merge Top-{} into Top-{} !!!", other.k, self.k));
+        }
+
+        let self_min = self.min_count();
+        let other_min = other.min_count();
+
+        let mut merged:HashMap<T, (u64, u64)> = HashMap::new();
+
+        for (item, c) in self.counters.iter() {
+            let (count, error) = match other.counters.get(item) {
+                Some(o) => (c.count.saturating_add(o.count), c.error.saturating_add(o.error)),
+                None => (c.count.saturating_add(other_min), c.error.saturating_add(other_min)),
+            };
+            merged.insert(item.clone(), (count, error));
+        }
+
+        for (item, o) in other.counters.iter() {
+            if !self.counters.contains_key(item) {
+                merged.insert(item.clone(), (o.count.saturating_add(self_min), o.error.saturating_add(self_min)));
+            }
+        }
+
+        let mut entries:Vec<(T, (u64, u64))> = merged.into_iter().collect();
+        entries.sort_by_key(|e| Reverse(e.1.0));
+        entries.truncate(self.k);
+
+        let total = self.total.saturating_add(other.total);
+        *self = TopK::new(self.k);
+        self.total = total;
+
+        // Insert in increasing order of count so that ties keep a stable order.
+        for (item, (count, error)) in entries.into_iter().rev() {
+            self.insert_counter(item, count, error);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    // Layout: magic "TOPK", version, k, number of items, total, then for every
+    // item its count, error, key length and key bytes, least frequent first.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out:Vec<u8> = Vec::new();
+        out.extend_from_slice(TOPK_MAGIC);
+        out.push(TOPK_VERSION);
+        out.extend_from_slice(&(self.k as u32).to_le_bytes());
+        out.extend_from_slice(&(self.counters.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.total.to_le_bytes());
+
+        for item in self.order.values() {
+            let c = &self.counters[item];
+            let key = item.to_key_bytes();
+            out.extend_from_slice(&c.count.to_le_bytes());
+            out.extend_from_slice(&c.error.to_le_bytes());
+            out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            out.extend_from_slice(&key);
+        }
+
+        out
+    }
+}
+
+impl<T: SketchKey> TopK<T> {
+    pub fn from_bytes(bytes:&[u8]) -> Result<Self, String> {
+        if bytes.len() < TOPK_MAGIC.len()+1 || &bytes[..TOPK_MAGIC.len()] != TOPK_MAGIC {
+            return Err("Not a valid Top-K summary !!!".to_string());
+        }
+
+        let version = bytes[TOPK_MAGIC.len()];
+        if version != TOPK_VERSION {
+            return Err(format!("Unsupported Top-K version {} !!!", version));
+        }
+
+        let truncated = "Truncated Top-K summary !!!";
+        let mut pos = TOPK_MAGIC.len()+1;
+        let k = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+        let n = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+        let total = read_u64(bytes, &mut pos).ok_or(truncated)?;
+
+        if k == 0 || n > k {
+            return Err("Invalid Top-K dimensions !!!".to_string());
+        }
+
+        let mut summary:TopK<T> = TopK::new(k);
+        summary.total = total;
+
+        for _ in 0..n {
+            let count = read_u64(bytes, &mut pos).ok_or(truncated)?;
+            let error = read_u64(bytes, &mut pos).ok_or(truncated)?;
+            let len = read_u32(bytes, &mut pos).ok_or(truncated)? as usize;
+            let key = bytes.get(pos..pos.saturating_add(len)).ok_or(truncated)?;
+            pos += len;
+
+            let item = T::from_key_bytes(key).ok_or("Invalid Top-K key !!!")?;
+            if summary.counters.contains_key(&item) {
+                return Err("Duplicate Top-K key !!!".to_string());
+            }
+            summary.insert_counter(item, count, error);
+        }
+
+        if pos != bytes.len() {
+            return Err("Trailing bytes after Top-K summary !!!".to_string());
+        }
+
+        Ok(summary)
+    }
+}